@@ -0,0 +1,199 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow, bail};
+use log::{info, warn};
+use serde_json::{Value, json};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::net::{HttpUrl, post_json, tls_connector};
+use crate::parser::{
+    EventData, KeywordFilter, SECURITY_SERVICES, Service, matches_keyword, service_rule_names,
+};
+use crate::regex::str_to_regex_names;
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+// Discord rejects messages over 2000 characters
+const MAX_TEXT_LEN: usize = 1900;
+
+// Where alerts are POSTed, e.g. https://hooks.slack.com/services/... or http://localhost:9000/hook
+#[derive(Debug, Clone)]
+pub struct Webhook(HttpUrl);
+
+impl Webhook {
+    pub fn parse(url: &str) -> Result<Self> {
+        HttpUrl::parse(url).map(Self)
+    }
+
+    fn payload(&self, text: String, rule: &AlertRule, ev: &EventData) -> Value {
+        // Slack and Discord only take their own message shape, anything else gets the event
+        if self.0.host == "hooks.slack.com" {
+            json!({ "text": text })
+        } else if self.0.host == "discord.com" || self.0.host == "discordapp.com" {
+            json!({ "content": text })
+        } else {
+            json!({ "text": text, "rule": rule.spec, "event": ev })
+        }
+    }
+}
+
+// SERVICE:EVENT_TYPE[:KEYWORD], e.g. sshd.events:Failure or kernel.events:KernelPanic.
+// EVENT_TYPE takes the names /drain's event_type does, KEYWORD /drain's query syntax
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    spec: String,
+    service: String,
+    rule_names: &'static [&'static str],
+    keyword: Option<KeywordFilter>,
+}
+
+impl AlertRule {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut parts = spec.splitn(3, ':');
+        let service = parts.next().unwrap_or_default();
+        let event_type = parts
+            .next()
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| anyhow!("expected SERVICE:EVENT_TYPE[:KEYWORD]"))?;
+        let keyword = KeywordFilter::from_query(parts.next(), None)?;
+
+        let service_rules = service_rule_names(service)?;
+        let rule_names = str_to_regex_names(event_type);
+        if rule_names.is_empty() {
+            bail!("unknown event_type {event_type}");
+        }
+        if !rule_names.iter().any(|name| service_rules.contains(name)) {
+            bail!("{service} has no {event_type} events");
+        }
+
+        Ok(Self {
+            spec: spec.to_string(),
+            service: service.to_string(),
+            rule_names,
+            keyword,
+        })
+    }
+
+    // Whether events of `service_name` can match, security.events stands for its members
+    pub fn watches(&self, service_name: &str) -> bool {
+        if self.service == "security.events" {
+            SECURITY_SERVICES.contains(&service_name)
+        } else {
+            self.service == service_name
+        }
+    }
+
+    fn matches(&self, ev: &EventData) -> bool {
+        self.watches(&service_name(&ev.service))
+            && ev
+                .matched_rule
+                .as_deref()
+                .is_some_and(|rule| self.rule_names.contains(&rule))
+            && matches_keyword(ev, &self.keyword)
+    }
+}
+
+// Service variants are the service names without the .events suffix
+fn service_name(service: &Service) -> String {
+    format!("{}.events", format!("{service:?}").to_lowercase())
+}
+
+// After an alert, a rule stays quiet for the cooldown; what it would have sent meanwhile is
+// counted and mentioned in its next alert
+struct RuleState {
+    rule: AlertRule,
+    last_sent: Option<Instant>,
+    suppressed: u64,
+}
+
+// POSTs an alert to the webhook for every event on the broadcast channel that matches a
+// rule, at most once per rule per cooldown. Each alert is sent from its own task, so a
+// slow webhook can't hold up reading events. A failed alert is logged and dropped
+pub fn spawn_alerter(
+    webhook: Webhook,
+    rules: Vec<AlertRule>,
+    cooldown: Duration,
+    mut rx: broadcast::Receiver<EventData>,
+) -> Result<()> {
+    let connector = if webhook.0.tls {
+        Some(tls_connector()?)
+    } else {
+        None
+    };
+    info!(
+        "Sending alerts for {} rule(s) to {}",
+        rules.len(),
+        webhook.0
+    );
+
+    let mut states: Vec<RuleState> = rules
+        .into_iter()
+        .map(|rule| RuleState {
+            rule,
+            last_sent: None,
+            suppressed: 0,
+        })
+        .collect();
+
+    tokio::spawn(async move {
+        loop {
+            let ev = match rx.recv().await {
+                Ok(ev) => ev,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Alerter fell behind, {skipped} events were not checked");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            for state in states.iter_mut().filter(|s| s.rule.matches(&ev)) {
+                if state.last_sent.is_some_and(|at| at.elapsed() < cooldown) {
+                    state.suppressed += 1;
+                    continue;
+                }
+                let text = alert_text(&state.rule, &ev, state.suppressed);
+                let body = webhook.payload(text, &state.rule, &ev).to_string();
+                state.last_sent = Some(Instant::now());
+                state.suppressed = 0;
+
+                let url = webhook.0.clone();
+                let connector = connector.clone();
+                let spec = state.rule.spec.clone();
+                tokio::spawn(async move {
+                    let sent = tokio::time::timeout(
+                        SEND_TIMEOUT,
+                        post_json(&url, connector.as_ref(), &body),
+                    )
+                    .await;
+                    let err = match sent {
+                        Ok(Ok(())) => return,
+                        Ok(Err(e)) => e.to_string(),
+                        Err(_) => "timed out".to_string(),
+                    };
+                    warn!("Failed to send alert for {spec}: {err}");
+                });
+            }
+        }
+    });
+    Ok(())
+}
+
+fn alert_text(rule: &AlertRule, ev: &EventData, suppressed: u64) -> String {
+    let (category, variant) = ev.event_type.kind();
+    let mut fields: Vec<_> = ev.data.iter().collect();
+    fields.sort();
+    let mut text = format!("Drashta alert {}: {category}:{variant}", rule.spec);
+    for (key, value) in fields {
+        text.push_str(&format!(" {key}={value}"));
+    }
+    if suppressed > 0 {
+        text.push_str(&format!(" ({suppressed} more since the last alert)"));
+    }
+    if text.len() > MAX_TEXT_LEN {
+        let mut end = MAX_TEXT_LEN;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+    text
+}
@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+
+use http::HeaderValue;
+use once_cell::sync::OnceCell;
+
+use crate::logging::LogFormat;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub port: u16,
+    pub persist: Option<PathBuf>,
+    pub metrics: bool,
+    pub resolve_ptr: bool,
+    pub buffer_size: usize,
+    pub poll_interval_ms: u64,
+    pub pacman_log: PathBuf,
+    pub access_log: PathBuf,
+    pub auth_log: PathBuf,
+    pub otlp_endpoint: Option<String>,
+    pub syslog_forward: Option<String>,
+    pub alert_webhook: Option<String>,
+    pub alert_rules: Vec<String>,
+    pub alert_cooldown_secs: u64,
+    pub replay_dir: Option<PathBuf>,
+    pub static_dir: Option<PathBuf>,
+    pub journal_dir: Option<PathBuf>,
+    pub sse_retry_ms: u64,
+    pub keepalive_secs: u64,
+    pub tls_cert: Option<PathBuf>,
+    pub tls_key: Option<PathBuf>,
+    pub log_format: LogFormat,
+    pub verbosity: u8,
+    pub cors_origins: Vec<HeaderValue>,
+    pub max_limit: i32,
+    pub max_connections: usize,
+    pub enabled_services: Vec<String>,
+    pub disabled_services: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            port: 3200,
+            persist: None,
+            metrics: false,
+            resolve_ptr: false,
+            buffer_size: 5_000,
+            poll_interval_ms: 500,
+            pacman_log: PathBuf::from("/var/log/pacman.log"),
+            access_log: PathBuf::from("/var/log/nginx/access.log"),
+            auth_log: PathBuf::from("/var/log/auth.log"),
+            otlp_endpoint: None,
+            syslog_forward: None,
+            alert_webhook: None,
+            alert_rules: Vec::new(),
+            alert_cooldown_secs: 60,
+            replay_dir: None,
+            static_dir: None,
+            journal_dir: None,
+            sse_retry_ms: 3_000,
+            keepalive_secs: 15,
+            tls_cert: None,
+            tls_key: None,
+            log_format: LogFormat::Pretty,
+            verbosity: 0,
+            cors_origins: Vec::new(),
+            max_limit: 100_000,
+            max_connections: 256,
+            enabled_services: Vec::new(),
+            disabled_services: Vec::new(),
+        }
+    }
+}
+
+// Where installed frontends are looked for, relative to the binary first so a
+// /usr/local or /opt prefix works as well as a distro package
+const INSTALLED_STATIC_DIRS: [&str; 2] = ["../share/drashta/static", "/usr/share/drashta/static"];
+
+impl Config {
+    // --static-dir (or DRASHTA_STATIC_DIR) when given, otherwise the first installed
+    // frontend found, falling back to the source checkout for dev builds
+    pub fn static_dir(&self) -> PathBuf {
+        if let Some(dir) = &self.static_dir {
+            return dir.clone();
+        }
+        let exe_dir = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(PathBuf::from));
+        INSTALLED_STATIC_DIRS
+            .iter()
+            .filter_map(|dir| match &exe_dir {
+                Some(exe_dir) => Some(exe_dir.join(dir)),
+                None if dir.starts_with('/') => Some(PathBuf::from(dir)),
+                None => None,
+            })
+            .find(|dir| dir.is_dir())
+            .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("static"))
+    }
+
+    // With no --enable-service every service is on; --disable-service wins over both
+    pub fn service_enabled(&self, name: &str) -> bool {
+        (self.enabled_services.is_empty() || self.enabled_services.iter().any(|s| s == name))
+            && !self.disabled_services.iter().any(|s| s == name)
+    }
+}
+
+pub static CONFIG: OnceCell<Config> = OnceCell::new();
+
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use http::StatusCode;
+use thiserror::Error;
+
+use crate::config::config;
+use crate::parser::{enabled_service_configs, get_service_configs};
+
+// Failures callers need to tell apart. Everything else stays an anyhow::Error and is
+// reported as a bad request
+#[derive(Debug, Error)]
+pub enum DrashtaError {
+    #[error("Unknown Service: {name}")]
+    UnknownService {
+        name: String,
+        valid_services: Vec<&'static str>,
+    },
+    #[error("Service {0} is disabled on this server")]
+    ServiceDisabled(String),
+    #[error("Couldn't open journal after {attempts} attempts: {source}")]
+    JournalOpen {
+        attempts: u32,
+        source: std::io::Error,
+    },
+    #[error("Invalid cursor: {0}")]
+    CursorParse(String),
+    #[error("Log file {} does not exist", .0.display())]
+    FileNotFound(PathBuf),
+    #[error("Bundle is larger than {0} bytes, narrow the time range or event_type")]
+    BundleTooLarge(usize),
+    #[error("Too many open streams, at most {0} are allowed (see --max-connections)")]
+    TooManyConnections(usize),
+}
+
+impl DrashtaError {
+    // For a lookup that found nothing among the enabled services
+    pub fn unknown_service(name: &str) -> Self {
+        if !config().service_enabled(name) && get_service_configs().contains_key(name) {
+            return Self::ServiceDisabled(name.to_string());
+        }
+        let mut valid_services: Vec<&'static str> = enabled_service_configs().into_keys().collect();
+        valid_services.sort();
+        Self::UnknownService {
+            name: name.to_string(),
+            valid_services,
+        }
+    }
+
+    // Sent as `code` next to the message in error responses and events
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnknownService { .. } => "unknown_service",
+            Self::ServiceDisabled(_) => "service_disabled",
+            Self::JournalOpen { .. } => "journal_open",
+            Self::CursorParse(_) => "cursor_parse",
+            Self::FileNotFound(_) => "file_not_found",
+            Self::BundleTooLarge(_) => "bundle_too_large",
+            Self::TooManyConnections(_) => "too_many_connections",
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::UnknownService { .. } | Self::FileNotFound(_) => StatusCode::NOT_FOUND,
+            Self::ServiceDisabled(_) => StatusCode::FORBIDDEN,
+            Self::JournalOpen { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            Self::CursorParse(_) => StatusCode::BAD_REQUEST,
+            Self::BundleTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::TooManyConnections(_) => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+}
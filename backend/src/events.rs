@@ -1,78 +1,932 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use axum::{
+    Json,
     extract::State,
-    response::sse::{Event, KeepAlive, Sse},
+    http::{HeaderValue, StatusCode, header},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
 };
 use axum_extra::extract::Query;
-use futures::StreamExt;
-use log::info;
+use chrono::SecondsFormat;
+use futures::{Stream, StreamExt};
+use log::{debug, error, info};
 use rayon::iter::IntoParallelRefIterator;
 use rayon::prelude::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, to_string};
-use std::{collections::VecDeque, convert::Infallible, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    convert::Infallible,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
 use tokio::sync::mpsc::{self};
-use tokio_stream::wrappers::BroadcastStream;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::MissedTickBehavior;
+use tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError};
 
+use crate::config::config;
+use crate::error::DrashtaError;
 use crate::parser::*;
+use crate::regex::str_to_regex_names;
+use crate::resolve::annotate_rhostname;
+use crate::shutdown;
+use crate::store::read_history;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct FilterEvent {
     event_name: Option<String>,
-    #[serde(default, deserialize_with = "deserialize_cursor")]
-    cursor: Option<CursorType>,
+    cursor: Option<String>,
     limit: Option<i32>,
     query: Option<String>,
     event_type: Option<Vec<String>>,
+    boot: Option<i32>,
+    query_mode: Option<String>,
+    field_filter: Option<String>,
+    match_fields: Option<Vec<String>>,
+    dedup: Option<bool>,
+    include_raw: Option<String>,
+    max_events_per_sec: Option<u32>,
+    source: Option<String>,
+    tail: Option<i32>,
+    timestamp_format: Option<String>,
+    fields: Option<Vec<String>>,
+    heartbeat_secs: Option<u64>,
+    usb_sessions: Option<bool>,
+    ssh_sessions: Option<bool>,
+    max_priority: Option<u8>,
+}
+
+const DEFAULT_DRAIN_LIMIT: i32 = 200;
+
+// limit for /drain, /older and /previous: DEFAULT_DRAIN_LIMIT when omitted, never more
+// than --max-limit
+fn drain_limit(limit: Option<i32>) -> i32 {
+    limit
+        .unwrap_or(DEFAULT_DRAIN_LIMIT)
+        .clamp(1, config().max_limit)
+}
+
+const DRAIN_CHANNEL_CAPACITY: usize = 102_400;
+
+// The drains finish reading before their stream starts, and parsers block on a full
+// channel, so it has to hold every event a drain may send
+fn drain_channel(limit: i32) -> (mpsc::Sender<EventData>, mpsc::Receiver<EventData>) {
+    mpsc::channel((limit as usize).max(DRAIN_CHANNEL_CAPACITY))
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct HistoryQuery {
+    event_name: Option<String>,
+    timestamp_from: Option<u64>,
+    timestamp_to: Option<u64>,
+    within: Option<String>,
+    limit: Option<usize>,
+    timestamp_format: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ContextQuery {
+    event_name: Option<String>,
+    cursor: Option<String>,
+    window: Option<usize>,
+    timestamp_format: Option<String>,
+}
+
+const MAX_CONTEXT_WINDOW: usize = 500;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReplayQuery {
+    file: Option<String>,
+    rate: Option<u32>,
+}
+
+const DEFAULT_REPLAY_RATE: u32 = 10;
+// Past about 1e9 the pacing interval rounds down to zero, which tokio rejects
+const MAX_REPLAY_RATE: u32 = 100_000;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SearchQuery {
+    event_name: Option<String>,
+    query: Option<String>,
+    query_mode: Option<String>,
+    event_type: Option<Vec<String>>,
+    limit: Option<i32>,
+    boot: Option<i32>,
+    source: Option<String>,
+    timestamp_format: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SearchHit {
+    score: usize,
+    event: serde_json::Value,
+}
+
+const MAX_SEARCH_LIMIT: i32 = 10_000;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct HistogramQuery {
+    event_name: Option<String>,
+    timestamp_from: Option<u64>,
+    timestamp_to: Option<u64>,
+    within: Option<String>,
+    bucket: Option<String>,
+    by_event_type: Option<bool>,
+    event_type: Option<Vec<String>>,
+    limit: Option<i32>,
+    boot: Option<i32>,
+    source: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct HistogramBucket {
+    bucket_start: i64,
+    count: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_types: Option<BTreeMap<String, u64>>,
+}
+
+impl HistogramBucket {
+    fn new(bucket_start: i64, by_event_type: bool) -> Self {
+        Self {
+            bucket_start,
+            count: 0,
+            event_types: by_event_type.then(BTreeMap::new),
+        }
+    }
+}
+
+const MAX_HISTOGRAM_SCAN: i32 = 100_000;
+const MAX_HISTOGRAM_BUCKETS: i64 = 10_000;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FacetsQuery {
+    event_name: Option<String>,
+    field: Option<String>,
+    max_values: Option<usize>,
+    event_type: Option<Vec<String>>,
+    limit: Option<i32>,
+    boot: Option<i32>,
+    source: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FacetValue {
+    value: String,
+    count: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Facets {
+    field: String,
+    scanned: u64,
+    values: Vec<FacetValue>,
+    truncated: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BundleQuery {
+    event_name: Option<String>,
+    timestamp_from: Option<u64>,
+    timestamp_to: Option<u64>,
+    within: Option<String>,
+    event_type: Option<Vec<String>>,
+    limit: Option<i32>,
+    boot: Option<i32>,
+    source: Option<String>,
+    timestamp_format: Option<String>,
+}
+
+const MAX_BUNDLE_SCAN: i32 = 100_000;
+const MAX_BUNDLE_BYTES: usize = 64 * 1024 * 1024;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RuleStatsQuery {
+    event_name: Option<String>,
+    event_type: Option<Vec<String>>,
+    limit: Option<i32>,
+    boot: Option<i32>,
+    source: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RuleHits {
+    rule: String,
+    hits: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RuleStats {
+    service: String,
+    scanned: u64,
+    unknown: u64,
+    unknown_rate: f64,
+    rules: Vec<RuleHits>,
+    dead_rules: Vec<String>,
+}
+
+const MAX_RULE_STATS_SCAN: i32 = 100_000;
+
+const MAX_FACET_SCAN: i32 = 100_000;
+const DEFAULT_FACET_VALUES: usize = 100;
+const MAX_FACET_VALUES: usize = 1_000;
+
+// Fields that differ between otherwise identical events in a burst (a new source port
+// per SSH attempt, a new pid per cron run), left out when comparing for dedup
+const DEDUP_IGNORED_FIELDS: [&str; 3] = ["port", "pid", "severity"];
+
+fn key_fields(ev: &EventData) -> impl Iterator<Item = (&String, &String)> {
+    ev.data
+        .iter()
+        .filter(|(k, _)| !DEDUP_IGNORED_FIELDS.contains(&k.as_str()))
+}
+
+fn is_repeat(prev: &EventData, ev: &EventData) -> bool {
+    prev.service == ev.service
+        && prev.event_type == ev.event_type
+        && key_fields(prev).count() == key_fields(ev).count()
+        && key_fields(prev).all(|(k, v)| ev.data.get(k) == Some(v))
+}
+
+struct DedupReceiver {
+    rx: mpsc::Receiver<EventData>,
+    enabled: bool,
+    pending: Option<(EventData, u64)>,
+}
+
+impl DedupReceiver {
+    fn new(rx: mpsc::Receiver<EventData>, enabled: bool) -> Self {
+        Self {
+            rx,
+            enabled,
+            pending: None,
+        }
+    }
+
+    fn finish((mut ev, count): (EventData, u64)) -> EventData {
+        if count > 1 {
+            ev.data.insert("count".to_string(), count.to_string());
+        }
+        ev
+    }
+
+    async fn recv(&mut self) -> Option<EventData> {
+        if !self.enabled {
+            return self.rx.recv().await;
+        }
+
+        loop {
+            let Some(ev) = self.rx.recv().await else {
+                return self.pending.take().map(Self::finish);
+            };
+
+            match self.pending.take() {
+                Some((prev, count)) if is_repeat(&prev, &ev) => {
+                    self.pending = Some((prev, count + 1));
+                }
+                Some(prev) => {
+                    self.pending = Some((ev, 1));
+                    return Some(Self::finish(prev));
+                }
+                None => self.pending = Some((ev, 1)),
+            }
+        }
+    }
+}
+
+// An sshd session that never logs its close (sshd killed, journal rotated) is
+// forgotten after this long instead of being held forever
+const SSH_SESSION_TIMEOUT_SECS: i64 = 24 * 60 * 60;
+const MAX_PENDING_SESSIONS: usize = 10_000;
+
+// Events that start and end something, paired into one `<kind>_session` event with how
+// long it lasted: kernel USB connect/disconnect by bus-port (usb_sessions=true) and
+// sshd session open/close by user and sshd pid (ssh_sessions=true)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum SessionKind {
+    Usb,
+    Ssh,
+}
+
+impl SessionKind {
+    // The key pairing an event with its counterpart, and whether it is the start
+    fn classify(self, ev: &EventData) -> Option<(String, bool)> {
+        match self {
+            SessionKind::Usb => {
+                if ev.event_type != EventType::Kernel(KernelEvent::UsbDeviceEvent) {
+                    return None;
+                }
+                let is_start = ev.data.get("event").is_some_and(|e| e.starts_with("New"));
+                Some((ev.data.get("device")?.clone(), is_start))
+            }
+            SessionKind::Ssh => {
+                let is_start = match ev.event_type {
+                    EventType::Auth(AuthEvent::SessionOpened) => true,
+                    EventType::Auth(AuthEvent::SessionClosed) => false,
+                    _ => return None,
+                };
+                if ev.service != Service::Sshd {
+                    return None;
+                }
+                let user = ev.data.get("user")?;
+                let pid = match &ev.raw_msg {
+                    RawMsgType::Structured(entry) => entry.get("_PID").map(String::as_str),
+                    RawMsgType::Plain(_) => None,
+                };
+                Some((format!("{user}:{}", pid.unwrap_or_default()), is_start))
+            }
+        }
+    }
+
+    fn timeout_secs(self) -> Option<i64> {
+        match self {
+            SessionKind::Usb => None,
+            SessionKind::Ssh => Some(SSH_SESSION_TIMEOUT_SECS),
+        }
+    }
+
+    fn event_name(self) -> &'static str {
+        match self {
+            SessionKind::Usb => "usb_session",
+            SessionKind::Ssh => "ssh_session",
+        }
+    }
+
+    fn summary(self, start: &EventData, end: &EventData) -> serde_json::Value {
+        match self {
+            SessionKind::Usb => json!({
+                "device": start.data.get("device"),
+                "vendor_id": start.data.get("vendor_id"),
+                "product_id": start.data.get("product_id"),
+                "details": start.data.get("details"),
+                "connected_at": start.timestamp,
+                "disconnected_at": end.timestamp,
+            }),
+            SessionKind::Ssh => {
+                let pid = match &start.raw_msg {
+                    RawMsgType::Structured(entry) => entry.get("_PID"),
+                    RawMsgType::Plain(_) => None,
+                };
+                json!({
+                    "user": start.data.get("user"),
+                    "pid": pid,
+                    "opened_at": start.timestamp,
+                    "closed_at": end.timestamp,
+                })
+            }
+        }
+    }
+}
+
+// Drains read in either direction, so whichever half of a session comes first waits
+// for the other
+struct SessionTracker {
+    kinds: Vec<SessionKind>,
+    pending: HashMap<(SessionKind, String), EventData>,
+}
+
+impl SessionTracker {
+    fn new(usb_sessions: Option<bool>, ssh_sessions: Option<bool>) -> Self {
+        let kinds = [
+            (SessionKind::Usb, usb_sessions),
+            (SessionKind::Ssh, ssh_sessions),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| enabled.unwrap_or(false))
+        .map(|(kind, _)| kind)
+        .collect();
+        Self {
+            kinds,
+            pending: HashMap::new(),
+        }
+    }
+
+    fn expire(&mut self, kind: SessionKind, now: &EventData) {
+        let (Some(timeout), Some(now)) = (kind.timeout_secs(), event_datetime(now)) else {
+            return;
+        };
+        self.pending.retain(|(k, _), ev| {
+            *k != kind
+                || event_datetime(ev).is_none_or(|t| (now - t).num_seconds().abs() <= timeout)
+        });
+    }
+
+    fn observe(&mut self, ev: &EventData) -> Option<Event> {
+        let (kind, session) = self.pair(ev)?;
+        Some(
+            Event::default()
+                .event(kind.event_name())
+                .data(session.to_string()),
+        )
+    }
+
+    fn pair(&mut self, ev: &EventData) -> Option<(SessionKind, serde_json::Value)> {
+        let (kind, key, is_start) = self
+            .kinds
+            .iter()
+            .find_map(|&kind| kind.classify(ev).map(|(key, start)| (kind, key, start)))?;
+        self.expire(kind, ev);
+
+        let prev = match self.pending.remove(&(kind, key.clone())) {
+            Some(prev) if kind.classify(&prev).is_some_and(|(_, s)| s != is_start) => prev,
+            _ => {
+                if self.pending.len() < MAX_PENDING_SESSIONS {
+                    self.pending.insert((kind, key), ev.clone());
+                }
+                return None;
+            }
+        };
+        let (start, end) = if is_start { (ev, &prev) } else { (&prev, ev) };
+
+        let mut session = kind.summary(start, end);
+        session["duration_secs"] = json!(
+            event_datetime(start)
+                .zip(event_datetime(end))
+                .map(|(from, to)| (to - from).num_seconds().abs())
+        );
+        Some((kind, session))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum IncludeRaw {
+    Full,
+    Minimal,
+    None,
+}
+
+impl IncludeRaw {
+    fn from_query(mode: Option<&str>) -> Result<Self> {
+        match mode.unwrap_or("full") {
+            "full" => Ok(IncludeRaw::Full),
+            "minimal" => Ok(IncludeRaw::Minimal),
+            "none" => Ok(IncludeRaw::None),
+            other => Err(anyhow!(
+                "Unknown include_raw `{other}`, expected one of: full, minimal, none"
+            )),
+        }
+    }
+}
+
+const MINIMAL_RAW_FIELDS: [&str; 5] = ["MESSAGE", "PRIORITY", "SYSLOG_IDENTIFIER", "_PID", "_COMM"];
+
+#[derive(Clone, Copy, Debug)]
+enum TimestampFormat {
+    Syslog,
+    Iso,
+    Epoch,
+}
+
+impl TimestampFormat {
+    fn from_query(format: Option<&str>) -> Result<Self> {
+        match format.unwrap_or("syslog") {
+            "syslog" => Ok(TimestampFormat::Syslog),
+            "iso" => Ok(TimestampFormat::Iso),
+            "epoch" => Ok(TimestampFormat::Epoch),
+            other => Err(anyhow!(
+                "Unknown timestamp_format `{other}`, expected one of: syslog, iso, epoch"
+            )),
+        }
+    }
+
+    // Parsers all emit their own syslog-style strings, so the other formats are derived
+    // from the event's parsed time on the way out. An unparseable time is left as is
+    fn apply(self, ev: &EventData, value: &mut serde_json::Value) {
+        if let TimestampFormat::Syslog = self {
+            return;
+        }
+        let Some(dt) = event_datetime(ev) else {
+            return;
+        };
+        value["timestamp"] = match self {
+            TimestampFormat::Iso => json!(dt.to_rfc3339_opts(SecondsFormat::AutoSi, false)),
+            _ => json!(dt.timestamp()),
+        };
+    }
+}
+
+fn event_value(ev: &EventData, ts_format: TimestampFormat) -> serde_json::Value {
+    let mut value = serde_json::to_value(ev).unwrap_or_default();
+    ts_format.apply(ev, &mut value);
+    value
+}
+
+// How events are written to SSE streams
+#[derive(Clone, Debug)]
+struct EventFormat {
+    include_raw: IncludeRaw,
+    timestamp: TimestampFormat,
+    // Only these keys of `data` are sent, like journalctl --output-fields
+    fields: Option<Vec<String>>,
+}
+
+impl Default for EventFormat {
+    fn default() -> Self {
+        Self {
+            include_raw: IncludeRaw::Full,
+            timestamp: TimestampFormat::Syslog,
+            fields: None,
+        }
+    }
+}
+
+impl EventFormat {
+    fn from_query(query: &FilterEvent) -> Result<Self> {
+        // fields=user,ip and fields=user&fields=ip are the same
+        let fields: Option<Vec<String>> = query.fields.as_ref().map(|fields| {
+            fields
+                .iter()
+                .flat_map(|f| f.split(','))
+                .map(str::trim)
+                .filter(|f| !f.is_empty())
+                .map(String::from)
+                .collect()
+        });
+        Ok(Self {
+            include_raw: IncludeRaw::from_query(query.include_raw.as_deref())?,
+            timestamp: TimestampFormat::from_query(query.timestamp_format.as_deref())?,
+            fields: fields.filter(|f| !f.is_empty()),
+        })
+    }
+}
+
+fn event_json(ev: &EventData, format: &EventFormat) -> String {
+    let EventFormat {
+        include_raw,
+        timestamp: ts_format,
+        fields,
+    } = format;
+    let json = match (include_raw, ts_format, fields) {
+        (IncludeRaw::Full, TimestampFormat::Syslog, None) => to_string(ev),
+        _ => serde_json::to_value(ev).and_then(|mut value| {
+            ts_format.apply(ev, &mut value);
+            if let Some(fields) = fields
+                && let Some(data) = value.get_mut("data").and_then(|d| d.as_object_mut())
+            {
+                data.retain(|key, _| fields.contains(key));
+            }
+            match (include_raw, value.as_object_mut()) {
+                (IncludeRaw::None, Some(obj)) => {
+                    obj.remove("raw_msg");
+                }
+                (IncludeRaw::Minimal, Some(obj)) => {
+                    if let Some(entry) = obj
+                        .get_mut("raw_msg")
+                        .and_then(|raw| raw.get_mut("value"))
+                        .and_then(|entry| entry.as_object_mut())
+                    {
+                        entry.retain(|field, _| MINIMAL_RAW_FIELDS.contains(&field.as_str()));
+                    }
+                }
+                _ => {}
+            }
+            to_string(&value)
+        }),
+    };
+    json.unwrap_or_else(|_| "{}".to_string())
+}
+
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u32) -> Self {
+        Self {
+            rate: rate as f64,
+            tokens: rate as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn try_take(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+fn parse_rate_limit(max_events_per_sec: Option<u32>) -> Result<Option<u32>> {
+    match max_events_per_sec {
+        Some(0) => Err(anyhow!("max_events_per_sec must be greater than 0")),
+        rate => Ok(rate),
+    }
+}
+
+fn parse_heartbeat(heartbeat_secs: Option<u64>) -> Result<Option<Duration>> {
+    match heartbeat_secs {
+        Some(0) => Err(anyhow!("heartbeat_secs must be greater than 0")),
+        secs => Ok(secs.map(Duration::from_secs)),
+    }
+}
+
+// Adds a `heartbeat` event every `every` with the server time and how many receivers the
+// live feed has, so a client can tell a stalled stream from a quiet one
+fn with_heartbeat<S>(
+    events: S,
+    every: Option<Duration>,
+    tx: tokio::sync::broadcast::Sender<EventData>,
+) -> impl Stream<Item = Result<Event, Infallible>>
+where
+    S: Stream<Item = Result<Event, Infallible>>,
+{
+    async_stream::stream! {
+        let mut events = std::pin::pin!(events);
+        let Some(every) = every else {
+            while let Some(ev) = events.next().await {
+                yield ev;
+            }
+            return;
+        };
+
+        let mut beat = tokio::time::interval(every);
+        beat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        beat.tick().await;
+
+        loop {
+            tokio::select! {
+                ev = events.next() => match ev {
+                    Some(ev) => yield ev,
+                    None => break,
+                },
+                _ = beat.tick() => {
+                    let heartbeat = json!({
+                        "ts": chrono::Utc::now().timestamp_millis(),
+                        "subscribers": tx.receiver_count(),
+                    });
+                    yield Ok(Event::default().event("heartbeat").data(heartbeat.to_string()));
+                }
+            }
+        }
+    }
+}
+
+// What /live sends after the tail: parsed events count against max_events_per_sec, while
+// notices like lag and usb_session always go through
+enum LiveEvent {
+    Data(Event),
+    Notice(Event),
+}
+
+impl LiveEvent {
+    fn into_event(self) -> Event {
+        match self {
+            LiveEvent::Data(ev) | LiveEvent::Notice(ev) => ev,
+        }
+    }
+}
+
+// Passes data events through at most `rate` per second, dropping the excess and reporting
+// how many were dropped once a second while the limit is being hit
+fn throttle<S>(events: S, rate: Option<u32>) -> impl Stream<Item = Result<Event, Infallible>>
+where
+    S: Stream<Item = LiveEvent>,
+{
+    async_stream::stream! {
+        let mut events = std::pin::pin!(events);
+        let Some(rate) = rate else {
+            while let Some(ev) = events.next().await {
+                yield Ok(ev.into_event());
+            }
+            return;
+        };
+
+        let mut bucket = TokenBucket::new(rate);
+        let mut suppressed: u64 = 0;
+        let mut notice = tokio::time::interval(Duration::from_secs(1));
+        notice.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        notice.tick().await;
+
+        loop {
+            let next = tokio::select! {
+                ev = events.next() => Some(ev),
+                _ = notice.tick() => None,
+            };
+
+            match next {
+                Some(Some(LiveEvent::Notice(ev))) => yield Ok(ev),
+                Some(Some(LiveEvent::Data(ev))) if bucket.try_take() => yield Ok(ev),
+                Some(Some(LiveEvent::Data(_))) => suppressed += 1,
+                Some(None) => break,
+                None if suppressed > 0 => {
+                    let notice = json!({
+                        "suppressed": suppressed,
+                        "message": format!("{suppressed} events suppressed"),
+                    });
+                    yield Ok(Event::default().event("suppressed").data(notice.to_string()));
+                    suppressed = 0;
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+// Sent before the new cursor when the requested one was rotated out of the journal or
+// no longer matches its log file, so clients can reset their pagination
+fn cursor_stale_event(cursor: Option<&str>, resync: &str) -> Event {
+    let notice = json!({
+        "cursor": cursor,
+        "resync": resync,
+        "message": format!("Cursor is no longer valid, reading from the {resync} instead"),
+    });
+    Event::default()
+        .event("cursor_stale")
+        .data(notice.to_string())
+}
+
+// Every SSE stream sends a `keepalive` comment after --keepalive-secs without events, so
+// proxies that drop idle connections leave it open
+fn keep_alive() -> KeepAlive {
+    KeepAlive::new()
+        .interval(Duration::from_secs(config().keepalive_secs))
+        .text("keepalive")
+}
+
+// Sent first on every SSE stream so browsers wait --sse-retry-ms before reconnecting
+fn retry_hint() -> futures::stream::Once<futures::future::Ready<Result<Event, Infallible>>> {
+    let retry = Duration::from_millis(config().sse_retry_ms);
+    futures::stream::once(futures::future::ready(Ok(Event::default().retry(retry))))
+}
+
+// Caps the SSE streams open at once at --max-connections, since every /live stream keeps
+// a reader thread busy. Each stream holds a permit until the client goes away
+#[derive(Clone)]
+pub struct StreamLimit(Arc<Semaphore>);
+
+impl StreamLimit {
+    pub fn new(max_connections: usize) -> Self {
+        Self(Arc::new(Semaphore::new(max_connections)))
+    }
+
+    fn acquire(&self) -> Result<OwnedSemaphorePermit, (StatusCode, Json<serde_json::Value>)> {
+        self.0.clone().try_acquire_owned().map_err(|_| {
+            error_response(DrashtaError::TooManyConnections(config().max_connections).into())
+        })
+    }
+}
+
+// Keeps the permit alive for as long as the stream, it is released when axum drops it
+fn hold_permit<S: Stream>(stream: S, permit: OwnedSemaphorePermit) -> impl Stream<Item = S::Item> {
+    stream.map(move |item| {
+        let _permit = &permit;
+        item
+    })
+}
+
+// Set once the stream holding it is dropped, which tells its reader thread to stop
+struct StopOnDrop(Arc<AtomicBool>);
+
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+fn stop_on_drop<S: Stream>(stream: S, stop: Arc<AtomicBool>) -> impl Stream<Item = S::Item> {
+    let guard = StopOnDrop(stop);
+    stream.map(move |item| {
+        let _guard = &guard;
+        item
+    })
+}
+
+#[derive(Clone)]
+pub struct LiveState {
+    pub tx: tokio::sync::broadcast::Sender<EventData>,
+    pub streams: StreamLimit,
+}
+
+fn error_json(e: &anyhow::Error) -> serde_json::Value {
+    match e.downcast_ref::<DrashtaError>() {
+        Some(err @ DrashtaError::UnknownService { valid_services, .. }) => json!({
+            "error": err.to_string(),
+            "code": err.code(),
+            "valid_services": valid_services,
+        }),
+        Some(err) => json!({ "error": err.to_string(), "code": err.code() }),
+        None => json!({ "error": e.to_string() }),
+    }
+}
+
+fn error_response(e: anyhow::Error) -> (StatusCode, Json<serde_json::Value>) {
+    let status = e
+        .downcast_ref::<DrashtaError>()
+        .map_or(StatusCode::BAD_REQUEST, DrashtaError::status);
+    (status, Json(error_json(&e)))
+}
+
+fn error_event(e: &anyhow::Error) -> Event {
+    Event::default()
+        .event("error")
+        .data(error_json(e).to_string())
 }
 
 pub async fn drain_older_logs(
+    State(streams): State<StreamLimit>,
     filter_event: Query<FilterEvent>,
-) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
-    let (tx, mut rx) = mpsc::channel::<EventData>(102400);
-
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<serde_json::Value>)>
+{
+    let permit = streams.acquire()?;
+    let event_format = EventFormat::from_query(&filter_event.0);
     let journal_units = filter_event.0.event_name.unwrap_or_default();
 
-    let limit = filter_event.0.limit.unwrap();
-    let filter_keyword = filter_event.0.query;
+    let limit = drain_limit(filter_event.0.limit);
+    let (tx, rx) = drain_channel(limit);
+    let mut rx = DedupReceiver::new(rx, filter_event.0.dedup.unwrap_or(false));
+    let mut sessions =
+        SessionTracker::new(filter_event.0.usb_sessions, filter_event.0.ssh_sessions);
+    let boot = filter_event.0.boot;
+    let filter_keyword = KeywordFilter::from_query(
+        filter_event.0.query.as_deref(),
+        filter_event.0.query_mode.as_deref(),
+    );
+    let field_filters = parse_field_filters(filter_event.0.field_filter.as_deref());
+    let match_fields = parse_match_fields(
+        filter_event.0.match_fields.as_deref(),
+        filter_event.0.max_priority,
+    );
+    let format = event_format.as_ref().cloned().unwrap_or_default();
+    let source = LogSource::from_query(filter_event.0.source.as_deref());
 
-    let cursor_type = filter_event.0.cursor.unwrap();
+    let cursor_type = parse_cursor(filter_event.0.cursor.as_deref());
+    let requested_cursor = filter_event.0.cursor.clone();
+    let resync = "head";
 
     let handle = tokio::task::spawn_blocking(move || {
         let tx = tx;
-        let mut new_cursor_type: Option<CursorType> = None;
         let ref_event_type = filter_event
             .0
             .event_type
             .as_ref()
             .map(|v| v.iter().map(|s| s.as_str()).collect::<Vec<_>>());
 
+        let cursor_type = cursor_type?;
         info!("Draining {journal_units} from {cursor_type:?} upto {limit} entries (next)",);
 
-        let opts = ParserFuncArgs::new(
+        event_format?;
+        let opts = ParserFuncArgs::try_new(
             &journal_units,
             tx.clone(),
             limit,
             ProcessLogType::ProcessOlderLogs,
-            filter_keyword.clone(),
+            filter_keyword?,
+            field_filters?,
+            match_fields?,
             ref_event_type.clone(),
             Some(cursor_type.clone()),
-        );
-        if let Ok(cursor_type) = handle_service_event(opts) {
-            new_cursor_type = cursor_type
-        }
-
-        new_cursor_type
+            boot,
+            source?,
+        )?;
+        let cursor_stale = opts.cursor_stale();
+        handle_service_event(opts).map(|cursor| (cursor, cursor_stale.get()))
     });
 
-    let new_cursor = handle.await.unwrap();
+    let result = handle
+        .await
+        .unwrap_or_else(|e| Err(anyhow!("Drain task failed: {e}")));
     let mut batch = VecDeque::with_capacity(100);
     let parallel_required_bro = limit >= 1000;
     let stream = async_stream::stream! {
+        let new_cursor = match result {
+            Ok((cursor, stale)) => {
+                if stale {
+                    yield Ok(cursor_stale_event(requested_cursor.as_deref(), resync));
+                }
+                cursor
+            }
+            Err(e) => {
+                yield Ok(error_event(&e));
+                return;
+            }
+        };
         let cursor_json = json!({ "cursor": new_cursor }).to_string();
         yield Ok(Event::default().event("cursor").data(cursor_json));
 
         while let Some(msg) = rx.recv().await {
+            let msg = annotate_rhostname(msg).await;
+            if let Some(session) = sessions.observe(&msg) {
+                yield Ok(session);
+            }
             batch.push_back(msg);
 
             if batch.len() >= 100 {
@@ -80,7 +934,7 @@ pub async fn drain_older_logs(
                     let logs: Vec<_> = batch
                         .par_iter()
                         .map(|x| {
-                            let json = serde_json::to_string(x).unwrap_or("{}".to_string());
+                            let json = event_json(x, &format);
                             Event::default().event("log").data(json)
                         })
                         .collect();
@@ -93,7 +947,7 @@ pub async fn drain_older_logs(
 
                 } else {
                     for x in batch.drain(..) {
-                        let json = serde_json::to_string(&x).unwrap_or("{}".to_string());
+                        let json = event_json(&x, &format);
                         yield Ok(Event::default().event("log").data(json));
                     }
                 }
@@ -101,18 +955,36 @@ pub async fn drain_older_logs(
         }
     };
 
-    Sse::new(stream).keep_alive(KeepAlive::default())
+    Ok(Sse::new(hold_permit(retry_hint().chain(stream), permit)).keep_alive(keep_alive()))
 }
 
 pub async fn drain_upto_n_entries(
+    State(streams): State<StreamLimit>,
     filter_event: Query<FilterEvent>,
-) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
-    let (tx, mut rx) = mpsc::channel::<EventData>(102400);
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<serde_json::Value>)>
+{
+    let permit = streams.acquire()?;
+    let event_format = EventFormat::from_query(&filter_event.0);
     let journal_units = filter_event.0.event_name.unwrap_or_default();
 
-    let limit = filter_event.0.limit.unwrap();
+    let limit = drain_limit(filter_event.0.limit);
+    let (tx, rx) = drain_channel(limit);
+    let mut rx = DedupReceiver::new(rx, filter_event.0.dedup.unwrap_or(false));
+    let mut sessions =
+        SessionTracker::new(filter_event.0.usb_sessions, filter_event.0.ssh_sessions);
+    let boot = filter_event.0.boot;
     let journal_units_clone = journal_units.clone();
-    let filter_keyword = filter_event.0.query;
+    let filter_keyword = KeywordFilter::from_query(
+        filter_event.0.query.as_deref(),
+        filter_event.0.query_mode.as_deref(),
+    );
+    let field_filters = parse_field_filters(filter_event.0.field_filter.as_deref());
+    let match_fields = parse_match_fields(
+        filter_event.0.match_fields.as_deref(),
+        filter_event.0.max_priority,
+    );
+    let format = event_format.as_ref().cloned().unwrap_or_default();
+    let source = LogSource::from_query(filter_event.0.source.as_deref());
     let handle = std::thread::spawn(move || {
         let ref_event_type: Option<Vec<&str>> = filter_event
             .0
@@ -120,43 +992,59 @@ pub async fn drain_upto_n_entries(
             .as_ref()
             .map(|s| s.iter().map(|s| s.as_str()).collect());
 
-        let mut last_cursor: Option<CursorType> = None;
-
         info!("Invoked initial drain for service: {journal_units}");
-        let opts = ParserFuncArgs::new(
+        event_format?;
+        let opts = ParserFuncArgs::try_new(
             &journal_units_clone,
             tx.clone(),
             limit,
             ProcessLogType::ProcessInitialLogs,
-            filter_keyword.clone(),
+            filter_keyword?,
+            field_filters?,
+            match_fields?,
             ref_event_type.clone(),
             None,
-        );
+            boot,
+            source?,
+        )?;
 
-        if let Ok(Some(cursor_type)) = handle_service_event(opts) {
-            last_cursor = Some(cursor_type);
-            info!("Cursor - {last_cursor:?}");
+        let result = handle_service_event(opts);
+        if let Ok(Some(cursor_type)) = &result {
+            debug!("Cursor - {cursor_type:?}");
         }
 
-        last_cursor
+        result
     });
 
-    let cursor = handle.join().unwrap();
+    let result = handle
+        .join()
+        .unwrap_or_else(|_| Err(anyhow!("Drain task panicked")));
     let mut batch = VecDeque::with_capacity(100);
     let parallel_required_bro = limit >= 1000;
 
     let stream = async_stream::stream! {
+        let cursor = match result {
+            Ok(cursor) => cursor,
+            Err(e) => {
+                yield Ok(error_event(&e));
+                return;
+            }
+        };
         if let Some(cursor) = cursor {
             let cursor_json = json!({ "cursor": cursor }).to_string();
             yield Ok(Event::default().event("cursor").data(cursor_json));
         }
 
         while let Some(msg) = rx.recv().await {
+            let msg = annotate_rhostname(msg).await;
+            if let Some(session) = sessions.observe(&msg) {
+                yield Ok(session);
+            }
             batch.push_back(msg);
 
             if parallel_required_bro{
                 let logs: Vec<_> = batch.par_iter().map(|x|{
-                    let json = serde_json::to_string(x).unwrap_or("{}".to_string());
+                    let json = event_json(x, &format);
                     Event::default().event("log").data(json)
                 }).collect();
 
@@ -167,7 +1055,7 @@ pub async fn drain_upto_n_entries(
 
             } else {
                 for x in batch.drain(..){
-                    let json = serde_json::to_string(&x).unwrap_or("{}".to_string());
+                    let json = event_json(&x, &format);
                     yield Ok(Event::default().event("log").data(json));
 
                 }
@@ -175,62 +1063,99 @@ pub async fn drain_upto_n_entries(
         }
     };
 
-    Sse::new(stream).keep_alive(KeepAlive::default())
+    Ok(Sse::new(hold_permit(retry_hint().chain(stream), permit)).keep_alive(keep_alive()))
 }
 
 pub async fn drain_previous_logs(
+    State(streams): State<StreamLimit>,
     filter_event: Query<FilterEvent>,
-) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
-    let (tx, mut rx) = mpsc::channel::<EventData>(102400);
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<serde_json::Value>)>
+{
+    let permit = streams.acquire()?;
+    let event_format = EventFormat::from_query(&filter_event.0);
     let journal_units = filter_event.0.event_name.unwrap_or_default();
 
-    let limit = filter_event.0.limit.unwrap();
+    let limit = drain_limit(filter_event.0.limit);
+    let (tx, rx) = drain_channel(limit);
+    let mut rx = DedupReceiver::new(rx, filter_event.0.dedup.unwrap_or(false));
+    let mut sessions =
+        SessionTracker::new(filter_event.0.usb_sessions, filter_event.0.ssh_sessions);
+    let boot = filter_event.0.boot;
 
-    let cursor_type = filter_event.0.cursor.unwrap();
-    let filter_keyword = filter_event.0.query;
+    let cursor_type = parse_cursor(filter_event.0.cursor.as_deref());
+    let requested_cursor = filter_event.0.cursor.clone();
+    let resync = "tail";
+    let filter_keyword = KeywordFilter::from_query(
+        filter_event.0.query.as_deref(),
+        filter_event.0.query_mode.as_deref(),
+    );
+    let field_filters = parse_field_filters(filter_event.0.field_filter.as_deref());
+    let match_fields = parse_match_fields(
+        filter_event.0.match_fields.as_deref(),
+        filter_event.0.max_priority,
+    );
+    let format = event_format.as_ref().cloned().unwrap_or_default();
+    let source = LogSource::from_query(filter_event.0.source.as_deref());
 
     let handle = tokio::task::spawn_blocking(move || {
-        let mut new_cursor_type = None;
-
         let ref_event_type: Option<Vec<&str>> = filter_event
             .0
             .event_type
             .as_ref()
             .map(|s| s.iter().map(|s| s.as_str()).collect());
 
+        let cursor_type = cursor_type?;
         info!("Draining {journal_units} from {cursor_type:?} upto {limit:?} entries (previous)",);
 
-        let opts = ParserFuncArgs::new(
+        event_format?;
+        let opts = ParserFuncArgs::try_new(
             &journal_units,
             tx.clone(),
             limit,
             ProcessLogType::ProcessPreviousLogs,
-            filter_keyword.clone(),
+            filter_keyword?,
+            field_filters?,
+            match_fields?,
             ref_event_type.clone(),
             Some(cursor_type.clone()),
-        );
-
-        let result = handle_service_event(opts);
+            boot,
+            source?,
+        )?;
 
-        if let Ok(cursor_type) = result {
-            new_cursor_type = cursor_type;
-        }
-
-        new_cursor_type
+        let cursor_stale = opts.cursor_stale();
+        handle_service_event(opts).map(|cursor| (cursor, cursor_stale.get()))
     });
 
-    let new_cursor = handle.await.unwrap();
+    let result = handle
+        .await
+        .unwrap_or_else(|e| Err(anyhow!("Drain task failed: {e}")));
     let mut batch = VecDeque::with_capacity(100);
     let parallel_required_bro = limit >= 1000;
 
     let stream = async_stream::stream! {
+        let new_cursor = match result {
+            Ok((cursor, stale)) => {
+                if stale {
+                    yield Ok(cursor_stale_event(requested_cursor.as_deref(), resync));
+                }
+                cursor
+            }
+            Err(e) => {
+                yield Ok(error_event(&e));
+                return;
+            }
+        };
         let cursor_json = json!({ "cursor": new_cursor }).to_string();
         yield Ok(Event::default().event("cursor").data(cursor_json));
         while let Some(msg) = rx.recv().await {
+            let msg = annotate_rhostname(msg).await;
+            if let Some(session) = sessions.observe(&msg) {
+                yield Ok(session);
+            }
             batch.push_back(msg);
             if parallel_required_bro{
                 let logs: Vec<_> = batch.par_iter().map(|x|{
-                    let json = serde_json::to_string(x).unwrap_or("{}".to_string());
+                    let json = event_json(x, &format);
                     Event::default().event("log").data(json)
                 }).collect();
 
@@ -241,7 +1166,7 @@ pub async fn drain_previous_logs(
 
             } else {
                 for event in batch.drain(..){
-                    let json = to_string(&event).unwrap_or_else(|_| "{}".to_string());
+                    let json = event_json(&event, &format);
                     yield Ok(Event::default().event("log").data(json));
                 }
             }
@@ -249,19 +1174,89 @@ pub async fn drain_previous_logs(
         }
     };
 
-    Sse::new(stream).keep_alive(KeepAlive::default())
+    Ok(Sse::new(hold_permit(retry_hint().chain(stream), permit)).keep_alive(keep_alive()))
 }
 
 pub async fn receive_data(
-    State(tx): State<tokio::sync::broadcast::Sender<EventData>>,
+    State(LiveState { tx, streams }): State<LiveState>,
     filter_event: Query<FilterEvent>,
-) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<serde_json::Value>)>
+{
+    let permit = streams.acquire()?;
     let rx = tx.clone().subscribe();
+    let heartbeat_tx = tx.clone();
+    let event_format = EventFormat::from_query(&filter_event.0);
     let journal_units = filter_event.0.event_name.unwrap_or_default();
 
-    let filter_keyword = filter_event.0.query;
+    let filter_keyword = KeywordFilter::from_query(
+        filter_event.0.query.as_deref(),
+        filter_event.0.query_mode.as_deref(),
+    );
+    let field_filters = parse_field_filters(filter_event.0.field_filter.as_deref());
+    let is_manual_event = MANUAL_PARSE_EVENTS.iter().any(|&x| x == journal_units);
+    let match_fields = match parse_match_fields(
+        filter_event.0.match_fields.as_deref(),
+        filter_event.0.max_priority,
+    ) {
+        Ok(matches) if is_manual_event && !matches.is_empty() => Err(anyhow!(
+            "Journal field matches (match_fields, max_priority) are not supported for {journal_units}"
+        )),
+        matches => matches,
+    };
+
+    let rate_limit = parse_rate_limit(filter_event.0.max_events_per_sec);
+    let heartbeat = parse_heartbeat(filter_event.0.heartbeat_secs);
+    let sessions = SessionTracker::new(filter_event.0.usb_sessions, filter_event.0.ssh_sessions);
+    let source = LogSource::from_query(filter_event.0.source.as_deref());
 
+    let parsed = (|| -> Result<_> {
+        Ok((
+            filter_keyword?,
+            field_filters?,
+            match_fields?,
+            event_format?,
+            (rate_limit?, heartbeat?),
+            source?,
+        ))
+    })();
+    let (
+        filter_keyword,
+        field_filters,
+        match_fields,
+        format,
+        (rate_limit, heartbeat),
+        source,
+        query_error,
+    ) = match parsed {
+        Ok((keyword, fields, matches, format, pacing, source)) => {
+            (keyword, fields, matches, format, pacing, source, None)
+        }
+        Err(e) => (
+            None,
+            Vec::new(),
+            Vec::new(),
+            EventFormat::default(),
+            (None, None),
+            LogSource::Auto,
+            Some(Ok(error_event(&e))),
+        ),
+    };
+    let query_ok = query_error.is_none();
+    let tail = filter_event
+        .0
+        .tail
+        .unwrap_or(0)
+        .clamp(0, config().max_limit) as usize;
+    let (tail_tx, mut tail_rx) = mpsc::channel::<Result<EventData>>(tail + 1);
+    let stop = Arc::new(AtomicBool::new(false));
+    let reader_stop = stop.clone();
+
+    // The reader holds the permit, so the slot is only freed once it has stopped
     std::thread::spawn(move || {
+        let _permit = permit;
+        if !query_ok {
+            return;
+        }
         let ref_event_type = filter_event
             .0
             .event_type
@@ -270,38 +1265,840 @@ pub async fn receive_data(
 
         info!("Trying to get Live Events from `{journal_units}`");
 
-        let is_manual_event = MANUAL_PARSE_EVENTS.iter().any(|&x| x == journal_units);
-        if is_manual_event {
+        let is_file_source = match reads_file_source(&journal_units, source, None, None) {
+            Ok(is_file_source) => is_file_source,
+            Err(e) => {
+                error!("{e}");
+                return;
+            }
+        };
+
+        // The tail is read up to where the live follow then picks up, so the two meet
+        // without a gap. tail_tx is dropped before following, which ends the tail part
+        // of the stream
+        let send_tail = |events: Result<Vec<EventData>>| match events {
+            Ok(events) => events.into_iter().for_each(|ev| {
+                let _ = tail_tx.blocking_send(Ok(ev));
+            }),
+            Err(e) => {
+                let _ = tail_tx.blocking_send(Err(e));
+            }
+        };
+
+        if is_file_source {
+            let mut from_offset = None;
+            if tail > 0 {
+                let last = read_last_events_manual(
+                    &journal_units,
+                    tail,
+                    &filter_keyword,
+                    &field_filters,
+                    ref_event_type.clone(),
+                );
+                send_tail(last.map(|(events, offset)| {
+                    from_offset = Some(offset);
+                    events
+                }));
+            }
+            drop(tail_tx);
+
             if let Err(e) = read_journal_logs_manual(
                 &journal_units,
                 filter_keyword.clone(),
+                field_filters.clone(),
                 ref_event_type.clone(),
+                from_offset,
                 tx.clone(),
+                &reader_stop,
+                false,
             ) {
-                eprintln!("Error: {e}");
+                error!("{e}");
+            }
+        } else {
+            let mut after_cursor = None;
+            if tail > 0 {
+                let last = read_last_events(
+                    &journal_units,
+                    tail,
+                    &filter_keyword,
+                    &field_filters,
+                    &match_fields,
+                    ref_event_type.clone(),
+                );
+                send_tail(last.map(|(events, cursor)| {
+                    after_cursor = cursor;
+                    events
+                }));
+            }
+            drop(tail_tx);
+
+            if let Err(e) = read_journal_logs(
+                &journal_units,
+                filter_keyword.clone(),
+                field_filters.clone(),
+                match_fields.clone(),
+                ref_event_type.clone(),
+                after_cursor,
+                tx.clone(),
+                &reader_stop,
+                false,
+            ) {
+                error!("{e}");
             }
-        } else if let Err(e) = read_journal_logs(
-            &journal_units,
-            filter_keyword.clone(),
-            ref_event_type.clone(),
-            tx.clone(),
-        ) {
-            eprintln!("Error: {e}");
         }
     });
 
-    let stream = BroadcastStream::new(rx).filter_map(|res| async move {
-        match res {
-            Ok(msg) => {
-                let json = to_string(&msg).unwrap_or_else(|_| "{}".to_string());
-                Some(Ok(Event::default().data(json)))
+    let format = Arc::new(format);
+    let tail_format = format.clone();
+    // Shared so a device that connected in the tail is paired with its live disconnect
+    let sessions = Arc::new(std::sync::Mutex::new(sessions));
+    let tail_sessions = sessions.clone();
+    let tail_events = async_stream::stream! {
+        while let Some(res) = tail_rx.recv().await {
+            match res {
+                Ok(msg) => {
+                    let msg = annotate_rhostname(msg).await;
+                    let session = tail_sessions.lock().unwrap().observe(&msg);
+                    if let Some(session) = session {
+                        yield Ok(session);
+                    }
+                    yield Ok(Event::default().data(event_json(&msg, &tail_format)));
+                }
+                Err(e) => yield Ok(error_event(&e)),
+            }
+        }
+    };
+
+    let live = BroadcastStream::new(rx)
+        .take_while(move |_| futures::future::ready(query_ok))
+        .then(move |res| {
+            let format = format.clone();
+            let sessions = sessions.clone();
+            async move {
+                match res {
+                    Ok(msg) => {
+                        let msg = annotate_rhostname(msg).await;
+                        let session = sessions.lock().unwrap().observe(&msg);
+                        let json = event_json(&msg, &format);
+                        session
+                            .map(LiveEvent::Notice)
+                            .into_iter()
+                            .chain([LiveEvent::Data(Event::default().data(json))])
+                            .collect()
+                    }
+                    Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                        vec![LiveEvent::Notice(
+                            Event::default()
+                                .event("lag")
+                                .data(json!({ "skipped": skipped }).to_string()),
+                        )]
+                    }
+                }
+            }
+        })
+        .flat_map(futures::stream::iter);
+    let stream = retry_hint()
+        .chain(futures::stream::iter(query_error))
+        .chain(with_heartbeat(
+            tail_events.chain(throttle(live, rate_limit)),
+            heartbeat,
+            heartbeat_tx,
+        ))
+        .take_until(shutdown::streams_closing());
+    Ok(Sse::new(stop_on_drop(stream, stop)).keep_alive(keep_alive()))
+}
+
+pub async fn get_history(
+    history_query: Query<HistoryQuery>,
+) -> Result<Json<Vec<serde_json::Value>>, (StatusCode, Json<serde_json::Value>)> {
+    let query = history_query.0;
+    let limit = query.limit.unwrap_or(1000).min(config().max_limit as usize);
+    let ts_format =
+        TimestampFormat::from_query(query.timestamp_format.as_deref()).map_err(error_response)?;
+    let from =
+        window_start(query.timestamp_from, query.within.as_deref()).map_err(error_response)?;
+
+    if let Some(name) = query.event_name.as_deref()
+        && !enabled_service_configs().contains_key(name)
+    {
+        return Err(error_response(DrashtaError::unknown_service(name).into()));
+    }
+
+    let result = tokio::task::spawn_blocking(move || {
+        read_history(query.event_name.as_deref(), from, query.timestamp_to, limit)
+    })
+    .await
+    .unwrap_or_else(|e| Err(anyhow!("History task failed: {e}")));
+
+    match result {
+        Ok(events) => Ok(Json(
+            events.iter().map(|ev| event_value(ev, ts_format)).collect(),
+        )),
+        Err(e) => Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": e.to_string() })),
+        )),
+    }
+}
+
+pub async fn get_context(
+    context_query: Query<ContextQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let query = context_query.0;
+    let window = query.window.unwrap_or(10).min(MAX_CONTEXT_WINDOW);
+    let ts_format =
+        TimestampFormat::from_query(query.timestamp_format.as_deref()).map_err(error_response)?;
+
+    let result = tokio::task::spawn_blocking(move || {
+        let service_name = query.event_name.unwrap_or_default();
+        let cursor = parse_cursor(query.cursor.as_deref())?;
+        read_event_context(&service_name, cursor, window)
+    })
+    .await
+    .unwrap_or_else(|e| Err(anyhow!("Context task failed: {e}")));
+
+    let values = |events: &[EventData]| -> Vec<_> {
+        events.iter().map(|ev| event_value(ev, ts_format)).collect()
+    };
+    result
+        .map(|context| {
+            Json(json!({
+                "before": values(&context.before),
+                "event": context.event.as_ref().map(|ev| event_value(ev, ts_format)),
+                "after": values(&context.after),
+            }))
+        })
+        .map_err(error_response)
+}
+
+// Only plain files inside --replay-dir can be replayed, anything resolving outside it
+// (`..`, absolute paths, symlinks) is rejected
+fn replay_file_path(file: &str) -> Result<PathBuf> {
+    let Some(dir) = &config().replay_dir else {
+        return Err(anyhow!(
+            "Replay is disabled, start drashta with --replay-dir <DIR>"
+        ));
+    };
+    let dir = dir
+        .canonicalize()
+        .map_err(|e| anyhow!("Replay directory {} is unavailable: {e}", dir.display()))?;
+    let path = dir
+        .join(file)
+        .canonicalize()
+        .map_err(|e| anyhow!("Cannot open replay file {file}: {e}"))?;
+
+    if !path.starts_with(&dir) || !path.is_file() {
+        return Err(anyhow!("{file} is not a file in the replay directory"));
+    }
+    Ok(path)
+}
+
+pub async fn replay_events(
+    State(streams): State<StreamLimit>,
+    replay_query: Query<ReplayQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<serde_json::Value>)>
+{
+    let permit = streams.acquire()?;
+    let query = replay_query.0;
+
+    let rate = match query.rate.unwrap_or(DEFAULT_REPLAY_RATE) {
+        0 => return Err(error_response(anyhow!("rate must be greater than 0"))),
+        rate if rate > MAX_REPLAY_RATE => {
+            return Err(error_response(anyhow!(
+                "rate must be at most {MAX_REPLAY_RATE} events per second"
+            )));
+        }
+        rate => rate,
+    };
+    let file = query
+        .file
+        .ok_or_else(|| anyhow!("A file is required"))
+        .map_err(error_response)?;
+    let path = replay_file_path(&file).map_err(error_response)?;
+    let reader = File::open(&path)
+        .map(BufReader::new)
+        .map_err(|e| error_response(anyhow!("Cannot open replay file {file}: {e}")))?;
+
+    info!("Replaying {} at {rate} events/sec", path.display());
+
+    let (tx, mut rx) = mpsc::channel::<Result<EventData>>(rate.min(1024) as usize);
+    std::thread::spawn(move || {
+        for (line_no, line) in reader.lines().enumerate() {
+            let ev = match line {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => serde_json::from_str::<EventData>(&line)
+                    .map_err(|e| anyhow!("Line {}: {e}", line_no + 1)),
+                Err(e) => Err(anyhow!("Line {}: {e}", line_no + 1)),
+            };
+            if tx.blocking_send(ev).is_err() {
+                break;
             }
-            Err(_) => None,
         }
     });
-    Sse::new(stream).keep_alive(
-        KeepAlive::new()
-            .interval(Duration::from_secs(15))
-            .text("keepalive"),
+
+    let stream = async_stream::stream! {
+        let mut pace = tokio::time::interval(Duration::from_secs_f64(1.0 / rate as f64));
+        pace.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        while let Some(ev) = rx.recv().await {
+            match ev {
+                Ok(ev) => {
+                    pace.tick().await;
+                    yield Ok(Event::default().data(event_json(&ev, &EventFormat::default())));
+                }
+                Err(e) => yield Ok(error_event(&e)),
+            }
+        }
+    }
+    .take_until(shutdown::streams_closing());
+
+    Ok(Sse::new(hold_permit(retry_hint().chain(stream), permit)).keep_alive(keep_alive()))
+}
+
+struct ScanRequest {
+    service_name: String,
+    limit: i32,
+    filter: Option<KeywordFilter>,
+    event_type: Option<Vec<String>>,
+    boot: Option<i32>,
+    source: Option<String>,
+    time_range: TimeRange,
+}
+
+// Runs the initial drain pipeline on its own thread and hands back the parsed events,
+// for endpoints that answer with an aggregate instead of a stream. The thread returns how
+// many entries it read, matched or not
+fn scan_events(
+    req: ScanRequest,
+) -> (
+    mpsc::Receiver<EventData>,
+    std::thread::JoinHandle<Result<usize>>,
+) {
+    let (tx, rx) =
+        mpsc::channel::<EventData>(req.limit.clamp(1, DRAIN_CHANNEL_CAPACITY as i32) as usize);
+    let handle = std::thread::spawn(move || {
+        let ev_type: Option<Vec<&str>> = req
+            .event_type
+            .as_ref()
+            .map(|s| s.iter().map(|s| s.as_str()).collect());
+
+        let opts = ParserFuncArgs::try_new(
+            &req.service_name,
+            tx,
+            req.limit,
+            ProcessLogType::ProcessInitialLogs,
+            req.filter,
+            Vec::new(),
+            Vec::new(),
+            ev_type,
+            None,
+            req.boot,
+            LogSource::from_query(req.source.as_deref())?,
+        )?
+        .with_time_range(req.time_range);
+        let entries_read = opts.entries_read();
+        handle_service_event(opts)?;
+        Ok(entries_read.get())
+    });
+    (rx, handle)
+}
+
+async fn finish_scan(handle: std::thread::JoinHandle<Result<usize>>) -> Result<usize> {
+    tokio::task::spawn_blocking(move || handle.join())
+        .await
+        .map_err(|e| anyhow!("Scan task failed: {e}"))
+        .and_then(|joined| joined.unwrap_or_else(|_| Err(anyhow!("Scan task panicked"))))
+}
+
+// Matches up to `limit` events like /drain, then ranks them by how often the query terms
+// occur, newest first among equal scores
+pub async fn search_events(
+    search_query: Query<SearchQuery>,
+) -> Result<Json<Vec<SearchHit>>, (StatusCode, Json<serde_json::Value>)> {
+    let query = search_query.0;
+
+    let ts_format =
+        TimestampFormat::from_query(query.timestamp_format.as_deref()).map_err(error_response)?;
+    let keyword = KeywordFilter::from_query(query.query.as_deref(), query.query_mode.as_deref())
+        .and_then(|k| k.ok_or_else(|| anyhow!("A query is required")))
+        .map_err(error_response)?;
+    let limit = query
+        .limit
+        .unwrap_or(1000)
+        .clamp(1, MAX_SEARCH_LIMIT.min(config().max_limit));
+
+    let (mut rx, handle) = scan_events(ScanRequest {
+        service_name: query.event_name.unwrap_or_default(),
+        limit,
+        filter: Some(keyword.clone()),
+        event_type: query.event_type,
+        boot: query.boot,
+        source: query.source,
+        time_range: TimeRange::default(),
+    });
+
+    // File sources are read oldest first, so arrival order stands in for their timestamp
+    let mut hits = Vec::new();
+    while let Some(ev) = rx.recv().await {
+        let score = keyword_score(&ev, &keyword);
+        let time = match &ev.raw_msg {
+            RawMsgType::Structured(entry) => entry_datetime(entry),
+            RawMsgType::Plain(_) => None,
+        };
+        let event = event_value(&ev, ts_format);
+        hits.push(((time, hits.len()), SearchHit { score, event }));
+    }
+
+    finish_scan(handle).await.map_err(error_response)?;
+
+    hits.sort_by(|(a_recency, a), (b_recency, b)| {
+        b.score.cmp(&a.score).then_with(|| b_recency.cmp(a_recency))
+    });
+
+    Ok(Json(hits.into_iter().map(|(_, hit)| hit).collect()))
+}
+
+// "30s", "5m", "1h" or "1d" in seconds, `param` names the query parameter in errors
+fn parse_duration(value: &str, param: &str) -> Result<i64> {
+    let invalid = || anyhow!("Invalid {param} `{value}`, expected e.g. 30s, 5m, 1h or 1d");
+    let unit_at = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (count, unit) = value.split_at(unit_at);
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        _ => return Err(invalid()),
+    };
+    match count.parse::<i64>() {
+        Ok(count) if count > 0 => count.checked_mul(unit_secs).ok_or_else(invalid),
+        _ => Err(invalid()),
+    }
+}
+
+// The lower time bound in Unix seconds: `within=15m` means the last 15 minutes, and when
+// timestamp_from is given too the later of the two wins
+fn window_start(from: Option<u64>, within: Option<&str>) -> Result<Option<u64>> {
+    let Some(within) = within else {
+        return Ok(from);
+    };
+    let secs = parse_duration(within, "within")?;
+    let start = chrono::Utc::now().timestamp().saturating_sub(secs).max(0) as u64;
+    Ok(Some(from.map_or(start, |from| from.max(start))))
+}
+
+// Tallies the first `limit` events (as /drain reads them) into fixed-size time buckets. Every bucket between
+// the first and last one is returned, empty ones with a count of 0, so the result can
+// be charted as is
+pub async fn get_histogram(
+    histogram_query: Query<HistogramQuery>,
+) -> Result<Json<Vec<HistogramBucket>>, (StatusCode, Json<serde_json::Value>)> {
+    let query = histogram_query.0;
+
+    let bucket_secs = parse_duration(query.bucket.as_deref().unwrap_or("1h"), "bucket")
+        .map_err(error_response)?;
+    let limit = query
+        .limit
+        .unwrap_or(10_000)
+        .clamp(1, MAX_HISTOGRAM_SCAN.min(config().max_limit));
+    let time_range = TimeRange {
+        from: window_start(query.timestamp_from, query.within.as_deref())
+            .map_err(error_response)?
+            .map(|t| t as i64),
+        to: query.timestamp_to.map(|t| t as i64),
+    };
+    let by_event_type = query.by_event_type.unwrap_or(false);
+
+    let (mut rx, handle) = scan_events(ScanRequest {
+        service_name: query.event_name.unwrap_or_default(),
+        limit,
+        filter: None,
+        event_type: query.event_type,
+        boot: query.boot,
+        source: query.source,
+        time_range,
+    });
+
+    let mut tally: BTreeMap<i64, HistogramBucket> = BTreeMap::new();
+    while let Some(ev) = rx.recv().await {
+        // The journal is bounded by receive time, an event's own time can differ slightly
+        let Some(secs) = event_datetime(&ev).map(|dt| dt.timestamp()) else {
+            continue;
+        };
+        if !time_range.contains(secs) {
+            continue;
+        }
+
+        let bucket_start = secs.div_euclid(bucket_secs) * bucket_secs;
+        let bucket = tally
+            .entry(bucket_start)
+            .or_insert_with(|| HistogramBucket::new(bucket_start, by_event_type));
+        bucket.count += 1;
+        if let Some(event_types) = &mut bucket.event_types {
+            let (category, variant) = ev.event_type.kind();
+            *event_types
+                .entry(format!("{category}:{variant}"))
+                .or_default() += 1;
+        }
+    }
+
+    finish_scan(handle).await.map_err(error_response)?;
+
+    let first = time_range
+        .from
+        .map(|t| t.div_euclid(bucket_secs) * bucket_secs);
+    let last = time_range
+        .to
+        .map(|t| t.div_euclid(bucket_secs) * bucket_secs);
+    let (Some(first), Some(last)) = (
+        first.or_else(|| tally.keys().next().copied()),
+        last.or_else(|| tally.keys().next_back().copied()),
+    ) else {
+        return Ok(Json(Vec::new()));
+    };
+    if (last - first) / bucket_secs >= MAX_HISTOGRAM_BUCKETS {
+        return Err(error_response(anyhow!(
+            "Too many buckets, use a larger bucket or a shorter time range (max {MAX_HISTOGRAM_BUCKETS})"
+        )));
+    }
+
+    let buckets = (first..=last)
+        .step_by(bucket_secs as usize)
+        .map(|start| {
+            tally
+                .remove(&start)
+                .unwrap_or_else(|| HistogramBucket::new(start, by_event_type))
+        })
+        .collect();
+
+    Ok(Json(buckets))
+}
+
+// Counts the distinct values of one data field over the first `limit` events (as /drain
+// reads them), most common first. Events without the field are scanned but not counted
+pub async fn get_facets(
+    facets_query: Query<FacetsQuery>,
+) -> Result<Json<Facets>, (StatusCode, Json<serde_json::Value>)> {
+    let query = facets_query.0;
+
+    let field = query
+        .field
+        .filter(|f| !f.is_empty())
+        .ok_or_else(|| error_response(anyhow!("A field is required")))?;
+    let max_values = query
+        .max_values
+        .unwrap_or(DEFAULT_FACET_VALUES)
+        .clamp(1, MAX_FACET_VALUES);
+    let limit = query
+        .limit
+        .unwrap_or(10_000)
+        .clamp(1, MAX_FACET_SCAN.min(config().max_limit));
+
+    let (mut rx, handle) = scan_events(ScanRequest {
+        service_name: query.event_name.unwrap_or_default(),
+        limit,
+        filter: None,
+        event_type: query.event_type,
+        boot: query.boot,
+        source: query.source,
+        time_range: TimeRange::default(),
+    });
+
+    let mut scanned = 0;
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    while let Some(ev) = rx.recv().await {
+        scanned += 1;
+        if let Some(value) = ev.data.get(&field) {
+            *counts.entry(value.clone()).or_default() += 1;
+        }
+    }
+
+    finish_scan(handle).await.map_err(error_response)?;
+
+    let mut values: Vec<FacetValue> = counts
+        .into_iter()
+        .map(|(value, count)| FacetValue { value, count })
+        .collect();
+    values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    let truncated = values.len() > max_values;
+    values.truncate(max_values);
+
+    Ok(Json(Facets {
+        field,
+        scanned,
+        values,
+        truncated,
+    }))
+}
+
+// Counts which regex matched each of the first `limit` events /drain would return, to find
+// rules that never match and services whose lines mostly fall through to UNKNOWN. Every
+// rule of the service is listed, so dead ones show up with 0 hits; with event_type only
+// the rules behind those types are
+pub async fn get_rule_stats(
+    rule_stats_query: Query<RuleStatsQuery>,
+) -> Result<Json<RuleStats>, (StatusCode, Json<serde_json::Value>)> {
+    let query = rule_stats_query.0;
+
+    let service = query.event_name.unwrap_or_default();
+    let mut rule_names = service_rule_names(&service).map_err(error_response)?;
+    if let Some(types) = &query.event_type {
+        // Kind filters select by what an event means, not by rule, so they can't say
+        // which rules should have matched
+        if let Some(t) = types
+            .iter()
+            .find(|t| t.contains(':') || str_to_regex_names(t).is_empty())
+        {
+            return Err(error_response(anyhow!(
+                "Unsupported event_type for rule stats: {t}"
+            )));
+        }
+        rule_names.retain(|name| types.iter().any(|t| str_to_regex_names(t).contains(name)));
+    }
+    let limit = query
+        .limit
+        .unwrap_or(10_000)
+        .clamp(1, MAX_RULE_STATS_SCAN.min(config().max_limit));
+
+    let (mut rx, handle) = scan_events(ScanRequest {
+        service_name: service.clone(),
+        limit,
+        filter: None,
+        event_type: query.event_type,
+        boot: query.boot,
+        source: query.source,
+        time_range: TimeRange::default(),
+    });
+
+    let mut counts: HashMap<String, u64> = rule_names
+        .iter()
+        .map(|name| (name.to_string(), 0))
+        .collect();
+    while let Some(ev) = rx.recv().await {
+        if let Some(rule) = ev.matched_rule {
+            *counts.entry(rule).or_default() += 1;
+        }
+    }
+
+    // Entries no rule matched never become events, so the rate is over entries read
+    let scanned = finish_scan(handle).await.map_err(error_response)? as u64;
+
+    let unknown = counts.get("UNKNOWN").copied().unwrap_or(0);
+    let unknown_rate = if scanned == 0 {
+        0.0
+    } else {
+        unknown as f64 / scanned as f64
+    };
+    let mut rules: Vec<RuleHits> = counts
+        .into_iter()
+        .map(|(rule, hits)| RuleHits { rule, hits })
+        .collect();
+    rules.sort_by(|a, b| b.hits.cmp(&a.hits).then_with(|| a.rule.cmp(&b.rule)));
+    let dead_rules = rules
+        .iter()
+        .filter(|r| r.hits == 0 && r.rule != "UNKNOWN")
+        .map(|r| r.rule.clone())
+        .collect();
+
+    Ok(Json(RuleStats {
+        service,
+        scanned,
+        unknown,
+        unknown_rate,
+        rules,
+        dead_rules,
+    }))
+}
+
+// Collects the events of a time range into one JSON array served as a file download, for
+// archiving an incident window. Scans the first `limit` events like /drain; the response
+// is refused once it would grow past MAX_BUNDLE_BYTES
+pub async fn get_bundle(
+    bundle_query: Query<BundleQuery>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    let query = bundle_query.0;
+
+    let ts_format =
+        TimestampFormat::from_query(query.timestamp_format.as_deref()).map_err(error_response)?;
+    let limit = query
+        .limit
+        .unwrap_or(MAX_BUNDLE_SCAN)
+        .clamp(1, MAX_BUNDLE_SCAN.min(config().max_limit));
+    let time_range = TimeRange {
+        from: window_start(query.timestamp_from, query.within.as_deref())
+            .map_err(error_response)?
+            .map(|t| t as i64),
+        to: query.timestamp_to.map(|t| t as i64),
+    };
+    let service_name = query.event_name.unwrap_or_default();
+
+    let (mut rx, handle) = scan_events(ScanRequest {
+        service_name: service_name.clone(),
+        limit,
+        filter: None,
+        event_type: query.event_type,
+        boot: query.boot,
+        source: query.source,
+        time_range,
+    });
+
+    let mut body = b"[".to_vec();
+    let mut scanned = 0;
+    while let Some(ev) = rx.recv().await {
+        scanned += 1;
+        if event_datetime(&ev).is_some_and(|dt| !time_range.contains(dt.timestamp())) {
+            continue;
+        }
+
+        if body.len() > 1 {
+            body.push(b',');
+        }
+        serde_json::to_writer(&mut body, &event_value(&ev, ts_format))
+            .map_err(|e| error_response(e.into()))?;
+        if body.len() > MAX_BUNDLE_BYTES {
+            // Dropping the receiver stops the scan thread at its next send
+            drop(rx);
+            let _ = finish_scan(handle).await;
+            return Err(error_response(
+                DrashtaError::BundleTooLarge(MAX_BUNDLE_BYTES).into(),
+            ));
+        }
+    }
+    body.push(b']');
+
+    finish_scan(handle).await.map_err(error_response)?;
+
+    let range_label = |secs: Option<i64>, default: &str| {
+        secs.and_then(|s| chrono::DateTime::from_timestamp(s, 0))
+            .map(|dt| dt.format("%Y%m%dT%H%M%SZ").to_string())
+            .unwrap_or_else(|| default.to_string())
+    };
+    let filename = format!(
+        "drashta-{}-{}-{}.json",
+        service_name.trim_end_matches(".events"),
+        range_label(time_range.from, "start"),
+        range_label(time_range.to, "now"),
+    );
+
+    let mut response = (
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        body,
     )
+        .into_response();
+    // The scan stopped at `limit` events, so older events in the range may be missing
+    if scanned >= limit {
+        response
+            .headers_mut()
+            .insert("x-drashta-truncated", HeaderValue::from_static("true"));
+    }
+    Ok(response)
+}
+
+pub async fn parse_message(
+    Json(req): Json<ParseRequest>,
+) -> Result<Json<ParseResult>, (StatusCode, Json<serde_json::Value>)> {
+    parse_single_message(&req.service, &req.message)
+        .map(Json)
+        .map_err(error_response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usb_event(message: &str, received_secs: i64) -> EventData {
+        let entry: Entry = [
+            ("MESSAGE", message.to_string()),
+            ("_TRANSPORT", "kernel".to_string()),
+            (
+                "__REALTIME_TIMESTAMP",
+                (received_secs * 1_000_000).to_string(),
+            ),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+        parse_kernel_events(entry, None).unwrap()
+    }
+
+    #[test]
+    fn usb_connect_and_disconnect_pair_into_a_session() {
+        let connect = usb_event(
+            "usb 1-2: New USB device found, idVendor=046d, idProduct=c52b",
+            1_760_608_800,
+        );
+        let disconnect = usb_event("usb 1-2: USB disconnect, device number 5", 1_760_608_890);
+
+        let mut sessions = SessionTracker::new(Some(true), None);
+        assert!(sessions.pair(&connect).is_none());
+        let (kind, session) = sessions.pair(&disconnect).unwrap();
+        assert_eq!(kind, SessionKind::Usb);
+        assert_eq!(session["device"], "1-2");
+        assert_eq!(session["duration_secs"], 90);
+    }
+
+    #[test]
+    fn usb_sessions_pair_when_read_backwards() {
+        let connect = usb_event(
+            "usb 3-1: New USB device found, idVendor=0781, idProduct=5581",
+            1_760_608_800,
+        );
+        let disconnect = usb_event("usb 3-1: USB disconnect, device number 2", 1_760_612_400);
+
+        let mut sessions = SessionTracker::new(Some(true), None);
+        assert!(sessions.pair(&disconnect).is_none());
+        let (_, session) = sessions.pair(&connect).unwrap();
+        assert_eq!(session["connected_at"], json!(connect.timestamp));
+        assert_eq!(session["disconnected_at"], json!(disconnect.timestamp));
+        assert_eq!(session["duration_secs"], 3600);
+    }
+
+    #[test]
+    fn within_bounds_the_scan_to_the_last_hour() {
+        let now = chrono::Utc::now().timestamp();
+        let from = window_start(None, Some("1h")).unwrap().unwrap() as i64;
+        assert!((now - 3_600 - from).abs() <= 1);
+
+        let range = TimeRange {
+            from: Some(from),
+            to: None,
+        };
+        assert!(range.before(now - 7_200));
+        assert!(range.contains(now - 60));
+    }
+
+    #[test]
+    fn within_and_timestamp_from_take_the_later_bound() {
+        let recent = (chrono::Utc::now().timestamp() - 60) as u64;
+        assert_eq!(
+            window_start(Some(recent), Some("1h")).unwrap(),
+            Some(recent)
+        );
+        assert_ne!(window_start(Some(0), Some("1h")).unwrap(), Some(0));
+        assert_eq!(window_start(Some(42), None).unwrap(), Some(42));
+        assert!(window_start(None, Some("1w")).is_err());
+    }
+
+    #[tokio::test]
+    async fn throttle_drops_data_but_passes_notices() {
+        let data = |n: u32| LiveEvent::Data(Event::default().data(n.to_string()));
+        let lag = || LiveEvent::Notice(Event::default().event("lag").data("{}"));
+        let input = futures::stream::iter([data(1), data(2), lag(), data(3), lag()]);
+
+        let out: Vec<String> = throttle(input, Some(1))
+            .map(|ev| format!("{:?}", ev.unwrap()))
+            .collect()
+            .await;
+
+        assert_eq!(out.len(), 3);
+        assert_eq!(out.iter().filter(|ev| ev.contains("event: lag")).count(), 2);
+    }
 }
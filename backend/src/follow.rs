@@ -0,0 +1,75 @@
+use log::{info, warn};
+use tokio::sync::broadcast;
+
+use crate::parser::{
+    EventData, LogSource, enabled_service_configs, read_journal_logs, read_journal_logs_manual,
+    reads_file_source,
+};
+use crate::shutdown::STOPPING;
+
+const CHANNEL_CAPACITY: usize = 1024;
+
+// Every enabled service except security.events, whose events come from its member
+// services and would be seen twice
+pub fn exportable_services() -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = enabled_service_configs()
+        .into_keys()
+        .filter(|&name| name != "security.events")
+        .collect();
+    names.sort();
+    names
+}
+
+// Follows each service on its own thread from now on and sends every event into one
+// channel for the exporters and the alerter. These readers don't depend on /live clients,
+// and each service is read exactly once however many of them subscribe. With `persist`
+// they are also what writes to the --persist store, unfiltered
+pub fn spawn_followers(services: &[&'static str], persist: bool) -> broadcast::Sender<EventData> {
+    let (tx, mut rx) = broadcast::channel(CHANNEL_CAPACITY);
+    // Keeps a receiver around when only the store consumes the events, otherwise every
+    // send fails and the readers hold events back for a subscriber that never comes
+    tokio::spawn(async move {
+        while !matches!(rx.recv().await, Err(broadcast::error::RecvError::Closed)) {}
+    });
+    for &service_name in services {
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            info!("Following {service_name} in the background");
+            if let Err(e) = follow(service_name, tx, persist) {
+                warn!("Stopped following {service_name} in the background: {e}");
+            }
+        });
+    }
+    tx
+}
+
+fn follow(
+    service_name: &str,
+    tx: broadcast::Sender<EventData>,
+    persist: bool,
+) -> anyhow::Result<()> {
+    if reads_file_source(service_name, LogSource::Auto, None, None)? {
+        read_journal_logs_manual(
+            service_name,
+            None,
+            Vec::new(),
+            None,
+            None,
+            tx,
+            &STOPPING,
+            persist,
+        )
+    } else {
+        read_journal_logs(
+            service_name,
+            None,
+            Vec::new(),
+            Vec::new(),
+            None,
+            None,
+            tx,
+            &STOPPING,
+            persist,
+        )
+    }
+}
@@ -1,4 +1,17 @@
+pub mod alert;
+pub mod config;
+pub mod error;
 pub mod events;
+pub mod follow;
+pub mod logging;
+pub mod metrics;
+pub mod net;
+pub mod otlp;
 pub mod parser;
 pub mod regex;
 pub mod render;
+pub mod resolve;
+pub mod schema;
+pub mod shutdown;
+pub mod store;
+pub mod syslog;
@@ -0,0 +1,72 @@
+use std::io::{IsTerminal, Write};
+
+use chrono::{SecondsFormat, Utc};
+use log::{Level, LevelFilter};
+use serde_json::json;
+
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LogFormat {
+    #[default]
+    Pretty,
+    Json,
+}
+
+impl LogFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format {
+            "pretty" => Some(LogFormat::Pretty),
+            "json" => Some(LogFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+// Sends the log macros to stderr. Colors are only used in pretty mode on a terminal,
+// so journald and log shippers get plain lines.
+// Everything logs at info unless RUST_LOG says otherwise. Each -v raises Drashta's own
+// modules a level (debug, then trace) on top of that
+pub fn init(format: LogFormat, verbosity: u8) {
+    let color = format == LogFormat::Pretty && std::io::stderr().is_terminal();
+
+    let mut builder = env_logger::Builder::new();
+    builder
+        .filter_level(LevelFilter::Info)
+        .parse_env("RUST_LOG");
+    match verbosity {
+        0 => {}
+        1 => {
+            builder.filter_module(env!("CARGO_CRATE_NAME"), LevelFilter::Debug);
+        }
+        _ => {
+            builder.filter_module(env!("CARGO_CRATE_NAME"), LevelFilter::Trace);
+        }
+    }
+
+    builder.format(move |buf, record| match format {
+        LogFormat::Pretty if color => {
+            let color = match record.level() {
+                Level::Error => RED,
+                Level::Warn => YELLOW,
+                _ => CYAN,
+            };
+            writeln!(buf, "{color}[{}] {RESET}{}", record.level(), record.args())
+        }
+        LogFormat::Pretty => writeln!(buf, "[{}] {}", record.level(), record.args()),
+        LogFormat::Json => writeln!(
+            buf,
+            "{}",
+            json!({
+                "timestamp": Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+                "level": record.level().as_str(),
+                "target": record.target(),
+                "message": record.args().to_string(),
+            })
+        ),
+    });
+    let _ = builder.try_init();
+}
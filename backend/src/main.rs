@@ -1,16 +1,178 @@
+use std::path::PathBuf;
 use std::process::exit;
 
 use anyhow::Result;
-use drashta::parser::EventData;
-use drashta::render::render_app;
+use drashta::config::{CONFIG, Config};
+use drashta::logging::{self, LogFormat};
+use drashta::parser::{EventData, get_service_configs};
+use drashta::render::{parse_cors_origin, render_app};
+use drashta::store::{flush_store, open_store};
+use log::error;
 
-fn handle_args() -> u16 {
+fn handle_args() -> Config {
     let mut args = std::env::args().skip(1);
-    let mut port = None;
+    let mut config = Config::default();
+    if let Ok(path) = std::env::var("DRASHTA_PACMAN_LOG")
+        && !path.is_empty()
+    {
+        config.pacman_log = PathBuf::from(path);
+    }
+    if let Ok(path) = std::env::var("DRASHTA_ACCESS_LOG")
+        && !path.is_empty()
+    {
+        config.access_log = PathBuf::from(path);
+    }
+    if let Ok(path) = std::env::var("DRASHTA_STATIC_DIR")
+        && !path.is_empty()
+    {
+        config.static_dir = Some(PathBuf::from(path));
+    }
+    if let Ok(path) = std::env::var("DRASHTA_AUTH_LOG")
+        && !path.is_empty()
+    {
+        config.auth_log = PathBuf::from(path);
+    }
     while let Some(arg) = args.next() {
         match arg.as_str() {
             "--port" => {
-                port = args.next().and_then(|x| x.parse::<u16>().ok());
+                config.port = args
+                    .next()
+                    .and_then(|x| x.parse::<u16>().ok())
+                    .unwrap_or(3200);
+            }
+            "--persist" => {
+                config.persist = args.next().map(PathBuf::from);
+            }
+            "--tls-cert" => {
+                config.tls_cert = args.next().map(PathBuf::from);
+            }
+            "--tls-key" => {
+                config.tls_key = args.next().map(PathBuf::from);
+            }
+            "--metrics" => {
+                config.metrics = true;
+            }
+            "--buffer-size" => {
+                config.buffer_size = args
+                    .next()
+                    .and_then(|x| x.parse::<usize>().ok())
+                    .unwrap_or(5_000);
+            }
+            "--poll-interval-ms" => {
+                config.poll_interval_ms = args
+                    .next()
+                    .and_then(|x| x.parse::<u64>().ok())
+                    .unwrap_or(500);
+            }
+            "--sse-retry-ms" => {
+                config.sse_retry_ms = args
+                    .next()
+                    .and_then(|x| x.parse::<u64>().ok())
+                    .unwrap_or(3_000);
+            }
+            "--keepalive-secs" => {
+                config.keepalive_secs = args
+                    .next()
+                    .and_then(|x| x.parse::<u64>().ok())
+                    .filter(|&x| x > 0)
+                    .unwrap_or(15);
+            }
+            "--max-limit" => {
+                config.max_limit = args
+                    .next()
+                    .and_then(|x| x.parse::<i32>().ok())
+                    .filter(|&x| x > 0)
+                    .unwrap_or(100_000);
+            }
+            "--max-connections" => {
+                config.max_connections = args
+                    .next()
+                    .and_then(|x| x.parse::<usize>().ok())
+                    .filter(|&x| x > 0)
+                    .unwrap_or(256);
+            }
+            "--resolve-ptr" => {
+                config.resolve_ptr = true;
+            }
+            "--pacman-log" => {
+                if let Some(path) = args.next() {
+                    config.pacman_log = PathBuf::from(path);
+                }
+            }
+            "--access-log" => {
+                if let Some(path) = args.next() {
+                    config.access_log = PathBuf::from(path);
+                }
+            }
+            "--auth-log" => {
+                if let Some(path) = args.next() {
+                    config.auth_log = PathBuf::from(path);
+                }
+            }
+            "--cors-origin" => {
+                let origin = args.next().unwrap_or_default();
+                match parse_cors_origin(&origin) {
+                    Ok(origin) => config.cors_origins.push(origin),
+                    Err(e) => {
+                        eprintln!("Invalid --cors-origin: {e}");
+                        exit(1);
+                    }
+                }
+            }
+            "--otlp-endpoint" => {
+                config.otlp_endpoint = args.next();
+            }
+            "--log-format" => {
+                let format = args.next().unwrap_or_default();
+                match LogFormat::parse(&format) {
+                    Some(format) => config.log_format = format,
+                    None => {
+                        eprintln!("Invalid --log-format `{format}`, expected pretty or json");
+                        exit(1);
+                    }
+                }
+            }
+            "-v" | "--verbose" => {
+                config.verbosity = config.verbosity.saturating_add(1);
+            }
+            "-vv" => {
+                config.verbosity = config.verbosity.saturating_add(2);
+            }
+            "--syslog-forward" => {
+                config.syslog_forward = args.next();
+            }
+            "--alert-webhook" => {
+                config.alert_webhook = args.next();
+            }
+            "--alert-rule" => {
+                if let Some(rule) = args.next() {
+                    config.alert_rules.push(rule);
+                }
+            }
+            "--alert-cooldown-secs" => {
+                config.alert_cooldown_secs = args
+                    .next()
+                    .and_then(|x| x.parse::<u64>().ok())
+                    .unwrap_or(60);
+            }
+            "--enable-service" => {
+                if let Some(name) = args.next() {
+                    config.enabled_services.push(name);
+                }
+            }
+            "--disable-service" => {
+                if let Some(name) = args.next() {
+                    config.disabled_services.push(name);
+                }
+            }
+            "--journal-dir" => {
+                config.journal_dir = args.next().map(PathBuf::from);
+            }
+            "--static-dir" => {
+                config.static_dir = args.next().map(PathBuf::from);
+            }
+            "--replay-dir" => {
+                config.replay_dir = args.next().map(PathBuf::from);
             }
             "--help" | "-h" => {
                 print_help();
@@ -19,7 +181,7 @@ fn handle_args() -> u16 {
             _ => {}
         }
     }
-    if port.is_some() { port.unwrap() } else { 3200 }
+    config
 }
 fn print_help() {
     println!(
@@ -28,6 +190,33 @@ fn print_help() {
 Options:
   -h, --help        Print this help message
   --port <PORT>     Set the server port (default: 3200)
+  --persist <PATH>  Follow every enabled service and store its events in a sled database at PATH (disabled by default)
+  --tls-cert <PATH> Serve HTTPS with the PEM certificate chain at PATH (needs --tls-key)
+  --tls-key <PATH>  PEM private key for --tls-cert
+  --metrics         Expose Prometheus counters at /metrics (disabled by default)
+  --buffer-size <N> Live events kept per service while no client is connected (default: 5000)
+  --poll-interval-ms <N> Longest a live journal tail waits before rechecking (default: 500)
+  --sse-retry-ms <N> How long browsers wait before reconnecting a dropped stream (default: 3000)
+  --keepalive-secs <N> Seconds between keep-alive comments on idle SSE streams (default: 15)
+  --max-limit <N>   Largest limit a request may ask for, larger ones are clamped (default: 100000)
+  --max-connections <N> Most SSE streams open at once, more are refused with 429 (default: 256)
+  --resolve-ptr     Add a reverse DNS hostname (rhostname) to events with an ip field
+  --pacman-log <PATH> Read pkgmanager.events from PATH (default: $DRASHTA_PACMAN_LOG or /var/log/pacman.log)
+  --access-log <PATH> Read accesslog.events from PATH (default: $DRASHTA_ACCESS_LOG or /var/log/nginx/access.log)
+  --auth-log <PATH> Syslog file read by sshd, sudo, login, userchange and security events with source=file (default: $DRASHTA_AUTH_LOG or /var/log/auth.log)
+  --cors-origin <ORIGIN> Only allow cross-origin requests from ORIGIN, e.g. https://ui.example.com (repeatable, default: any origin)
+  --otlp-endpoint <URL> Follow every enabled service and export its events as OTLP logs to an http:// collector, e.g. http://localhost:4318
+  --syslog-forward <ADDR> Follow every enabled service and forward its events as RFC 5424 syslog to HOST:PORT over UDP, or tcp://HOST:PORT
+  --alert-webhook <URL> POST an alert to URL (Slack, Discord or any JSON endpoint) for live events matching an --alert-rule
+  --alert-rule <SERVICE:EVENT_TYPE[:KEYWORD]> Event to alert on, e.g. sshd.events:Failure or kernel.events:KernelPanic (repeatable)
+  --alert-cooldown-secs <N> Seconds a rule stays quiet after alerting, repeats are counted meanwhile (default: 60)
+  --log-format <FORMAT> Write Drashta's own logs to stderr as pretty or json lines (default: pretty)
+  -v, --verbose     Log debug messages from Drashta, twice (-vv) for trace; RUST_LOG (e.g. warn,drashta::parser=debug) is honored too
+  --enable-service <NAME> Only offer NAME, e.g. sshd.events (repeatable, default: every service)
+  --disable-service <NAME> Don't offer NAME, e.g. sudo.events (repeatable)
+  --journal-dir <PATH> Read journal files from PATH (a directory or a single .journal file) instead of the running system's journal
+  --static-dir <DIR> Serve the web UI from DIR (default: $DRASHTA_STATIC_DIR, then <binary dir>/../share/drashta/static, /usr/share/drashta/static, and the source checkout)
+  --replay-dir <DIR> Allow /replay to stream captured ndjson event files from DIR (disabled by default)
 "#
     );
 }
@@ -35,8 +224,54 @@ Options:
 #[tokio::main]
 pub async fn main() -> Result<()> {
     let (tx, _) = tokio::sync::broadcast::channel::<EventData>(1024);
-    let port = handle_args();
+    let config = handle_args();
+    logging::init(config.log_format, config.verbosity);
+
+    if let Some(path) = &config.journal_dir
+        && !path.exists()
+    {
+        error!("Journal path {} does not exist", path.display());
+        exit(1);
+    }
+
+    if config.tls_cert.is_some() != config.tls_key.is_some() {
+        error!("--tls-cert and --tls-key have to be given together");
+        exit(1);
+    }
+
+    if let Some(path) = &config.static_dir
+        && !path.is_dir()
+    {
+        error!("Static directory {} does not exist", path.display());
+        exit(1);
+    }
+
+    if let Some(path) = &config.persist
+        && let Err(e) = open_store(path)
+    {
+        error!("Failed to open event store at {}: {e}", path.display());
+        exit(1);
+    }
+
+    let port = config.port;
+    let _ = CONFIG.set(config);
+
+    // Service names are checked once the config is set, since building the service
+    // map reads it
+    let config = drashta::config::config();
+    let services = get_service_configs();
+    if let Some(name) = config
+        .enabled_services
+        .iter()
+        .chain(&config.disabled_services)
+        .find(|name| !services.contains_key(name.as_str()))
+    {
+        error!("Unknown service {name} in --enable-service/--disable-service");
+        exit(1);
+    }
+
     render_app(tx, port).await;
+    flush_store();
 
     Ok(())
 }
@@ -0,0 +1,175 @@
+use std::fmt::Write;
+use std::sync::{
+    Arc, RwLock,
+    atomic::{AtomicU64, Ordering},
+};
+
+use ahash::AHashMap;
+use once_cell::sync::Lazy;
+
+use crate::config::config;
+use crate::parser::EventData;
+
+#[derive(Default)]
+struct ServiceMetrics {
+    entries_read: AtomicU64,
+    events_dropped: AtomicU64,
+    buffer_fill: AtomicU64,
+    buffer_overflow: AtomicU64,
+    events_parsed: RwLock<AHashMap<String, AtomicU64>>,
+}
+
+static REGISTRY: Lazy<RwLock<AHashMap<String, Arc<ServiceMetrics>>>> = Lazy::new(Default::default);
+
+pub fn is_enabled() -> bool {
+    config().metrics
+}
+
+fn service_metrics(service_name: &str) -> Arc<ServiceMetrics> {
+    if let Some(m) = REGISTRY.read().unwrap().get(service_name) {
+        return m.clone();
+    }
+    REGISTRY
+        .write()
+        .unwrap()
+        .entry(service_name.to_string())
+        .or_default()
+        .clone()
+}
+
+pub fn record_entry_read(service_name: &str) {
+    if !is_enabled() {
+        return;
+    }
+    service_metrics(service_name)
+        .entries_read
+        .fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_event_parsed(service_name: &str, ev: &EventData) {
+    if !is_enabled() {
+        return;
+    }
+    let (category, variant) = ev.event_type.kind();
+    let kind = format!("{category}:{variant}");
+    let metrics = service_metrics(service_name);
+
+    if let Some(counter) = metrics.events_parsed.read().unwrap().get(&kind) {
+        counter.fetch_add(1, Ordering::Relaxed);
+        return;
+    }
+    metrics
+        .events_parsed
+        .write()
+        .unwrap()
+        .entry(kind)
+        .or_default()
+        .fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_event_dropped(service_name: &str) {
+    if !is_enabled() {
+        return;
+    }
+    service_metrics(service_name)
+        .events_dropped
+        .fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_buffer_overflow(service_name: &str) {
+    if !is_enabled() {
+        return;
+    }
+    service_metrics(service_name)
+        .buffer_overflow
+        .fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn set_buffer_fill(service_name: &str, len: usize) {
+    if !is_enabled() {
+        return;
+    }
+    service_metrics(service_name)
+        .buffer_fill
+        .store(len as u64, Ordering::Relaxed);
+}
+
+pub fn render_metrics() -> String {
+    let registry = REGISTRY.read().unwrap();
+    let mut services: Vec<_> = registry.iter().collect();
+    services.sort_by_key(|(name, _)| name.as_str());
+
+    let mut out = String::new();
+
+    let _ = writeln!(
+        out,
+        "# HELP drashta_entries_read_total Journal entries or log lines read per service"
+    );
+    let _ = writeln!(out, "# TYPE drashta_entries_read_total counter");
+    for (name, m) in &services {
+        let _ = writeln!(
+            out,
+            "drashta_entries_read_total{{service=\"{name}\"}} {}",
+            m.entries_read.load(Ordering::Relaxed)
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP drashta_events_parsed_total Events parsed per service and event kind"
+    );
+    let _ = writeln!(out, "# TYPE drashta_events_parsed_total counter");
+    for (name, m) in &services {
+        let parsed = m.events_parsed.read().unwrap();
+        let mut kinds: Vec<_> = parsed.iter().collect();
+        kinds.sort_by_key(|(kind, _)| kind.as_str());
+        for (kind, count) in kinds {
+            let _ = writeln!(
+                out,
+                "drashta_events_parsed_total{{service=\"{name}\",kind=\"{kind}\"}} {}",
+                count.load(Ordering::Relaxed)
+            );
+        }
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP drashta_events_dropped_total Events that could not be sent to a client"
+    );
+    let _ = writeln!(out, "# TYPE drashta_events_dropped_total counter");
+    for (name, m) in &services {
+        let _ = writeln!(
+            out,
+            "drashta_events_dropped_total{{service=\"{name}\"}} {}",
+            m.events_dropped.load(Ordering::Relaxed)
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP drashta_failed_buffer_events Live events buffered while no client is connected"
+    );
+    let _ = writeln!(out, "# TYPE drashta_failed_buffer_events gauge");
+    for (name, m) in &services {
+        let _ = writeln!(
+            out,
+            "drashta_failed_buffer_events{{service=\"{name}\"}} {}",
+            m.buffer_fill.load(Ordering::Relaxed)
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP drashta_buffer_overflow_total Buffered live events dropped because the buffer was full"
+    );
+    let _ = writeln!(out, "# TYPE drashta_buffer_overflow_total counter");
+    for (name, m) in &services {
+        let _ = writeln!(
+            out,
+            "drashta_buffer_overflow_total{{service=\"{name}\"}} {}",
+            m.buffer_overflow.load(Ordering::Relaxed)
+        );
+    }
+
+    out
+}
@@ -0,0 +1,194 @@
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow, bail};
+use rustls::pki_types::ServerName;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+
+// HOST, HOST:PORT, [IPV6] or [IPV6]:PORT. IPv6 hosts are returned without their brackets
+pub fn parse_host_port(authority: &str) -> Result<(String, Option<u16>)> {
+    let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+        let (host, after) = bracketed
+            .split_once(']')
+            .ok_or_else(|| anyhow!("unterminated IPv6 address"))?;
+        (host, after.strip_prefix(':'))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    if host.is_empty() {
+        bail!("missing host");
+    }
+    let port = port
+        .map(|p| p.parse::<u16>().map_err(|_| anyhow!("invalid port {p}")))
+        .transpose()?;
+    Ok((host.to_string(), port))
+}
+
+// An http:// or https:// URL to POST to. The port is the scheme's unless one was given
+#[derive(Debug, Clone)]
+pub struct HttpUrl {
+    pub tls: bool,
+    pub host: String,
+    pub port: Option<u16>,
+    pub path: String,
+}
+
+impl HttpUrl {
+    pub fn parse(url: &str) -> Result<Self> {
+        let (tls, rest) = if let Some(rest) = url.strip_prefix("https://") {
+            (true, rest)
+        } else if let Some(rest) = url.strip_prefix("http://") {
+            (false, rest)
+        } else {
+            bail!("expected an http:// or https:// URL");
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, "/"),
+        };
+        let (host, port) = parse_host_port(authority)?;
+        Ok(Self {
+            tls,
+            host,
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or(if self.tls { 443 } else { 80 })
+    }
+
+    fn host_header(&self) -> String {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        match (self.tls, self.port()) {
+            (true, 443) | (false, 80) => host,
+            (_, port) => format!("{host}:{port}"),
+        }
+    }
+}
+
+impl fmt::Display for HttpUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scheme = if self.tls { "https" } else { "http" };
+        write!(f, "{scheme}://{}{}", self.host_header(), self.path)
+    }
+}
+
+// Trusts the system's CA store, like curl does
+pub fn tls_connector() -> Result<TlsConnector> {
+    let mut roots = rustls::RootCertStore::empty();
+    let native = rustls_native_certs::load_native_certs();
+    let (added, _) = roots.add_parsable_certificates(native.certs);
+    if added == 0 {
+        bail!("no CA certificates found in the system trust store");
+    }
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+// One request per connection, only the status line of the response is read. https URLs
+// need the connector from tls_connector
+pub async fn post_json(url: &HttpUrl, connector: Option<&TlsConnector>, body: &str) -> Result<()> {
+    let stream = TcpStream::connect((url.host.as_str(), url.port())).await?;
+    match (url.tls, connector) {
+        (true, Some(connector)) => {
+            let name = ServerName::try_from(url.host.clone())?;
+            let stream = connector.connect(name, stream).await?;
+            send_request(url, stream, body).await
+        }
+        (true, None) => bail!("no TLS connector for {url}"),
+        (false, _) => send_request(url, stream, body).await,
+    }
+}
+
+async fn send_request<S: AsyncRead + AsyncWrite + Unpin>(
+    url: &HttpUrl,
+    mut stream: S,
+    body: &str,
+) -> Result<()> {
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        url.path,
+        url.host_header(),
+        body.len()
+    );
+    stream.write_all(request.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.flush().await?;
+
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line).await?;
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or_else(|| anyhow!("invalid HTTP response"))?;
+    if !(200..300).contains(&status) {
+        bail!("responded with {status}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_port_forms() {
+        assert_eq!(
+            parse_host_port("logs:6514").unwrap(),
+            ("logs".to_string(), Some(6514))
+        );
+        assert_eq!(
+            parse_host_port("10.0.0.5").unwrap(),
+            ("10.0.0.5".to_string(), None)
+        );
+        assert_eq!(
+            parse_host_port("[::1]:4318").unwrap(),
+            ("::1".to_string(), Some(4318))
+        );
+        assert_eq!(
+            parse_host_port("[fe80::1]").unwrap(),
+            ("fe80::1".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn host_port_errors() {
+        assert!(parse_host_port("").is_err());
+        assert!(parse_host_port(":80").is_err());
+        assert!(parse_host_port("[::1").is_err());
+        assert!(parse_host_port("host:http").is_err());
+        assert!(parse_host_port("host:70000").is_err());
+    }
+
+    #[test]
+    fn http_urls() {
+        let url = HttpUrl::parse("https://hooks.slack.com/services/T0/B0/x").unwrap();
+        assert_eq!(url.port(), 443);
+        assert_eq!(url.path, "/services/T0/B0/x");
+        assert_eq!(url.to_string(), "https://hooks.slack.com/services/T0/B0/x");
+
+        let url = HttpUrl::parse("http://[::1]:9000").unwrap();
+        assert_eq!(url.host, "::1");
+        assert_eq!(url.port(), 9000);
+        assert_eq!(url.to_string(), "http://[::1]:9000/");
+
+        assert!(HttpUrl::parse("ftp://host/").is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Result, bail};
+use log::{info, warn};
+use serde_json::{Value, json};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::net::{HttpUrl, post_json};
+use crate::parser::{EventData, RawMsgType, entry_datetime};
+
+const BATCH_SIZE: usize = 512;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_PORT: u16 = 4318;
+const LOGS_PATH: &str = "/v1/logs";
+
+// OTLP/HTTP collector address, e.g. http://localhost:4318
+#[derive(Debug, Clone)]
+pub struct OtlpEndpoint(HttpUrl);
+
+impl OtlpEndpoint {
+    pub fn parse(url: &str) -> Result<Self> {
+        if url.starts_with("https://") {
+            bail!("https is not supported, point it at a local collector over http://");
+        }
+        let mut url = HttpUrl::parse(url)?;
+        url.port.get_or_insert(DEFAULT_PORT);
+        // A bare collector address gets the standard logs path
+        if url.path.trim_end_matches('/').is_empty() {
+            url.path = LOGS_PATH.to_string();
+        }
+        Ok(Self(url))
+    }
+}
+
+// Forwards every event on the broadcast channel to the collector, in batches of up to
+// BATCH_SIZE records or whatever arrived within FLUSH_INTERVAL
+pub fn spawn_exporter(endpoint: OtlpEndpoint, mut rx: broadcast::Receiver<EventData>) {
+    info!("Exporting events as OTLP logs to {}", endpoint.0);
+
+    tokio::spawn(async move {
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        let mut flush = tokio::time::interval(FLUSH_INTERVAL);
+
+        loop {
+            tokio::select! {
+                res = rx.recv() => match res {
+                    Ok(ev) => {
+                        batch.push(log_record(&ev));
+                        if batch.len() >= BATCH_SIZE {
+                            export(&endpoint, &mut batch).await;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("OTLP exporter fell behind, {skipped} events were not exported");
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                _ = flush.tick() => {
+                    if !batch.is_empty() {
+                        export(&endpoint, &mut batch).await;
+                    }
+                }
+            }
+        }
+
+        if !batch.is_empty() {
+            export(&endpoint, &mut batch).await;
+        }
+    });
+}
+
+// A failed batch is dropped rather than retried, so a dead collector can't back up the exporter
+async fn export(endpoint: &OtlpEndpoint, batch: &mut Vec<Value>) {
+    let count = batch.len();
+    let body = json!({
+        "resourceLogs": [{
+            "resource": {
+                "attributes": [attribute("service.name", "drashta")],
+            },
+            "scopeLogs": [{
+                "scope": {
+                    "name": "drashta",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+                "logRecords": std::mem::take(batch),
+            }],
+        }],
+    });
+
+    let res = tokio::time::timeout(
+        SEND_TIMEOUT,
+        post_json(&endpoint.0, None, &body.to_string()),
+    )
+    .await;
+    match res {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => warn!("Failed to export {count} events over OTLP: {e}"),
+        Err(_) => warn!("Failed to export {count} events over OTLP: timed out"),
+    }
+}
+
+fn attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+// OTLP severity numbers for the syslog severities with_severity attaches
+fn severity_number(severity: &str) -> u8 {
+    match severity {
+        "emerg" => 21,
+        "alert" => 19,
+        "crit" => 18,
+        "err" => 17,
+        "warning" => 13,
+        "notice" => 10,
+        "info" => 9,
+        "debug" => 5,
+        _ => 0,
+    }
+}
+
+fn log_record(ev: &EventData) -> Value {
+    let observed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+
+    let (time, body) = match &ev.raw_msg {
+        RawMsgType::Structured(entry) => (
+            entry_datetime(entry)
+                .and_then(|dt| dt.timestamp_nanos_opt())
+                .map(|n| n as u128)
+                .unwrap_or(observed),
+            entry.get("MESSAGE").cloned().unwrap_or_default(),
+        ),
+        RawMsgType::Plain(line) => (observed, line.clone()),
+    };
+
+    let (category, variant) = ev.event_type.kind();
+    let mut attributes = vec![
+        attribute("drashta.service", &format!("{:?}", ev.service)),
+        attribute("drashta.event_type", &format!("{category}:{variant}")),
+    ];
+    if let Some(rule) = &ev.matched_rule {
+        attributes.push(attribute("drashta.matched_rule", rule));
+    }
+    let mut fields: Vec<_> = ev.data.iter().collect();
+    fields.sort();
+    attributes.extend(
+        fields
+            .into_iter()
+            .map(|(k, v)| attribute(&format!("drashta.data.{k}"), v)),
+    );
+
+    let severity = ev.data.get("severity").map(String::as_str).unwrap_or("");
+    let mut record = json!({
+        "timeUnixNano": time.to_string(),
+        "observedTimeUnixNano": observed.to_string(),
+        "body": { "stringValue": body },
+        "attributes": attributes,
+    });
+    if !severity.is_empty() {
+        record["severityText"] = json!(severity);
+        record["severityNumber"] = json!(severity_number(severity));
+    }
+    record
+}
@@ -1,15 +1,17 @@
 use std::{
+    cell::Cell,
     collections::{BTreeMap, VecDeque},
-    fmt::Debug,
+    fmt::{self, Debug},
     fs::File,
     io::{BufRead, BufReader, Read, Seek, SeekFrom},
-    path::PathBuf,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
     rc::Rc,
     result::Result::Ok,
     str::FromStr,
     sync::{
         Mutex,
-        atomic::{AtomicI32, Ordering},
+        atomic::{AtomicBool, Ordering},
     },
     thread::sleep,
     time::Duration,
@@ -18,17 +20,25 @@ use std::{
 use ahash::AHashMap;
 use anyhow::Result;
 use anyhow::anyhow;
-use chrono::{DateTime, Local, TimeZone};
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeZone};
+use flate2::read::GzDecoder;
 use inotify::{Inotify, WatchMask};
-use log::{error, info, warn};
+use log::{debug, error, info, warn};
 use memchr::memmem;
 use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
 
 use rayon::prelude::*;
-use serde::{Deserialize, Serialize, de::Deserializer};
+use serde::{Deserialize, Serialize};
 use systemd::*;
 
+use crate::error::DrashtaError;
+use crate::metrics::{
+    record_buffer_overflow, record_entry_read, record_event_dropped, record_event_parsed,
+    set_buffer_fill,
+};
 use crate::regex::*;
+use crate::store::persist_event;
 pub type Entry = BTreeMap<String, String>;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -39,23 +49,269 @@ pub enum RawMsgType {
 }
 
 impl RawMsgType {
-    fn contains_bytes(&self, pat: &str) -> bool {
-        let pat_lower = pat.to_lowercase();
+    fn matches(&self, keyword: &KeywordFilter) -> bool {
+        match self {
+            RawMsgType::Structured(map) => {
+                let values: Vec<&str> = map.values().map(String::as_str).collect();
+                keyword.is_match(&values)
+            }
+            RawMsgType::Plain(s) => keyword.is_match(&[s.as_str()]),
+        }
+    }
 
+    fn occurrences(&self, keyword: &KeywordFilter) -> usize {
         match self {
             RawMsgType::Structured(map) => {
-                map.values().any(|v| v.to_lowercase().contains(&pat_lower))
+                let values: Vec<&str> = map.values().map(String::as_str).collect();
+                keyword.occurrences(&values)
+            }
+            RawMsgType::Plain(s) => keyword.occurrences(&[s.as_str()]),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct QueryTerm {
+    pattern: String,
+    negated: bool,
+}
+
+impl QueryTerm {
+    fn new(term: &str, case_sensitive: bool) -> Self {
+        let (pattern, negated) = match term.strip_prefix('-') {
+            Some(rest) if !rest.is_empty() => (rest, true),
+            _ => (term, false),
+        };
+        let pattern = if case_sensitive {
+            pattern.to_string()
+        } else {
+            pattern.to_lowercase()
+        };
+        QueryTerm { pattern, negated }
+    }
+}
+
+// `|` (or OR) separates groups of which any may match, space separated terms inside a
+// group must all match (an explicit AND is allowed) and a leading `-` negates a term,
+// e.g. "sshd failed | root -session"
+fn parse_boolean_query(query: &str, case_sensitive: bool) -> Result<Vec<Vec<QueryTerm>>> {
+    let mut groups = vec![Vec::new()];
+    for token in query.replace('|', " | ").split_whitespace() {
+        match token {
+            "|" | "OR" => groups.push(Vec::new()),
+            "AND" => {}
+            term => groups
+                .last_mut()
+                .unwrap()
+                .push(QueryTerm::new(term, case_sensitive)),
+        }
+    }
+    groups.retain(|group| !group.is_empty());
+
+    if groups.is_empty() {
+        return Err(anyhow!("Query `{query}` has no search terms"));
+    }
+    Ok(groups)
+}
+
+#[derive(Clone, Debug)]
+pub enum KeywordFilter {
+    Substring(Vec<Vec<QueryTerm>>),
+    Case(Vec<Vec<QueryTerm>>),
+    Regex(Regex),
+}
+
+impl KeywordFilter {
+    pub fn new(query: &str, mode: Option<&str>) -> Result<Self> {
+        match mode.unwrap_or("substring") {
+            "substring" => parse_boolean_query(query, false).map(KeywordFilter::Substring),
+            "case" => parse_boolean_query(query, true).map(KeywordFilter::Case),
+            "regex" => Regex::new(query)
+                .map(KeywordFilter::Regex)
+                .map_err(|e| anyhow!("Invalid regex query `{query}`: {e}")),
+            other => Err(anyhow!(
+                "Unknown query_mode `{other}`, expected one of: substring, case, regex"
+            )),
+        }
+    }
+
+    pub fn from_query(query: Option<&str>, mode: Option<&str>) -> Result<Option<Self>> {
+        match query {
+            Some(q) if !q.trim().is_empty() => Self::new(q, mode).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn is_match(&self, values: &[&str]) -> bool {
+        match self {
+            KeywordFilter::Substring(groups) => {
+                let lowered: Vec<String> = values.iter().map(|v| v.to_lowercase()).collect();
+                let lowered: Vec<&str> = lowered.iter().map(String::as_str).collect();
+                query_groups_match(groups, &lowered)
+            }
+            KeywordFilter::Case(groups) => query_groups_match(groups, values),
+            KeywordFilter::Regex(re) => values.iter().any(|v| re.is_match(v)),
+        }
+    }
+
+    fn occurrences(&self, values: &[&str]) -> usize {
+        match self {
+            KeywordFilter::Substring(groups) => {
+                let lowered: Vec<String> = values.iter().map(|v| v.to_lowercase()).collect();
+                let lowered: Vec<&str> = lowered.iter().map(String::as_str).collect();
+                count_query_terms(groups, &lowered)
             }
-            RawMsgType::Plain(s) => s.to_lowercase().contains(&pat_lower),
+            KeywordFilter::Case(groups) => count_query_terms(groups, values),
+            KeywordFilter::Regex(re) => values.iter().map(|v| re.find_iter(v).count()).sum(),
         }
     }
 }
 
+// Each term is checked against every value, so "sshd failed" matches when one field
+// holds "sshd" and another "failed"
+fn query_groups_match(groups: &[Vec<QueryTerm>], values: &[&str]) -> bool {
+    groups.iter().any(|group| {
+        group.iter().all(|term| {
+            let found = values.iter().any(|v| v.contains(term.pattern.as_str()));
+            found != term.negated
+        })
+    })
+}
+
+// Negated terms only exclude events, they don't add to the count
+fn count_query_terms(groups: &[Vec<QueryTerm>], values: &[&str]) -> usize {
+    groups
+        .iter()
+        .flatten()
+        .filter(|term| !term.negated)
+        .map(|term| {
+            values
+                .iter()
+                .map(|v| v.matches(term.pattern.as_str()).count())
+                .sum::<usize>()
+        })
+        .sum()
+}
+
+// How often the query's terms occur in the event's raw entry, used to rank /search hits
+pub fn keyword_score(ev: &EventData, keyword: &KeywordFilter) -> usize {
+    ev.raw_msg.occurrences(keyword)
+}
+
+pub fn matches_keyword(ev: &EventData, keyword: &Option<KeywordFilter>) -> bool {
+    keyword.as_ref().is_none_or(|k| ev.raw_msg.matches(k))
+}
+
+pub type FieldFilter = (String, String);
+
+pub fn parse_field_filters(field_filter: Option<&str>) -> Result<Vec<FieldFilter>> {
+    let Some(field_filter) = field_filter else {
+        return Ok(Vec::new());
+    };
+
+    field_filter
+        .split(',')
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .map(|f| match f.split_once(':') {
+            Some((field, value)) if !field.is_empty() => Ok((field.to_string(), value.to_string())),
+            _ => Err(anyhow!("Invalid field filter `{f}`, expected field:value")),
+        })
+        .collect()
+}
+
+pub type JournalMatch = (String, String);
+
+// Journal field names are uppercase ASCII letters, digits and underscores, and may not
+// start with a digit. Fields starting with a double underscore are addresses, not data.
+fn is_valid_journal_field(field: &str) -> bool {
+    !field.is_empty()
+        && field.len() <= 64
+        && !field.starts_with("__")
+        && !field.starts_with(|c: char| c.is_ascii_digit())
+        && field
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+// match_fields=FIELD=value pairs, plus a PRIORITY match for every level from 0 (emerg)
+// up to max_priority, like journalctl -p, so journald skips the rest before parsing
+pub fn parse_match_fields(
+    match_fields: Option<&[String]>,
+    max_priority: Option<u8>,
+) -> Result<Vec<JournalMatch>> {
+    let priorities = match max_priority {
+        Some(p) if p > 7 => {
+            return Err(anyhow!(
+                "max_priority must be between 0 (emerg) and 7 (debug)"
+            ));
+        }
+        Some(p) => (0..=p)
+            .map(|level| ("PRIORITY".to_string(), level.to_string()))
+            .collect(),
+        None => Vec::new(),
+    };
+    let Some(match_fields) = match_fields else {
+        return Ok(priorities);
+    };
+
+    match_fields
+        .iter()
+        .map(|m| {
+            let Some((field, value)) = m.split_once('=') else {
+                return Err(anyhow!("Invalid match field `{m}`, expected FIELD=value"));
+            };
+            if !is_valid_journal_field(field) {
+                return Err(anyhow!(
+                    "Invalid journal field name `{field}`, expected uppercase letters, digits and underscores"
+                ));
+            }
+            if value.chars().any(char::is_control) {
+                return Err(anyhow!(
+                    "Invalid value for match field `{field}`, control characters are not allowed"
+                ));
+            }
+            Ok((field.to_string(), value.to_string()))
+        })
+        .chain(priorities.into_iter().map(Ok))
+        .collect()
+}
+
+pub fn matches_fields(ev: &EventData, field_filters: &[FieldFilter]) -> bool {
+    field_filters
+        .iter()
+        .all(|(field, value)| ev.data.get(field).is_some_and(|v| v == value))
+}
+
 #[derive(PartialEq, Deserialize, Serialize, Debug, Clone)]
 pub struct Cursor {
     pub timestamp: String,
     pub data: String,
     pub offset: u64,
+    #[serde(default)]
+    pub file_index: usize,
+}
+
+// Unix seconds bounds for an initial read. Journal reads seek to `from` and stop past
+// `to` by receive time, file reads skip and stop by the parsed event time
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TimeRange {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+impl TimeRange {
+    pub fn before(&self, secs: i64) -> bool {
+        self.from.is_some_and(|from| secs < from)
+    }
+
+    pub fn after(&self, secs: i64) -> bool {
+        self.to.is_some_and(|to| secs > to)
+    }
+
+    pub fn contains(&self, secs: i64) -> bool {
+        !self.before(secs) && !self.after(secs)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -65,16 +321,18 @@ pub enum ProcessLogType {
     ProcessPreviousLogs,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventData {
     pub timestamp: String,
     pub service: Service,
     pub event_type: EventType,
     pub data: AHashMap<String, String>,
     pub raw_msg: RawMsgType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_rule: Option<String>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Service {
     Sshd,
     Sudo,
@@ -85,20 +343,39 @@ pub enum Service {
     NetworkManager,
     Firewalld,
     Kernel,
+    Systemd,
+    Fail2ban,
+    Timesync,
+    Docker,
+    Bluetooth,
+    AppArmor,
+    Smartd,
+    AccessLog,
+    Wifi,
+    Power,
+    Mail,
+    Storage,
+    Flatpak,
+    FwLog,
+    Display,
+    Coredump,
+    Resolved,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AuthEvent {
     Success,
     Failure,
     SessionOpened,
     SessionClosed,
     ConnectionClosed,
+    ConnectionThrottled,
     TooManyAuthFailures,
     IncorrectPassword,
     AuthError,
     AuthFailure,
     NotInSudoers,
+    CommandRun,
     AccountExpired,
     NologinRefused,
     Warning,
@@ -106,7 +383,7 @@ pub enum AuthEvent {
     Other,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum UserEvent {
     NewUser,
     NewGroup,
@@ -119,7 +396,7 @@ pub enum UserEvent {
     Other,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PkgEvent {
     Installed,
     Removed,
@@ -129,7 +406,16 @@ pub enum PkgEvent {
     Other,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HttpEvent {
+    Success,
+    Redirect,
+    ClientError,
+    ServerError,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ConfigEvent {
     CmdRun,
     CronReload,
@@ -140,7 +426,7 @@ pub enum ConfigEvent {
     Other,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum NetworkEvent {
     NewConnection,
     ConnectionActivated,
@@ -170,7 +456,7 @@ pub enum NetworkEvent {
     Error,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FirewallEvent {
     ServiceStarted,
     ServiceStopped,
@@ -185,13 +471,16 @@ pub enum FirewallEvent {
     OperationStatus,
     ModuleMessage,
     DBusMessage,
+    PacketDropped,
+    PacketAccepted,
+    PacketLogged,
     Warning,
     Error,
     Info,
     Other,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum KernelEvent {
     Panic,
     OomKill,
@@ -202,11 +491,14 @@ pub enum KernelEvent {
     DiskError,
     FsMount,
     FsError,
+    FsReadOnly,
     CpuError,
     MemoryError,
+    MemoryPressure,
     DeviceDetected,
     DriverEvent,
     NetInterface,
+    NetStack,
     PciDevice,
     AcpiEvent,
     ThermalEvent,
@@ -229,7 +521,7 @@ pub enum KernelEvent {
     Other,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SystemEvent {
     Info,
     Warning,
@@ -237,7 +529,144 @@ pub enum SystemEvent {
     Other,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SystemdEvent {
+    Started,
+    Stopped,
+    Starting,
+    Stopping,
+    Reloaded,
+    Failed,
+    ProcessExited,
+    Deactivated,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Fail2banEvent {
+    Ban,
+    Unban,
+    Found,
+    AlreadyBanned,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TimesyncEvent {
+    Synced,
+    Stepped,
+    Slewed,
+    Lost,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ContainerEvent {
+    Start,
+    Stop,
+    Die,
+    Oom,
+    ImagePull,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BluetoothEvent {
+    Connected,
+    Disconnected,
+    Paired,
+    PairingFailed,
+    ConnectFailed,
+    AgentRequest,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AppArmorEvent {
+    Denied,
+    Allowed,
+    Status,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DiskHealthEvent {
+    PendingSectors,
+    UncorrectableSectors,
+    Temperature,
+    SelfTestFailed,
+    HealthCheckFailed,
+    AttributeChanged,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PowerEvent {
+    LidClosed,
+    LidOpened,
+    ButtonPressed,
+    Suspend,
+    Resume,
+    PowerOff,
+    Reboot,
+    DockChanged,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MailEvent {
+    Received,
+    Accepted,
+    Delivered,
+    Deferred,
+    Bounced,
+    Rejected,
+    AuthFailure,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StorageEvent {
+    DeviceFailed,
+    ArrayDegraded,
+    ArrayDisappeared,
+    RebuildStarted,
+    RebuildProgress,
+    RebuildFinished,
+    SpareActive,
+    ThinPoolFilling,
+    ThinPoolExhausted,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DisplayEvent {
+    GpuHang,
+    GpuReset,
+    XorgCrash,
+    XorgError,
+    CompositorCrash,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CoredumpEvent {
+    CoreDumped,
+    CrashedWithoutCore,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DnsEvent {
+    DnssecFailure,
+    ServerSwitched,
+    FeatureDegraded,
+    FeatureRestored,
+    QueryTimeout,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EventType {
     Auth(AuthEvent),
     User(UserEvent),
@@ -247,6 +676,75 @@ pub enum EventType {
     Kernel(KernelEvent),
     Config(ConfigEvent),
     System(SystemEvent),
+    Systemd(SystemdEvent),
+    Fail2ban(Fail2banEvent),
+    Timesync(TimesyncEvent),
+    Container(ContainerEvent),
+    Bluetooth(BluetoothEvent),
+    AppArmor(AppArmorEvent),
+    DiskHealth(DiskHealthEvent),
+    Http(HttpEvent),
+    Power(PowerEvent),
+    Mail(MailEvent),
+    Storage(StorageEvent),
+    Display(DisplayEvent),
+    Coredump(CoredumpEvent),
+    Dns(DnsEvent),
+}
+
+impl EventType {
+    pub fn kind(&self) -> (&'static str, String) {
+        match self {
+            EventType::Auth(e) => ("Auth", format!("{e:?}")),
+            EventType::User(e) => ("User", format!("{e:?}")),
+            EventType::Package(e) => ("Package", format!("{e:?}")),
+            EventType::Http(e) => ("Http", format!("{e:?}")),
+            EventType::Network(e) => ("Network", format!("{e:?}")),
+            EventType::Firewall(e) => ("Firewall", format!("{e:?}")),
+            EventType::Kernel(e) => ("Kernel", format!("{e:?}")),
+            EventType::Config(e) => ("Config", format!("{e:?}")),
+            EventType::System(e) => ("System", format!("{e:?}")),
+            EventType::Systemd(e) => ("Systemd", format!("{e:?}")),
+            EventType::Fail2ban(e) => ("Fail2ban", format!("{e:?}")),
+            EventType::Timesync(e) => ("Timesync", format!("{e:?}")),
+            EventType::Container(e) => ("Container", format!("{e:?}")),
+            EventType::Bluetooth(e) => ("Bluetooth", format!("{e:?}")),
+            EventType::AppArmor(e) => ("AppArmor", format!("{e:?}")),
+            EventType::DiskHealth(e) => ("DiskHealth", format!("{e:?}")),
+            EventType::Power(e) => ("Power", format!("{e:?}")),
+            EventType::Mail(e) => ("Mail", format!("{e:?}")),
+            EventType::Storage(e) => ("Storage", format!("{e:?}")),
+            EventType::Display(e) => ("Display", format!("{e:?}")),
+            EventType::Coredump(e) => ("Coredump", format!("{e:?}")),
+            EventType::Dns(e) => ("Dns", format!("{e:?}")),
+        }
+    }
+
+    pub fn matches_kind(&self, filter: &str) -> bool {
+        let Some((category, variant)) = filter.split_once(':') else {
+            return false;
+        };
+        let (ev_category, ev_variant) = self.kind();
+        ev_category == category && (variant == "*" || ev_variant == variant)
+    }
+}
+
+pub fn split_event_type_filter(ev_type: Option<Vec<&str>>) -> (Option<Vec<&str>>, Vec<&str>) {
+    let Some(ev_type) = ev_type else {
+        return (None, Vec::new());
+    };
+
+    let (kinds, names): (Vec<&str>, Vec<&str>) = ev_type.into_iter().partition(|s| s.contains(':'));
+
+    if names.is_empty() {
+        (None, kinds)
+    } else {
+        (Some(names), kinds)
+    }
+}
+
+pub fn matches_event_kinds(ev: &EventData, kinds: &[&str]) -> bool {
+    kinds.is_empty() || kinds.iter().any(|k| ev.event_type.matches_kind(k))
 }
 
 pub type ParserFn = fn(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData>;
@@ -260,6 +758,29 @@ pub enum ParserFunctionType {
 pub struct ServiceConfig {
     matches: Option<Vec<(&'static str, &'static str)>>,
     parser: ParserFunctionType,
+    regexes: Vec<&'static RegexTable>,
+    // Rules of a parser that names the rule itself after matching, listed alongside the
+    // regex table names
+    named_rules: &'static [&'static str],
+    // The file a manual service reads, or the flat file a journal service's syslog lines
+    // also land in (e.g. /var/log/auth.log via rsyslog)
+    file_source: Option<&'static Path>,
+}
+
+impl ServiceConfig {
+    fn rule_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.regexes
+            .iter()
+            .flat_map(|table| table.iter().map(|(rule, _)| *rule))
+            .chain(self.named_rules.iter().copied())
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ServiceInfo {
+    pub name: &'static str,
+    pub manual: bool,
+    pub event_types: Vec<&'static str>,
 }
 
 #[derive(Clone)]
@@ -268,27 +789,87 @@ pub struct ParserFuncArgs<'a> {
     tx: tokio::sync::mpsc::Sender<EventData>,
     limit: i32,
     processlogtype: ProcessLogType,
-    filter: Option<String>,
+    filter: Option<KeywordFilter>,
     ev_type: Option<Vec<&'a str>>,
+    ev_kinds: Vec<&'a str>,
+    field_filters: Vec<FieldFilter>,
+    match_fields: Vec<JournalMatch>,
     journal: Rc<Mutex<Journal>>,
     cursor: Option<CursorType>,
+    boot: Option<i32>,
+    source: LogSource,
+    time_range: TimeRange,
+    cursor_stale: Rc<Cell<bool>>,
+    entries_read: Rc<Cell<usize>>,
+}
+
+// A missing log file is a 404 rather than a generic error
+fn open_log_file(path: &Path) -> Result<File> {
+    File::open(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => DrashtaError::FileNotFound(path.to_path_buf()).into(),
+        _ => anyhow!("Failed to open {}: {e}", path.display()),
+    })
+}
+
+const JOURNAL_OPEN_ATTEMPTS: u32 = 3;
+const JOURNAL_OPEN_BACKOFF: Duration = Duration::from_millis(250);
+
+// The running system's journal, or the directory or journal file from --journal-dir
+fn open_journal_source() -> std::io::Result<Journal> {
+    let Some(path) = &crate::config::config().journal_dir else {
+        return journal::OpenOptions::default().all_namespaces(true).open();
+    };
+    let c_path = std::ffi::CString::new(path.as_os_str().as_encoded_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    if path.is_file() {
+        journal::OpenFilesOptions::default().open_files([c_path])
+    } else {
+        journal::OpenDirectoryOptions::default().open_directory(c_path)
+    }
+}
+
+// journald briefly refuses readers while it restarts or rotates, so retry a few times
+// with a doubling backoff before reporting the failure
+fn open_journal() -> Result<Journal> {
+    let mut attempt = 1;
+    loop {
+        match open_journal_source() {
+            Ok(journal) => return Ok(journal),
+            Err(e) if attempt < JOURNAL_OPEN_ATTEMPTS => {
+                warn!("Couldn't open journal (attempt {attempt}/{JOURNAL_OPEN_ATTEMPTS}): {e}");
+                sleep(JOURNAL_OPEN_BACKOFF * 2u32.pow(attempt - 1));
+                attempt += 1;
+            }
+            Err(e) => {
+                return Err(DrashtaError::JournalOpen {
+                    attempts: JOURNAL_OPEN_ATTEMPTS,
+                    source: e,
+                }
+                .into());
+            }
+        }
+    }
 }
 
 impl<'a> ParserFuncArgs<'a> {
-    pub fn new(
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
         service_name: &'a str,
         tx: tokio::sync::mpsc::Sender<EventData>,
         limit: i32,
         processlogtype: ProcessLogType,
-        filter: Option<String>,
+        filter: Option<KeywordFilter>,
+        field_filters: Vec<FieldFilter>,
+        match_fields: Vec<JournalMatch>,
         ev_type: Option<Vec<&'a str>>,
         cursor: Option<CursorType>,
-    ) -> Self {
-        let journal: Journal = journal::OpenOptions::default()
-            .all_namespaces(true)
-            .open()
-            .expect("Couldn't create new Journal");
-        Self {
+        boot: Option<i32>,
+        source: LogSource,
+    ) -> Result<Self> {
+        let journal = open_journal()?;
+        let (ev_type, ev_kinds) = split_event_type_filter(ev_type);
+        let cursor = cursor.map(|c| c.for_service(service_name)).transpose()?;
+        Ok(Self {
             cursor,
             service_name,
             tx,
@@ -296,12 +877,81 @@ impl<'a> ParserFuncArgs<'a> {
             processlogtype,
             filter,
             ev_type,
+            ev_kinds,
+            field_filters,
+            match_fields,
             journal: Rc::new(Mutex::new(journal)),
-        }
+            boot,
+            source,
+            time_range: TimeRange::default(),
+            cursor_stale: Rc::new(Cell::new(false)),
+            entries_read: Rc::new(Cell::new(0)),
+        })
+    }
+
+    // Only honoured by initial reads, paging follows its cursor
+    pub fn with_time_range(mut self, time_range: TimeRange) -> Self {
+        self.time_range = time_range;
+        self
+    }
+
+    // Set when the request's cursor no longer points at anything and reading started over
+    // from the head (older) or tail (previous) instead
+    pub fn cursor_stale(&self) -> Rc<Cell<bool>> {
+        self.cursor_stale.clone()
+    }
+
+    // Entries read up to the last event sent, including the ones no rule turned into an
+    // event, for rates over everything that was scanned
+    pub fn entries_read(&self) -> Rc<Cell<usize>> {
+        self.entries_read.clone()
+    }
+}
+
+// Whether the cursor's entry has been vacuumed out of the journal. Checked before any
+// matches are added, so an entry that is still there but filtered out doesn't count
+fn journal_cursor_stale(journal: &mut Journal, cursor: &str) -> Result<bool> {
+    journal.seek_cursor(cursor)?;
+    if journal.next_entry()?.is_none() {
+        return Ok(true);
     }
+    Ok(!journal.test_cursor(cursor)?)
 }
 
-pub static MANUAL_PARSE_EVENTS: Lazy<Vec<&'static str>> = Lazy::new(|| vec!["pkgmanager.events"]);
+// Services parsed from a flat file only, with a ParserFnForManual
+pub static MANUAL_PARSE_EVENTS: Lazy<Vec<&'static str>> = Lazy::new(|| {
+    let mut names: Vec<&'static str> = get_service_configs()
+        .into_iter()
+        .filter(|(_, config)| matches!(config.parser, ParserFunctionType::ParserFnForManual(_)))
+        .map(|(name, _)| name)
+        .collect();
+    names.sort();
+    names
+});
+
+pub const SECURITY_SERVICES: [&str; 4] = [
+    "sshd.events",
+    "sudo.events",
+    "login.events",
+    "userchange.events",
+];
+
+type MatchedParser = (Vec<(&'static str, &'static str)>, ParserFn);
+
+static SECURITY_PARSERS: Lazy<Vec<MatchedParser>> = Lazy::new(|| {
+    let configs = get_service_configs();
+    SECURITY_SERVICES
+        .iter()
+        .filter(|name| crate::config::config().service_enabled(name))
+        .filter_map(|name| {
+            let config = configs.get(name)?;
+            let ParserFunctionType::ParserFn(parserfn) = config.parser else {
+                return None;
+            };
+            Some((config.matches.clone().unwrap_or_default(), parserfn))
+        })
+        .collect()
+});
 
 macro_rules! handle_services {
     (
@@ -318,7 +968,7 @@ macro_rules! handle_services {
                     $cursor
                 ),
             )*
-            _ => Ok(String::new()),
+            _ => Err(DrashtaError::unknown_service(service_name).into()),
         };
 
         result
@@ -326,10 +976,14 @@ macro_rules! handle_services {
 }
 
 pub fn parse_sshd_logs(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
-    let timestamp = entry_map
-        .get("SYSLOG_TIMESTAMP")
-        .cloned()
-        .unwrap_or_default();
+    let timestamp = entry_timestamp(&entry_map);
+
+    let s = entry_map.get("MESSAGE")?;
+    let candidates = candidate_rules(&SSHD_REGEX, &SSHD_REGEX_SET, s).chain(candidate_rules(
+        &PROTOCOL_MISMATCH,
+        &PROTOCOL_MISMATCH_SET,
+        s,
+    ));
 
     let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
         let names: Vec<&str> = ev_types
@@ -337,23 +991,27 @@ pub fn parse_sshd_logs(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<E
             .flat_map(|&s| str_to_regex_names(s).to_owned())
             .collect();
 
-        SSHD_REGEX
-            .iter()
-            .chain(PROTOCOL_MISMATCH.iter())
+        candidates
             .filter(|(name, _)| names.contains(name))
             .collect()
     } else {
-        SSHD_REGEX.iter().chain(PROTOCOL_MISMATCH.iter()).collect()
+        candidates.collect()
     };
 
     let mut map = AHashMap::new();
-    let s = entry_map.get("MESSAGE")?;
 
     for (name, regex) in filtered_regexes {
         if let Some(caps) = regex.captures(s) {
             let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
                 "AUTH_SUCCESS" => (
-                    Some(&[("user", 2), ("ip", 3), ("port", 4), ("method", 1)]),
+                    Some(&[
+                        ("user", 2),
+                        ("ip", 3),
+                        ("port", 4),
+                        ("method", 1),
+                        ("key_type", 5),
+                        ("key_fingerprint", 6),
+                    ]),
                     EventType::Auth(AuthEvent::Success),
                 ),
                 "AUTH_FAILURE" => (
@@ -361,7 +1019,7 @@ pub fn parse_sshd_logs(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<E
                     EventType::Auth(AuthEvent::Failure),
                 ),
                 "SESSION_OPENED" => (
-                    Some(&[("user", 1)]),
+                    Some(&[("user", 1), ("uid", 2)]),
                     EventType::Auth(AuthEvent::SessionOpened),
                 ),
                 "SESSION_CLOSED" => (
@@ -372,6 +1030,30 @@ pub fn parse_sshd_logs(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<E
                     Some(&[("user", 1), ("ip", 2), ("port", 3)]),
                     EventType::Auth(AuthEvent::ConnectionClosed),
                 ),
+                "PREAUTH_DISCONNECT" => (
+                    Some(&[("user", 1), ("ip", 2), ("port", 3)]),
+                    EventType::Auth(AuthEvent::ConnectionClosed),
+                ),
+                "MAXSTARTUPS_BEGIN" => (None, EventType::Auth(AuthEvent::ConnectionThrottled)),
+                "MAXSTARTUPS_END" => (
+                    Some(&[("duration", 1), ("dropped", 2)]),
+                    EventType::Auth(AuthEvent::ConnectionThrottled),
+                ),
+                "MAXSTARTUPS_DROP" => (
+                    Some(&[
+                        ("connection", 1),
+                        ("ip", 2),
+                        ("port", 3),
+                        ("local_ip", 4),
+                        ("local_port", 5),
+                        ("reason", 6),
+                    ]),
+                    EventType::Auth(AuthEvent::ConnectionThrottled),
+                ),
+                "PREAUTH_TIMEOUT" => (
+                    Some(&[("ip", 1), ("port", 2)]),
+                    EventType::Auth(AuthEvent::ConnectionThrottled),
+                ),
                 "WARNING" => (Some(&[("msg", 1)]), EventType::Auth(AuthEvent::Warning)),
                 "TOO_MANY_AUTH" => (
                     Some(&[("user", 1)]),
@@ -387,6 +1069,20 @@ pub fn parse_sshd_logs(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<E
                     }
                 }
             }
+            match *name {
+                "MAXSTARTUPS_BEGIN" => {
+                    map.insert("throttling".to_string(), "started".to_string());
+                }
+                "MAXSTARTUPS_END" => {
+                    map.insert("throttling".to_string(), "ended".to_string());
+                }
+                "CONNECTION_CLOSED" | "PREAUTH_DISCONNECT"
+                    if s.trim_end().ends_with("[preauth]") =>
+                {
+                    map.insert("preauth".to_string(), "true".to_string());
+                }
+                _ => {}
+            }
 
             return Some(EventData {
                 timestamp,
@@ -394,6 +1090,7 @@ pub fn parse_sshd_logs(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<E
                 data: map,
                 event_type,
                 raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
             });
         }
     }
@@ -420,37 +1117,25 @@ pub fn parse_sudo_login_attempts(
 
     let mut map = AHashMap::new();
     if let Some(s) = entry_map.get("MESSAGE") {
-        let mut timestamp = String::new();
-        if let Some(tp) = entry_map.get("SYSLOG_TIMESTAMP") {
-            timestamp = tp.to_owned();
-        }
+        let timestamp = entry_timestamp(&entry_map);
         let trim_msg = s.trim();
 
         for (name, regex) in filtered_regexes.iter() {
             if let Some(msg) = regex.captures(trim_msg) {
-                let (data, _): (Option<&[(&str, usize)]>, EventType) = match *name {
+                let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
                     "COMMAND_RUN" => (
                         Some(&[
                             ("invoking_user", 1),
                             ("tty", 2),
                             ("pwd", 3),
                             ("target_user", 4),
-                            ("command", 5),
+                            ("target_group", 5),
+                            ("command", 6),
+                            ("binary", 7),
                         ]),
-                        EventType::Auth(AuthEvent::Info),
+                        EventType::Auth(AuthEvent::CommandRun),
                     ),
-                    _ => (None, EventType::Auth(AuthEvent::Other)),
-                };
-                if let Some(fields) = data {
-                    for &(name, idx) in fields {
-                        if let Some(m) = msg.get(idx) {
-                            map.insert(name.to_string(), m.as_str().to_string());
-                        }
-                    }
-                }
-            }
-            if let Some(msg) = regex.captures(trim_msg) {
-                let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+
                     "SESSION_OPENED_SU" => (
                         Some(&[
                             ("target_user", 1),
@@ -519,10 +1204,10 @@ pub fn parse_sudo_login_attempts(
                         }
                     }
                 }
-                if *name == "AUTH_ERROR" {
-                    if let Some(user) = msg.get(2) {
-                        map.insert("user".to_string(), user.as_str().to_string());
-                    }
+                if *name == "AUTH_ERROR"
+                    && let Some(user) = msg.get(2)
+                {
+                    map.insert("user".to_string(), user.as_str().to_string());
                 }
 
                 return Some(EventData {
@@ -531,6 +1216,7 @@ pub fn parse_sudo_login_attempts(
                     data: map,
                     event_type,
                     raw_msg: RawMsgType::Structured(entry_map),
+                    matched_rule: Some(name.to_string()),
                 });
             }
         }
@@ -539,6 +1225,19 @@ pub fn parse_sudo_login_attempts(
     None
 }
 
+// gdm logs PAM lines as `gdm-password]`, `gdm-autologin]` and so on
+fn display_manager(syslog_identifier: &str) -> Option<&'static str> {
+    if syslog_identifier.starts_with("gdm") {
+        Some("gdm")
+    } else if syslog_identifier.starts_with("lightdm") {
+        Some("lightdm")
+    } else if syslog_identifier.starts_with("sddm") {
+        Some("sddm")
+    } else {
+        None
+    }
+}
+
 pub fn parse_login_attempts(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
     let mut map = AHashMap::new();
     let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
@@ -555,103 +1254,118 @@ pub fn parse_login_attempts(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Opt
         LOGIN_REGEXES.iter().collect()
     };
 
+    let timestamp = entry_timestamp(&entry_map);
     for (name, regex) in filtered_regexes.iter() {
-        let journal_timestamp = entry_map
-            .get("_SOURCE_REALTIME_TIMESTAMP")
-            .cloned()
-            .unwrap_or_default();
-        let timestamp = format_syslog_timestamp(&journal_timestamp);
+        if let Some(s) = entry_map.get("MESSAGE")
+            && let Some(msg) = regex.captures(s)
+        {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "AUTH_FAILURE" => (None, EventType::Auth(AuthEvent::Failure)),
 
-        if let Some(s) = entry_map.get("MESSAGE") {
-            if let Some(msg) = regex.captures(s) {
-                let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
-                    "AUTH_FAILURE" => (None, EventType::Auth(AuthEvent::Failure)),
+                "AUTH_USER_UNKNOWN" | "ACCOUNT_EXPIRED" => (None, EventType::Auth(AuthEvent::Info)),
+                "FAILL0CK" => (Some(&[("msg", 1)]), EventType::Auth(AuthEvent::Info)),
+                "FAILLOCK_LOCKED" => (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Failure)),
+                "FAILLOCK_LOCKED_COUNT" => (
+                    Some(&[("user", 1), ("failures", 2)]),
+                    EventType::Auth(AuthEvent::Failure),
+                ),
 
-                    "AUTH_USER_UNKNOWN" | "FAILL0CK" | "ACCOUNT_EXPIRED" => {
-                        (None, EventType::Auth(AuthEvent::Info))
-                    }
+                "NOLOGIN_REFUSED" => (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Info)),
 
-                    "NOLOGIN_REFUSED" => (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Info)),
+                "SESSION_OPENED" => (
+                    Some(&[("user", 1)]),
+                    EventType::Auth(AuthEvent::SessionOpened),
+                ),
+                "SESSION_CLOSED" => (
+                    Some(&[("user", 1)]),
+                    EventType::Auth(AuthEvent::SessionClosed),
+                ),
+                "SYSTEMD_NEW_SESSION" => (
+                    Some(&[("user", 1)]),
+                    EventType::Auth(AuthEvent::SessionOpened),
+                ),
+                "SYSTEMD_SESSION_CLOSED" => (None, EventType::Auth(AuthEvent::SessionClosed)),
 
-                    "SESSION_OPENED" => (
-                        Some(&[("user", 1)]),
-                        EventType::Auth(AuthEvent::SessionOpened),
-                    ),
-                    "SESSION_CLOSED" => (
-                        Some(&[("user", 1)]),
-                        EventType::Auth(AuthEvent::SessionClosed),
-                    ),
-                    "SYSTEMD_NEW_SESSION" => (
-                        Some(&[("user", 1)]),
-                        EventType::Auth(AuthEvent::SessionOpened),
-                    ),
-                    "SYSTEMD_SESSION_CLOSED" => (None, EventType::Auth(AuthEvent::SessionClosed)),
+                "SYSTEMD_SESSION_OPENED_UID" => (
+                    Some(&[("user", 1)]),
+                    EventType::Auth(AuthEvent::SessionOpened),
+                ),
+                "SYSTEMD_SESSION_CLOSED_UID" => (
+                    Some(&[("user", 1)]),
+                    EventType::Auth(AuthEvent::SessionClosed),
+                ),
 
-                    "SYSTEMD_SESSION_OPENED_UID" => (
-                        Some(&[("user", 1)]),
-                        EventType::Auth(AuthEvent::SessionOpened),
-                    ),
-                    "SYSTEMD_SESSION_CLOSED_UID" => (
-                        Some(&[("user", 1)]),
-                        EventType::Auth(AuthEvent::SessionClosed),
-                    ),
+                "LOGIN_SUCCESS" => (
+                    Some(&[("tty", 1), ("user", 2)]),
+                    EventType::Auth(AuthEvent::Success),
+                ),
 
-                    "LOGIN_SUCCESS" => (
-                        Some(&[("tty", 1), ("user", 2)]),
-                        EventType::Auth(AuthEvent::Success),
-                    ),
-
-                    "FAILED_LOGIN" => (None, EventType::Auth(AuthEvent::Failure)),
-                    "FAILED_LOGIN_TTY" => (
-                        Some(&[("tty", 1), ("user", 2)]),
-                        EventType::Auth(AuthEvent::Failure),
-                    ),
+                "FAILED_LOGIN" => (None, EventType::Auth(AuthEvent::Failure)),
+                "FAILED_LOGIN_TTY" => (
+                    Some(&[("tty", 1), ("user", 2)]),
+                    EventType::Auth(AuthEvent::Failure),
+                ),
 
-                    "SDDM_LOGIN_SUCCESS" => {
-                        (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Success))
-                    }
-                    "SDDM_LOGIN_FAILURE" => {
-                        (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Failure))
-                    }
+                "SDDM_LOGIN_SUCCESS" => (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Success)),
+                "SDDM_LOGIN_FAILURE" => (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Failure)),
+                "DM_SESSION_STARTED" => (
+                    Some(&[("session_type", 1), ("command", 2)]),
+                    EventType::Auth(AuthEvent::Info),
+                ),
 
-                    "FAILED_PASSWORD_SSH" => {
-                        (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Failure))
-                    }
-                    "INVALID_USER_ATTEMPT" => {
-                        (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Failure))
-                    }
-                    "ACCOUNT_LOCKED" => (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Failure)),
-                    "PASSWORD_CHANGED" => (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Info)),
+                "FAILED_PASSWORD_SSH" => {
+                    (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Failure))
+                }
+                "INVALID_USER_ATTEMPT" => {
+                    (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Failure))
+                }
+                "ACCOUNT_LOCKED" => (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Failure)),
+                "PASSWORD_CHANGED" => (Some(&[("user", 1)]), EventType::Auth(AuthEvent::Info)),
 
-                    _ => (None, EventType::Auth(AuthEvent::Other)),
-                };
+                _ => (None, EventType::Auth(AuthEvent::Other)),
+            };
 
-                if let Some(fields) = data {
-                    for &(name, idx) in fields {
-                        if let Some(m) = msg.get(idx) {
-                            map.insert(name.to_string(), m.as_str().to_string());
-                        }
+            if let Some(fields) = data {
+                for &(name, idx) in fields {
+                    if let Some(m) = msg.get(idx) {
+                        map.insert(name.to_string(), m.as_str().to_string());
                     }
                 }
-                return Some(EventData {
-                    timestamp,
-                    service: Service::Login,
-                    data: map,
-                    event_type,
-                    raw_msg: RawMsgType::Structured(entry_map),
-                });
             }
+            if matches!(
+                *name,
+                "ACCOUNT_LOCKED" | "FAILLOCK_LOCKED" | "FAILLOCK_LOCKED_COUNT"
+            ) {
+                map.insert("locked".to_string(), "true".to_string());
+            }
+            if let Some(session_type) = map.get_mut("session_type") {
+                *session_type = session_type.to_lowercase();
+            }
+            if let Some(dm) = entry_map
+                .get("SYSLOG_IDENTIFIER")
+                .and_then(|id| display_manager(id))
+            {
+                map.insert("display_manager".to_string(), dm.to_string());
+            }
+            return Some(EventData {
+                timestamp,
+                service: Service::Login,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
         }
     }
     None
 }
 
 pub fn parse_kernel_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
-    let journal_timestamp = entry_map
-        .get("_SOURCE_BOOTTIME_TIMESTAMP")
-        .cloned()
-        .unwrap_or_default();
-    let timestamp = format_syslog_timestamp(&journal_timestamp);
+    // _SOURCE_BOOTTIME_TIMESTAMP counts from boot, not from the epoch
+    let timestamp = entry_timestamp(&entry_map);
+
+    let s = entry_map.get("MESSAGE")?;
+    let candidates = candidate_rules(&KERNEL_REGEX, &KERNEL_REGEX_SET, s);
 
     let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
         let names: Vec<&str> = ev_types
@@ -659,16 +1373,14 @@ pub fn parse_kernel_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Opti
             .flat_map(|&s| str_to_regex_names(s).to_owned())
             .collect();
 
-        KERNEL_REGEX
-            .iter()
+        candidates
             .filter(|(name, _)| names.contains(name))
             .collect()
     } else {
-        KERNEL_REGEX.iter().collect()
+        candidates.collect()
     };
 
     let mut map = AHashMap::new();
-    let s = entry_map.get("MESSAGE")?;
 
     for (name, regex) in filtered_regexes {
         if let Some(caps) = regex.captures(s) {
@@ -678,7 +1390,18 @@ pub fn parse_kernel_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Opti
                     EventType::Kernel(KernelEvent::Panic),
                 ),
                 "OOM_KILL" => (
-                    Some(&[("pid", 1), ("process", 2), ("score", 3)]),
+                    Some(&[
+                        ("pid", 1),
+                        ("process", 2),
+                        ("score", 3),
+                        ("total_vm_kb", 4),
+                        ("anon_rss_kb", 5),
+                        ("file_rss_kb", 6),
+                        ("shmem_rss_kb", 7),
+                        ("uid", 8),
+                        ("pgtables_kb", 9),
+                        ("oom_score_adj", 10),
+                    ]),
                     EventType::Kernel(KernelEvent::OomKill),
                 ),
                 "SEGFAULT" => (
@@ -715,6 +1438,14 @@ pub fn parse_kernel_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Opti
                     Some(&[("device", 1), ("sector", 2), ("operation", 3)]),
                     EventType::Kernel(KernelEvent::DiskError),
                 ),
+                "FS_REMOUNT_RO" | "BTRFS_FORCED_RO" => (
+                    Some(&[("filesystem", 1), ("device", 2), ("msg", 3)]),
+                    EventType::Kernel(KernelEvent::FsReadOnly),
+                ),
+                "XFS_SHUTDOWN" => (
+                    Some(&[("filesystem", 1), ("device", 2), ("msg", 3), ("reason", 4)]),
+                    EventType::Kernel(KernelEvent::FsReadOnly),
+                ),
                 "FS_MOUNT" => (
                     Some(&[("device", 1), ("action", 2), ("details", 3)]),
                     EventType::Kernel(KernelEvent::FsMount),
@@ -723,6 +1454,14 @@ pub fn parse_kernel_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Opti
                     Some(&[("device", 1), ("msg", 2)]),
                     EventType::Kernel(KernelEvent::FsError),
                 ),
+                "THERMAL_THROTTLE_START" => (
+                    Some(&[("cpu", 1), ("sensor", 2), ("throttle_events", 3)]),
+                    EventType::Kernel(KernelEvent::ThermalEvent),
+                ),
+                "THERMAL_THROTTLE_STOP" => (
+                    Some(&[("cpu", 1), ("sensor", 2)]),
+                    EventType::Kernel(KernelEvent::ThermalEvent),
+                ),
                 "CPU_ERROR" => (
                     Some(&[("cpu", 1), ("msg", 2)]),
                     EventType::Kernel(KernelEvent::CpuError),
@@ -743,6 +1482,72 @@ pub fn parse_kernel_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Opti
                     Some(&[("interface", 1), ("old_name", 2), ("speed", 3)]),
                     EventType::Kernel(KernelEvent::NetInterface),
                 ),
+                "NET_SYN_FLOOD" => (
+                    Some(&[("proto", 1), ("port", 2), ("action", 3)]),
+                    EventType::Kernel(KernelEvent::NetStack),
+                ),
+                "NET_CONNTRACK_FULL" => (None, EventType::Kernel(KernelEvent::NetStack)),
+                "NET_MARTIAN_SOURCE" => (
+                    Some(&[("dst_ip", 1), ("ip", 2), ("interface", 3)]),
+                    EventType::Kernel(KernelEvent::NetStack),
+                ),
+                "NET_MARTIAN_DESTINATION" => (
+                    Some(&[("dst_ip", 1), ("ip", 2), ("interface", 3)]),
+                    EventType::Kernel(KernelEvent::NetStack),
+                ),
+                "NET_TCP_PRESSURE" => (
+                    Some(&[("reason", 1)]),
+                    EventType::Kernel(KernelEvent::NetStack),
+                ),
+                "NET_NEIGHBOUR_OVERFLOW" => (
+                    Some(&[("table", 1)]),
+                    EventType::Kernel(KernelEvent::NetStack),
+                ),
+                "MEM_ALLOC_STALL" => (
+                    Some(&[("process", 1), ("stall_ms", 2), ("order", 3)]),
+                    EventType::Kernel(KernelEvent::MemoryPressure),
+                ),
+                "MEM_ALLOC_FAILURE" => (
+                    Some(&[
+                        ("process", 1),
+                        ("order", 2),
+                        ("gfp_mode", 3),
+                        ("gfp_flags", 4),
+                    ]),
+                    EventType::Kernel(KernelEvent::MemoryPressure),
+                ),
+                "ZRAM_ALLOC_FAILURE" => (
+                    Some(&[("resource", 1), ("page", 2), ("size", 3)]),
+                    EventType::Kernel(KernelEvent::MemoryPressure),
+                ),
+                "OOMD_MEMORY_PRESSURE" => (
+                    Some(&[
+                        ("resource", 1),
+                        ("stall_pct", 2),
+                        ("limit_pct", 3),
+                        ("duration", 4),
+                    ]),
+                    EventType::Kernel(KernelEvent::MemoryPressure),
+                ),
+                "OOMD_PRESSURE_KILL" => (
+                    Some(&[
+                        ("killed", 1),
+                        ("resource", 2),
+                        ("stall_pct", 3),
+                        ("limit_pct", 4),
+                        ("duration", 5),
+                    ]),
+                    EventType::Kernel(KernelEvent::OomKill),
+                ),
+                "OOMD_SWAP_KILL" => (
+                    Some(&[
+                        ("killed", 1),
+                        ("swap_used", 2),
+                        ("swap_total", 3),
+                        ("limit_pct", 4),
+                    ]),
+                    EventType::Kernel(KernelEvent::OomKill),
+                ),
                 "PCI_DEVICE" => (
                     Some(&[("device", 1), ("msg", 2)]),
                     EventType::Kernel(KernelEvent::PciDevice),
@@ -814,6 +1619,28 @@ pub fn parse_kernel_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Opti
                     }
                 }
             }
+            if let Some(sensor) = map.get_mut("sensor") {
+                *sensor = sensor.to_lowercase();
+            }
+            if let Some(fs) = map.get_mut("filesystem") {
+                *fs = fs.to_lowercase();
+            }
+            match *name {
+                "THERMAL_THROTTLE_START" => {
+                    map.insert("throttled".to_string(), "true".to_string());
+                }
+                "THERMAL_THROTTLE_STOP" => {
+                    map.insert("throttled".to_string(), "false".to_string());
+                }
+                // XFS has no read-only mode, it shuts the filesystem down instead
+                "FS_REMOUNT_RO" | "BTRFS_FORCED_RO" => {
+                    map.insert("state".to_string(), "read-only".to_string());
+                }
+                "XFS_SHUTDOWN" => {
+                    map.insert("state".to_string(), "shutdown".to_string());
+                }
+                _ => {}
+            }
 
             return Some(EventData {
                 timestamp,
@@ -821,6 +1648,7 @@ pub fn parse_kernel_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Opti
                 data: map,
                 event_type,
                 raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
             });
         }
     }
@@ -851,10 +1679,7 @@ pub fn parse_user_change_events(entry_map: Entry, ev_type: Option<Vec<&str>>) ->
     };
 
     let mut map = AHashMap::new();
-    let mut timestamp = String::new();
-    if let Some(tp) = entry_map.get("SYSLOG_TIMESTAMP") {
-        timestamp = tp.to_owned();
-    }
+    let timestamp = entry_timestamp(&entry_map);
 
     if let Some(msg) = entry_map.get("MESSAGE") {
         for (name, regex) in filtered_regexes.iter() {
@@ -882,30 +1707,6 @@ pub fn parse_user_change_events(entry_map: Entry, ev_type: Option<Vec<&str>>) ->
                     "GROUP_ADDED_ETC_GSHADOW" => {
                         (Some(&[("name", 1)]), EventType::User(UserEvent::Info))
                     }
-                    _ => (None, EventType::User(UserEvent::Other)),
-                };
-
-                if let Some(fields) = data {
-                    for &(name, idx) in fields {
-                        if let Some(m) = s.get(idx) {
-                            map.insert(name.to_string(), m.as_str().to_string());
-                        }
-                    }
-                }
-
-                return Some(EventData {
-                    timestamp,
-                    service: Service::UserChange,
-                    event_type,
-                    data: map,
-                    raw_msg: RawMsgType::Structured(entry_map),
-                });
-            }
-        }
-
-        for (name, regex) in filtered_regexes.iter() {
-            if let Some(s) = regex.captures(msg) {
-                let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
                     "DELETE_USER" => (
                         Some(&[
                             ("name", 1),
@@ -916,52 +1717,45 @@ pub fn parse_user_change_events(entry_map: Entry, ev_type: Option<Vec<&str>>) ->
                         ]),
                         EventType::User(UserEvent::DeleteUser),
                     ),
-                    "DELETE_USER_HOME" => (
-                        Some(&[("name", 1)]),
-                        EventType::User(UserEvent::DeleteGroup),
-                    ),
+                    "DELETE_USER_HOME" => {
+                        (Some(&[("name", 1)]), EventType::User(UserEvent::DeleteUser))
+                    }
                     "DELETE_USER_MAIL" => (Some(&[("name", 1)]), EventType::User(UserEvent::Info)),
                     "DELETE_GROUP" => (
                         Some(&[("name", 1), ("gid", 2)]),
                         EventType::User(UserEvent::DeleteGroup),
                     ),
-                    _ => (None, EventType::User(UserEvent::Other)),
-                };
-
-                if let Some(fields) = data {
-                    for &(name, idx) in fields {
-                        if let Some(m) = s.get(idx) {
-                            map.insert(name.to_string(), m.as_str().to_string());
-                        }
-                    }
-                }
-
-                return Some(EventData {
-                    timestamp,
-                    service: Service::UserChange,
-                    event_type,
-                    data: map,
-                    raw_msg: RawMsgType::Structured(entry_map),
-                });
-            }
-        }
-
-        for (name, regex) in filtered_regexes.iter() {
-            if let Some(s) = regex.captures(msg) {
-                let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
                     "MODIFY_USER" => (Some(&[("name", 1)]), EventType::User(UserEvent::ModifyUser)),
+                    "USERMOD_GROUP_MEMBER" => (
+                        Some(&[("name", 2), ("group", 4)]),
+                        EventType::User(UserEvent::ModifyUser),
+                    ),
+                    "USERMOD_RENAME" => (
+                        Some(&[("name", 2), ("from", 1), ("to", 2)]),
+                        EventType::User(UserEvent::ModifyUser),
+                    ),
+                    "USERMOD_CHANGE" => (
+                        Some(&[("name", 1), ("from", 3), ("to", 4)]),
+                        EventType::User(UserEvent::ModifyUser),
+                    ),
+                    "USERMOD_PASSWORD" => {
+                        (Some(&[("name", 2)]), EventType::User(UserEvent::ModifyUser))
+                    }
+                    "GROUPMOD_CHANGE" => (
+                        Some(&[("name", 2), ("gid", 3)]),
+                        EventType::User(UserEvent::ModifyGroup),
+                    ),
                     "MODIFY_GROUP" => (
                         Some(&[("name", 1)]),
-                        EventType::User(UserEvent::DeleteGroup),
+                        EventType::User(UserEvent::ModifyGroup),
                     ),
                     "USER_PASSWD_CHANGE" => (
                         Some(&[("process_id", 1), ("user", 2)]),
-                        EventType::User(UserEvent::Info),
-                    ),
-                    "USER_SHADOW_UPDATED" => (
-                        Some(&[("name", 1)]),
-                        EventType::User(UserEvent::DeleteGroup),
+                        EventType::User(UserEvent::PasswdChange),
                     ),
+                    "USER_SHADOW_UPDATED" => {
+                        (Some(&[("name", 1)]), EventType::User(UserEvent::Info))
+                    }
                     _ => (None, EventType::User(UserEvent::Other)),
                 };
 
@@ -972,6 +1766,7 @@ pub fn parse_user_change_events(entry_map: Entry, ev_type: Option<Vec<&str>>) ->
                         }
                     }
                 }
+                usermod_details(name, &s, &mut map);
 
                 return Some(EventData {
                     timestamp,
@@ -979,6 +1774,7 @@ pub fn parse_user_change_events(entry_map: Entry, ev_type: Option<Vec<&str>>) ->
                     event_type,
                     data: map,
                     raw_msg: RawMsgType::Structured(entry_map),
+                    matched_rule: Some(name.to_string()),
                 });
             }
         }
@@ -986,6 +1782,43 @@ pub fn parse_user_change_events(entry_map: Entry, ev_type: Option<Vec<&str>>) ->
     None
 }
 
+// usermod and groupmod log one line per change; action names the change so group
+// membership, renames and locks can be told apart without re-reading the message
+fn usermod_details(rule: &str, caps: &Captures, map: &mut AHashMap<String, String>) {
+    let action = match rule {
+        "USERMOD_GROUP_MEMBER" => {
+            if caps.get(3).is_some() {
+                map.insert("shadow".to_string(), "true".to_string());
+            }
+            match &caps[1] {
+                "add" => "add_to_group".to_string(),
+                _ => "remove_from_group".to_string(),
+            }
+        }
+        "USERMOD_RENAME" => "rename".to_string(),
+        "USERMOD_CHANGE" => format!("change_{}", caps[2].to_lowercase()),
+        "USERMOD_PASSWORD" => match &caps[1] {
+            "change" => "change_password".to_string(),
+            action => action.to_string(),
+        },
+        "GROUPMOD_CHANGE" => {
+            if let Some(new_name) = caps.get(4) {
+                map.insert("from".to_string(), caps[2].to_string());
+                map.insert("to".to_string(), new_name.as_str().to_string());
+                "rename".to_string()
+            } else if let Some(new_gid) = caps.get(5) {
+                map.insert("from".to_string(), caps[3].to_string());
+                map.insert("to".to_string(), new_gid.as_str().to_string());
+                "change_gid".to_string()
+            } else {
+                "change_password".to_string()
+            }
+        }
+        _ => return,
+    };
+    map.insert("action".to_string(), action);
+}
+
 pub fn parse_pkg_events(content: String, ev_type: Option<Vec<&str>>) -> Option<EventData> {
     let mut map = AHashMap::new();
     let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
@@ -1044,12 +1877,83 @@ pub fn parse_pkg_events(content: String, ev_type: Option<Vec<&str>>) -> Option<E
                 event_type,
                 data: map,
                 raw_msg: RawMsgType::Plain(content),
+                matched_rule: Some(name.to_string()),
             });
         }
     }
     None
 }
 
+// CLF timestamps look like `10/Oct/2000:13:55:36 -0700`; convert them to the
+// local time format the journal parsers use
+fn format_clf_timestamp(ts: &str) -> String {
+    DateTime::parse_from_str(ts, "%d/%b/%Y:%H:%M:%S %z")
+        .map(|dt| {
+            dt.with_timezone(&Local)
+                .format("%b %e %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|_| ts.to_string())
+}
+
+pub fn parse_access_log(content: String, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let s = CLF_REGEX.captures(&content)?;
+    let (name, event_type) = match s[8].as_bytes()[0] {
+        b'2' => ("HTTP_SUCCESS", EventType::Http(HttpEvent::Success)),
+        b'3' => ("HTTP_REDIRECT", EventType::Http(HttpEvent::Redirect)),
+        b'4' => ("HTTP_CLIENT_ERROR", EventType::Http(HttpEvent::ClientError)),
+        b'5' => ("HTTP_SERVER_ERROR", EventType::Http(HttpEvent::ServerError)),
+        _ => ("HTTP_OTHER", EventType::Http(HttpEvent::Other)),
+    };
+    if let Some(ev_types) = ev_type
+        && !ev_types
+            .iter()
+            .any(|&t| str_to_regex_names(t).contains(&name))
+    {
+        return None;
+    }
+
+    let timestamp = format_clf_timestamp(&s[3]);
+    let fields: &[(&str, usize)] = &[
+        ("ip", 1),
+        ("user", 2),
+        ("request", 4),
+        ("method", 5),
+        ("path", 6),
+        ("protocol", 7),
+        ("status", 8),
+        ("bytes", 9),
+        ("referer", 10),
+        ("user_agent", 11),
+    ];
+    let mut map = AHashMap::new();
+    for &(name, idx) in fields {
+        if let Some(m) = s.get(idx)
+            && m.as_str() != "-"
+        {
+            map.insert(name.to_string(), m.as_str().to_string());
+        }
+    }
+
+    Some(EventData {
+        timestamp,
+        service: Service::AccessLog,
+        event_type,
+        data: map,
+        raw_msg: RawMsgType::Plain(content),
+        matched_rule: Some(name.to_string()),
+    })
+}
+
+// Groups that didn't take part in the match are left out rather than stored as empty
+fn insert_captures(map: &mut AHashMap<String, String>, caps: &Captures, fields: &[(&str, usize)]) {
+    for &(name, idx) in fields {
+        if let Some(m) = caps.get(idx) {
+            map.insert(name.to_string(), m.as_str().to_string());
+        }
+    }
+}
+
 pub fn parse_config_change_events(
     entry_map: Entry,
     ev_type: Option<Vec<&str>>,
@@ -1069,10 +1973,7 @@ pub fn parse_config_change_events(
     };
 
     let mut map = AHashMap::new();
-    let mut timestamp = String::new();
-    if let Some(tp) = entry_map.get("SYSLOG_TIMESTAMP") {
-        timestamp = tp.trim().to_owned();
-    }
+    let timestamp = entry_timestamp(&entry_map);
 
     for (name, regex) in filtered_regexes.iter() {
         if let Some(s) = entry_map.get("MESSAGE") {
@@ -1112,12 +2013,7 @@ pub fn parse_config_change_events(
                 };
 
                 if let Some(data) = fields {
-                    for &(fields, idx) in data {
-                        map.insert(
-                            fields.to_string(),
-                            msg.get(idx).unwrap().as_str().to_string(),
-                        );
-                    }
+                    insert_captures(&mut map, &msg, data);
                 }
 
                 return Some(EventData {
@@ -1126,6 +2022,7 @@ pub fn parse_config_change_events(
                     event_type,
                     data: map,
                     raw_msg: RawMsgType::Structured(entry_map),
+                    matched_rule: Some(name.to_string()),
                 });
             }
         }
@@ -1133,14 +2030,110 @@ pub fn parse_config_change_events(
     None
 }
 
-fn format_syslog_timestamp(ts_str: &str) -> String {
+#[derive(Clone, Copy, Debug)]
+pub enum TimestampUnit {
+    Seconds,
+    Millis,
+    Micros,
+}
+
+pub fn entry_datetime(entry_map: &Entry) -> Option<DateTime<Local>> {
+    if let Some(ts) = entry_map.get("_SOURCE_REALTIME_TIMESTAMP")
+        && let Ok(value) = ts.parse::<i64>()
+    {
+        return Local.timestamp_micros(value).single();
+    }
+
+    let received = entry_map
+        .get("__REALTIME_TIMESTAMP")
+        .and_then(|ts| ts.parse::<i64>().ok())
+        .and_then(|value| Local.timestamp_micros(value).single());
+
+    // Kernel and native journal entries have no SYSLOG_TIMESTAMP, their time is when
+    // journald received them
+    match entry_map.get("SYSLOG_TIMESTAMP") {
+        Some(syslog_ts) => syslog_datetime(syslog_ts.trim(), received.unwrap_or_else(Local::now)),
+        None => received,
+    }
+}
+
+// SYSLOG_TIMESTAMP carries no year, so borrow it from when journald received the
+// entry, stepping back a year when that would put the event after its receipt
+// (logged on Dec 31, received on Jan 1).
+fn syslog_datetime(ts: &str, received: DateTime<Local>) -> Option<DateTime<Local>> {
+    let at_year = |year: i32| {
+        NaiveDateTime::parse_from_str(&format!("{year} {ts}"), "%Y %b %e %H:%M:%S")
+            .ok()
+            .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+    };
+
+    let dt = at_year(received.year())?;
+    if dt > received + chrono::Duration::days(1) {
+        return at_year(received.year() - 1);
+    }
+    Some(dt)
+}
+
+// Wall clock time of an event: the journal's own timestamp, or for file lines the parsed
+// timestamp (pacman's ISO 8601, its older "YYYY-MM-DD HH:MM" form, or the syslog form
+// access log times are normalized to)
+pub fn event_datetime(ev: &EventData) -> Option<DateTime<Local>> {
+    if let RawMsgType::Structured(entry) = &ev.raw_msg {
+        return entry_datetime(entry);
+    }
+
+    let ts = ev.timestamp.trim();
+    DateTime::parse_from_str(ts, "%Y-%m-%dT%H:%M:%S%z")
+        .map(|dt| dt.with_timezone(&Local))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M")
+                .ok()
+                .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+        })
+        .or_else(|| syslog_datetime(ts, Local::now()))
+}
+
+fn entry_timestamp(entry_map: &Entry) -> String {
+    match entry_datetime(entry_map) {
+        Some(datetime) => datetime.format("%b %e %H:%M:%S").to_string(),
+        None => "invalid".into(),
+    }
+}
+
+pub const SEVERITIES: [&str; 8] = [
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+// Tag events with the syslog severity from the entry's PRIORITY, so even lines no
+// rule could structure have something to sort and colour by
+fn with_severity(mut ev: EventData) -> EventData {
+    if let RawMsgType::Structured(entry) = &ev.raw_msg
+        && let Some(severity) = entry
+            .get("PRIORITY")
+            .and_then(|p| p.trim().parse::<usize>().ok())
+            .and_then(|p| SEVERITIES.get(p))
+    {
+        ev.data
+            .entry("severity".to_string())
+            .or_insert_with(|| severity.to_string());
+    }
+    ev
+}
+
+fn with_receive_time(journal: &Journal, mut data: JournalRecord) -> JournalRecord {
+    if let Ok(usec) = journal.timestamp_usec() {
+        data.insert("__REALTIME_TIMESTAMP".to_string(), usec.to_string());
+    }
+    data
+}
+
+pub fn format_syslog_timestamp(ts_str: &str, unit: TimestampUnit) -> String {
     if let Ok(value) = ts_str.parse::<i64>() {
-        let dt: Option<DateTime<Local>> = if value > 1_000_000_000_000_000 {
-            Local.timestamp_micros(value).single()
-        } else if value > 10_000_000_000 {
-            Local.timestamp_millis_opt(value).single()
-        } else {
-            Local.timestamp_opt(value, 0).single()
+        let dt: Option<DateTime<Local>> = match unit {
+            TimestampUnit::Micros => Local.timestamp_micros(value).single(),
+            TimestampUnit::Millis => Local.timestamp_millis_opt(value).single(),
+            TimestampUnit::Seconds => Local.timestamp_opt(value, 0).single(),
         };
 
         if let Some(datetime) = dt {
@@ -1152,29 +2145,61 @@ fn format_syslog_timestamp(ts_str: &str) -> String {
         "invalid".into()
     }
 }
+
+// NetworkManager's VPN plugin states, reported as the connection state a user would name
+fn vpn_state(plugin_state: &str) -> Option<&'static str> {
+    match plugin_state {
+        "starting" => Some("connecting"),
+        "started" => Some("connected"),
+        "stopping" => Some("disconnecting"),
+        "stopped" => Some("disconnected"),
+        _ => None,
+    }
+}
+
+fn vpn_details(map: &mut AHashMap<String, String>) {
+    let Some(msg) = map.get("msg").cloned() else {
+        return;
+    };
+    if let Some(caps) = VPN_TYPE_REGEX.captures(&msg) {
+        map.insert("vpn_type".to_string(), caps[1].to_string());
+    }
+    if let Some(caps) = VPN_STATE_REGEX.captures(&msg) {
+        if let Some(state) = vpn_state(&caps[1]) {
+            map.insert("state".to_string(), state.to_string());
+        }
+        map.insert("plugin_state".to_string(), caps[1].to_string());
+    }
+    if let Some(caps) = VPN_FAILURE_REGEX.captures(&msg) {
+        map.insert("state".to_string(), "failed".to_string());
+        map.insert("reason".to_string(), caps[1].to_string());
+    }
+}
+
 pub fn parse_network_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let s = entry_map.get("MESSAGE")?;
+    let candidates = candidate_rules(&NETWORK_REGEX, &NETWORK_REGEX_SET, s);
+
     let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
         let names: Vec<&str> = ev_types
             .iter()
             .flat_map(|&s| str_to_regex_names(s).to_owned())
             .collect();
 
-        NETWORK_REGEX
-            .iter()
+        candidates
             .filter(|(name, _)| names.contains(name))
             .collect()
     } else {
-        NETWORK_REGEX.iter().collect()
+        candidates.collect()
     };
 
     let mut map = AHashMap::new();
-    let s = entry_map.get("MESSAGE")?;
 
     let journal_timestamp = entry_map
         .get("_SOURCE_REALTIME_TIMESTAMP")
         .cloned()
         .unwrap_or_default();
-    let timestamp = format_syslog_timestamp(&journal_timestamp);
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
 
     for (name, regex) in filtered_regexes {
         if let Some(caps) = regex.captures(s) {
@@ -1279,7 +2304,7 @@ pub fn parse_network_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Opt
                 ),
 
                 "VPN_EVENT" => (
-                    Some(&[("msg", 1)]),
+                    Some(&[("uuid", 3), ("connection", 4), ("msg", 5)]),
                     EventType::Network(NetworkEvent::VpnEvent),
                 ),
 
@@ -1348,7 +2373,7 @@ pub fn parse_network_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Opt
                 ),
 
                 "VPN_ERROR" => (
-                    Some(&[("msg", 1)]),
+                    Some(&[("uuid", 3), ("connection", 4), ("msg", 5)]),
                     EventType::Network(NetworkEvent::VpnEvent),
                 ),
 
@@ -1372,22 +2397,27 @@ pub fn parse_network_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Opt
                 }
             }
 
+            if matches!(*name, "VPN_EVENT" | "VPN_ERROR") {
+                vpn_details(&mut map);
+            }
+
             return Some(EventData {
                 timestamp,
                 service: Service::NetworkManager,
                 data: map,
                 event_type,
                 raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
             });
         }
     }
     None
 }
 pub fn parse_firewalld_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
-    let timestamp = entry_map
-        .get("SYSLOG_TIMESTAMP")
-        .cloned()
-        .unwrap_or_default();
+    let timestamp = entry_timestamp(&entry_map);
+
+    let s = entry_map.get("MESSAGE")?;
+    let candidates = candidate_rules(&FIREWALLD_REGEX, &FIREWALLD_REGEX_SET, s);
 
     let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
         let names: Vec<&str> = ev_types
@@ -1395,16 +2425,14 @@ pub fn parse_firewalld_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> O
             .flat_map(|&s| str_to_regex_names(s).to_owned())
             .collect();
 
-        FIREWALLD_REGEX
-            .iter()
+        candidates
             .filter(|(name, _)| names.contains(name))
             .collect()
     } else {
-        FIREWALLD_REGEX.iter().collect()
+        candidates.collect()
     };
 
     let mut map = AHashMap::new();
-    let s = entry_map.get("MESSAGE")?;
 
     for (name, regex) in filtered_regexes {
         if let Some(caps) = regex.captures(s) {
@@ -1484,95 +2512,2174 @@ pub fn parse_firewalld_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> O
                 data: map,
                 event_type,
                 raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
             });
         }
     }
     None
 }
 
-pub fn get_service_configs() -> AHashMap<&'static str, ServiceConfig> {
-    let mut map = AHashMap::new();
-    map.insert(
-        "pkgmanager.events",
-        ServiceConfig {
-            matches: None,
-            parser: ParserFunctionType::ParserFnForManual(parse_pkg_events),
-        },
-    );
+pub fn parse_systemd_unit_events(
+    entry_map: Entry,
+    ev_type: Option<Vec<&str>>,
+) -> Option<EventData> {
+    let journal_timestamp = entry_map
+        .get("_SOURCE_REALTIME_TIMESTAMP")
+        .cloned()
+        .unwrap_or_default();
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
 
-    map.insert(
-        "sshd.events",
-        ServiceConfig {
-            matches: Some(vec![
-                ("_COMM", "sshd"),
-                ("_EXE", "/usr/sbin/sshd"),
-                ("_SYSTEMD_UNIT", "sshd.service"),
-            ]),
-            parser: ParserFunctionType::ParserFn(parse_sshd_logs),
-        },
-    );
+    let s = entry_map.get("MESSAGE")?;
+    let candidates = candidate_rules(&SYSTEMD_UNIT_REGEX, &SYSTEMD_UNIT_REGEX_SET, s);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        candidates
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        candidates.collect()
+    };
+
+    let mut map = AHashMap::new();
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, action, event_type): (&[(&str, usize)], &str, EventType) = match *name {
+                "UNIT_STARTED" => (
+                    &[("unit", 1), ("description", 2)],
+                    "started",
+                    EventType::Systemd(SystemdEvent::Started),
+                ),
+                "UNIT_STOPPED" => (
+                    &[("unit", 1), ("description", 2)],
+                    "stopped",
+                    EventType::Systemd(SystemdEvent::Stopped),
+                ),
+                "UNIT_STARTING" => (
+                    &[("unit", 1), ("description", 2)],
+                    "starting",
+                    EventType::Systemd(SystemdEvent::Starting),
+                ),
+                "UNIT_STOPPING" => (
+                    &[("unit", 1), ("description", 2)],
+                    "stopping",
+                    EventType::Systemd(SystemdEvent::Stopping),
+                ),
+                "UNIT_RELOADED" => (
+                    &[("unit", 1), ("description", 2)],
+                    "reloaded",
+                    EventType::Systemd(SystemdEvent::Reloaded),
+                ),
+                "UNIT_START_FAILED" => (
+                    &[("unit", 1), ("description", 2)],
+                    "start_failed",
+                    EventType::Systemd(SystemdEvent::Failed),
+                ),
+                "UNIT_FAILED" => (
+                    &[("unit", 1), ("result", 2)],
+                    "failed",
+                    EventType::Systemd(SystemdEvent::Failed),
+                ),
+                "UNIT_PROCESS_EXITED" => (
+                    &[("unit", 1), ("code", 2), ("status", 3)],
+                    "exited",
+                    EventType::Systemd(SystemdEvent::ProcessExited),
+                ),
+                "UNIT_DEACTIVATED" => (
+                    &[("unit", 1)],
+                    "deactivated",
+                    EventType::Systemd(SystemdEvent::Deactivated),
+                ),
+                _ => (
+                    &[("msg", 1)],
+                    "other",
+                    EventType::Systemd(SystemdEvent::Other),
+                ),
+            };
+
+            for &(fname, idx) in data {
+                if let Some(m) = caps.get(idx) {
+                    map.insert(fname.to_string(), m.as_str().to_string());
+                }
+            }
+            map.insert("action".to_string(), action.to_string());
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Systemd,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+pub fn parse_fail2ban_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let journal_timestamp = entry_map
+        .get("_SOURCE_REALTIME_TIMESTAMP")
+        .cloned()
+        .unwrap_or_default();
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        FAIL2BAN_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        FAIL2BAN_REGEX.iter().collect()
+    };
+
+    let mut map = AHashMap::new();
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "F2B_BAN" => (
+                    Some(&[("level", 1), ("jail", 2), ("ip", 3)]),
+                    EventType::Fail2ban(Fail2banEvent::Ban),
+                ),
+                "F2B_UNBAN" => (
+                    Some(&[("level", 1), ("jail", 2), ("ip", 3)]),
+                    EventType::Fail2ban(Fail2banEvent::Unban),
+                ),
+                "F2B_FOUND" => (
+                    Some(&[("level", 1), ("jail", 2), ("ip", 3), ("found_at", 4)]),
+                    EventType::Fail2ban(Fail2banEvent::Found),
+                ),
+                "F2B_ALREADY_BANNED" => (
+                    Some(&[("level", 1), ("jail", 2), ("ip", 3)]),
+                    EventType::Fail2ban(Fail2banEvent::AlreadyBanned),
+                ),
+                _ => (
+                    Some(&[("msg", 1)]),
+                    EventType::Fail2ban(Fail2banEvent::Other),
+                ),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Fail2ban,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+pub fn parse_timesync_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let journal_timestamp = entry_map
+        .get("_SOURCE_REALTIME_TIMESTAMP")
+        .cloned()
+        .unwrap_or_default();
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        TIMESYNC_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        TIMESYNC_REGEX.iter().collect()
+    };
+
+    let mut map = AHashMap::new();
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "TIME_SYNCED" => (
+                    Some(&[("server", 1), ("port", 2), ("server_name", 3)]),
+                    EventType::Timesync(TimesyncEvent::Synced),
+                ),
+                "CHRONY_SOURCE_SELECTED" => (
+                    Some(&[("server", 1), ("server_name", 2)]),
+                    EventType::Timesync(TimesyncEvent::Synced),
+                ),
+                "CLOCK_STEPPED" => (
+                    Some(&[("offset_secs", 1)]),
+                    EventType::Timesync(TimesyncEvent::Stepped),
+                ),
+                "CLOCK_SLEWED" => (
+                    Some(&[("offset_secs", 1)]),
+                    EventType::Timesync(TimesyncEvent::Slewed),
+                ),
+                "TIME_SERVER_TIMEOUT" => (
+                    Some(&[("server", 1), ("port", 2), ("server_name", 3)]),
+                    EventType::Timesync(TimesyncEvent::Lost),
+                ),
+                "CHRONY_NO_SOURCES" => (
+                    Some(&[("reason", 1)]),
+                    EventType::Timesync(TimesyncEvent::Lost),
+                ),
+                _ => (
+                    Some(&[("msg", 1)]),
+                    EventType::Timesync(TimesyncEvent::Other),
+                ),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Timesync,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+pub fn parse_docker_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let journal_timestamp = entry_map
+        .get("_SOURCE_REALTIME_TIMESTAMP")
+        .cloned()
+        .unwrap_or_default();
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        DOCKER_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        DOCKER_REGEX.iter().collect()
+    };
+
+    let mut map = AHashMap::new();
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "CONTAINER_OOM" => (
+                    Some(&[("container_id", 1), ("container_id", 2)]),
+                    EventType::Container(ContainerEvent::Oom),
+                ),
+                "CONTAINER_START" => (
+                    Some(&[
+                        ("container_id", 1),
+                        ("container_id", 2),
+                        ("container_id", 3),
+                    ]),
+                    EventType::Container(ContainerEvent::Start),
+                ),
+                "CONTAINER_STOP" => (
+                    Some(&[("container_id", 1)]),
+                    EventType::Container(ContainerEvent::Stop),
+                ),
+                "CONTAINER_KILL" => (
+                    Some(&[("container_id", 1), ("timeout_secs", 2), ("signal", 3)]),
+                    EventType::Container(ContainerEvent::Stop),
+                ),
+                "CONTAINER_DIE" => (
+                    Some(&[("container_id", 1), ("container_id", 2)]),
+                    EventType::Container(ContainerEvent::Die),
+                ),
+                "IMAGE_PULLED" => (
+                    Some(&[("image", 1), ("image_ref", 2)]),
+                    EventType::Container(ContainerEvent::ImagePull),
+                ),
+                "DOCKER_DAEMON_LOG" => (
+                    Some(&[("level", 2), ("msg", 3)]),
+                    EventType::Container(ContainerEvent::Other),
+                ),
+                _ => (
+                    Some(&[("msg", 1)]),
+                    EventType::Container(ContainerEvent::Other),
+                ),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Docker,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+// security.events reads the union of the member services' matches from one journal,
+// so entries arrive in journal order and each is handed to the parser of the
+// service it belongs to.
+pub fn parse_security_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let (_, parserfn) = SECURITY_PARSERS.iter().find(|(matches, _)| {
+        matches
+            .iter()
+            .any(|(field, value)| entry_map.get(*field).is_some_and(|v| v == value))
+    })?;
+
+    parserfn(entry_map, ev_type)
+}
+
+pub fn parse_bluetooth_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let journal_timestamp = entry_map
+        .get("_SOURCE_REALTIME_TIMESTAMP")
+        .cloned()
+        .unwrap_or_default();
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
+    let s = entry_map.get("MESSAGE")?;
+    let candidates = candidate_rules(&BLUETOOTH_REGEX, &BLUETOOTH_REGEX_SET, s);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        candidates
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        candidates.collect()
+    };
+
+    let mut map = AHashMap::new();
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "BT_DEVICE_CONNECTED" => (
+                    Some(&[("mac", 1), ("name", 2)]),
+                    EventType::Bluetooth(BluetoothEvent::Connected),
+                ),
+                "BT_DEVICE_DISCONNECTED" => (
+                    Some(&[("mac", 1), ("name", 2), ("reason", 3)]),
+                    EventType::Bluetooth(BluetoothEvent::Disconnected),
+                ),
+                "BT_DEVICE_PAIRED" => (
+                    Some(&[("mac", 1), ("name", 2)]),
+                    EventType::Bluetooth(BluetoothEvent::Paired),
+                ),
+                "BT_BONDING_FAILED" => (
+                    Some(&[("mac", 1), ("error", 2), ("status", 3)]),
+                    EventType::Bluetooth(BluetoothEvent::PairingFailed),
+                ),
+                "BT_PROFILE_CONNECT_FAILED" => (
+                    Some(&[("profile", 1), ("mac", 2), ("error", 3)]),
+                    EventType::Bluetooth(BluetoothEvent::ConnectFailed),
+                ),
+                "BT_AGENT_REQUEST" => (
+                    Some(&[("request", 1), ("mac", 2)]),
+                    EventType::Bluetooth(BluetoothEvent::AgentRequest),
+                ),
+                "BT_NO_AGENT" => (None, EventType::Bluetooth(BluetoothEvent::AgentRequest)),
+                _ => (
+                    Some(&[("msg", 1)]),
+                    EventType::Bluetooth(BluetoothEvent::Other),
+                ),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Bluetooth,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+const APPARMOR_FIELDS: [(&str, &str); 10] = [
+    ("operation", "operation"),
+    ("profile", "profile"),
+    ("name", "path"),
+    ("requested_mask", "requested_mask"),
+    ("denied_mask", "denied_mask"),
+    ("comm", "comm"),
+    ("pid", "pid"),
+    ("fsuid", "fsuid"),
+    ("info", "info"),
+    ("class", "class"),
+];
+
+pub fn parse_apparmor_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let journal_timestamp = entry_map
+        .get("_SOURCE_REALTIME_TIMESTAMP")
+        .cloned()
+        .unwrap_or_default();
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        APPARMOR_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        APPARMOR_REGEX.iter().collect()
+    };
+
+    let mut map = AHashMap::new();
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "APPARMOR_DENIED" => (None, EventType::AppArmor(AppArmorEvent::Denied)),
+                "APPARMOR_ALLOWED" => (None, EventType::AppArmor(AppArmorEvent::Allowed)),
+                "APPARMOR_STATUS" => (None, EventType::AppArmor(AppArmorEvent::Status)),
+                _ => (
+                    Some(&[("apparmor", 1)]),
+                    EventType::AppArmor(AppArmorEvent::Other),
+                ),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+
+            // The audit record is key=value pairs in no fixed order
+            for kv in APPARMOR_KV_REGEX.captures_iter(s) {
+                let Some(&(_, fname)) = APPARMOR_FIELDS.iter().find(|(key, _)| *key == &kv[1])
+                else {
+                    continue;
+                };
+                map.insert(fname.to_string(), kv[2].trim_matches('"').to_string());
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::AppArmor,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+pub fn parse_smartd_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let journal_timestamp = entry_map
+        .get("_SOURCE_REALTIME_TIMESTAMP")
+        .cloned()
+        .unwrap_or_default();
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        SMARTD_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        SMARTD_REGEX.iter().collect()
+    };
+
+    let mut map = AHashMap::new();
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "SMART_PENDING_SECTORS" => (
+                    Some(&[
+                        ("device", 1),
+                        ("device_type", 2),
+                        ("value", 3),
+                        ("attribute", 4),
+                    ]),
+                    EventType::DiskHealth(DiskHealthEvent::PendingSectors),
+                ),
+                "SMART_UNCORRECTABLE_SECTORS" => (
+                    Some(&[
+                        ("device", 1),
+                        ("device_type", 2),
+                        ("value", 3),
+                        ("attribute", 4),
+                    ]),
+                    EventType::DiskHealth(DiskHealthEvent::UncorrectableSectors),
+                ),
+                "SMART_TEMPERATURE" => (
+                    Some(&[
+                        ("device", 1),
+                        ("device_type", 2),
+                        ("value", 3),
+                        ("attribute", 4),
+                        ("limit", 5),
+                    ]),
+                    EventType::DiskHealth(DiskHealthEvent::Temperature),
+                ),
+                "SMART_SELF_TEST_FAILED" => (
+                    Some(&[
+                        ("device", 1),
+                        ("device_type", 2),
+                        ("attribute", 3),
+                        ("value", 4),
+                    ]),
+                    EventType::DiskHealth(DiskHealthEvent::SelfTestFailed),
+                ),
+                "SMART_HEALTH_FAILED" => (
+                    Some(&[("device", 1), ("device_type", 2), ("attribute", 3)]),
+                    EventType::DiskHealth(DiskHealthEvent::HealthCheckFailed),
+                ),
+                "SMART_ATTRIBUTE_CHANGED" => (
+                    Some(&[
+                        ("device", 1),
+                        ("device_type", 2),
+                        ("attribute_id", 3),
+                        ("attribute", 4),
+                        ("from", 5),
+                        ("value", 6),
+                    ]),
+                    EventType::DiskHealth(DiskHealthEvent::AttributeChanged),
+                ),
+                _ => (
+                    Some(&[("msg", 1)]),
+                    EventType::DiskHealth(DiskHealthEvent::Other),
+                ),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Smartd,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+// IEEE 802.11 deauthentication / disassociation reason codes seen in practice
+const WIFI_REASON_CODES: [(&str, &str); 12] = [
+    ("1", "UNSPECIFIED"),
+    ("2", "PREV_AUTH_NOT_VALID"),
+    ("3", "DEAUTH_LEAVING"),
+    ("4", "DISASSOC_DUE_TO_INACTIVITY"),
+    ("5", "DISASSOC_AP_BUSY"),
+    ("6", "CLASS2_FRAME_FROM_NONAUTH_STA"),
+    ("7", "CLASS3_FRAME_FROM_NONASSOC_STA"),
+    ("8", "DISASSOC_STA_HAS_LEFT"),
+    ("14", "MICHAEL_MIC_FAILURE"),
+    ("15", "4WAY_HANDSHAKE_TIMEOUT"),
+    ("16", "GROUP_KEY_UPDATE_TIMEOUT"),
+    ("23", "IEEE_802_1X_AUTH_FAILED"),
+];
+
+pub fn parse_wifi_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let journal_timestamp = entry_map
+        .get("_SOURCE_REALTIME_TIMESTAMP")
+        .cloned()
+        .unwrap_or_default();
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        WIFI_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        WIFI_REGEX.iter().collect()
+    };
+
+    let mut map = AHashMap::new();
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "WIFI_WRONG_KEY" => (
+                    Some(&[
+                        ("interface", 1),
+                        ("ssid", 2),
+                        ("auth_failures", 3),
+                        ("reason", 4),
+                    ]),
+                    EventType::Network(NetworkEvent::WifiAuthFailure),
+                ),
+                "WIFI_HANDSHAKE_FAILED" => (
+                    Some(&[("interface", 1), ("reason", 2)]),
+                    EventType::Network(NetworkEvent::WifiAuthFailure),
+                ),
+                "WIFI_AUTH_TIMEOUT" => (
+                    Some(&[("interface", 1), ("bssid", 2)]),
+                    EventType::Network(NetworkEvent::WifiAuthFailure),
+                ),
+                "WIFI_ASSOC_REJECT" => (
+                    Some(&[("interface", 1), ("bssid", 2), ("status_code", 3)]),
+                    EventType::Network(NetworkEvent::WifiAuthFailure),
+                ),
+                "WIFI_DISCONNECTED" => (
+                    Some(&[
+                        ("interface", 1),
+                        ("bssid", 2),
+                        ("reason_code", 3),
+                        ("initiator", 4),
+                    ]),
+                    EventType::Network(NetworkEvent::ConnectionDeactivated),
+                ),
+                "IWD_DEAUTH" => (
+                    Some(&[("interface", 1), ("reason_code", 2), ("from_ap", 3)]),
+                    EventType::Network(NetworkEvent::ConnectionDeactivated),
+                ),
+                "WIFI_CONNECTED" => (
+                    Some(&[("interface", 1), ("bssid", 2)]),
+                    EventType::Network(NetworkEvent::WifiAssociationSuccess),
+                ),
+                "WIFI_ASSOCIATING" => (
+                    Some(&[("interface", 1), ("bssid", 2), ("ssid", 3), ("ssid", 4)]),
+                    EventType::Network(NetworkEvent::ConnectionAttempt),
+                ),
+                _ => (Some(&[("msg", 1)]), EventType::Network(NetworkEvent::Other)),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+
+            if let Some(code) = map.get("reason_code")
+                && let Some(&(_, reason)) = WIFI_REASON_CODES.iter().find(|(c, _)| c == code)
+            {
+                map.insert("reason".to_string(), reason.to_string());
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Wifi,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+pub fn parse_power_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let journal_timestamp = entry_map
+        .get("_SOURCE_REALTIME_TIMESTAMP")
+        .cloned()
+        .unwrap_or_default();
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        POWER_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        POWER_REGEX.iter().collect()
+    };
+
+    let mut map = AHashMap::new();
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "LID_CLOSED" => (None, EventType::Power(PowerEvent::LidClosed)),
+                "LID_OPENED" => (None, EventType::Power(PowerEvent::LidOpened)),
+                "POWER_BUTTON" => (
+                    Some(&[("button", 1), ("press", 2)]),
+                    EventType::Power(PowerEvent::ButtonPressed),
+                ),
+                "SYSTEM_SUSPEND" => (
+                    Some(&[("state", 1), ("state", 2)]),
+                    EventType::Power(PowerEvent::Suspend),
+                ),
+                "SYSTEM_RESUME" => (
+                    Some(&[("operation", 1)]),
+                    EventType::Power(PowerEvent::Resume),
+                ),
+                "SYSTEM_POWEROFF" => (None, EventType::Power(PowerEvent::PowerOff)),
+                "SYSTEM_REBOOT" => (None, EventType::Power(PowerEvent::Reboot)),
+                "DOCK_CHANGE" => (
+                    Some(&[("state", 1)]),
+                    EventType::Power(PowerEvent::DockChanged),
+                ),
+                _ => (Some(&[("msg", 1)]), EventType::Power(PowerEvent::Other)),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Power,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+pub fn parse_mail_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let journal_timestamp = entry_map
+        .get("_SOURCE_REALTIME_TIMESTAMP")
+        .cloned()
+        .unwrap_or_default();
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        MAIL_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        MAIL_REGEX.iter().collect()
+    };
+
+    let mut map = AHashMap::new();
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "MAIL_SASL_AUTH_FAILED" => (
+                    Some(&[("client", 1), ("ip", 2), ("mechanism", 3), ("reason", 4)]),
+                    EventType::Mail(MailEvent::AuthFailure),
+                ),
+                "MAIL_REJECT" => (
+                    Some(&[
+                        ("queue_id", 1),
+                        ("stage", 2),
+                        ("client", 3),
+                        ("ip", 4),
+                        ("smtp_code", 5),
+                        ("dsn", 6),
+                        ("reason", 7),
+                        ("sender", 8),
+                        ("recipient", 9),
+                    ]),
+                    EventType::Mail(MailEvent::Rejected),
+                ),
+                "MAIL_DELIVERED" => (
+                    Some(&[
+                        ("queue_id", 1),
+                        ("recipient", 2),
+                        ("orig_recipient", 3),
+                        ("relay", 4),
+                        ("dsn", 5),
+                        ("status", 6),
+                        ("reason", 7),
+                    ]),
+                    EventType::Mail(MailEvent::Delivered),
+                ),
+                "MAIL_DEFERRED" => (
+                    Some(&[
+                        ("queue_id", 1),
+                        ("recipient", 2),
+                        ("orig_recipient", 3),
+                        ("relay", 4),
+                        ("dsn", 5),
+                        ("status", 6),
+                        ("reason", 7),
+                    ]),
+                    EventType::Mail(MailEvent::Deferred),
+                ),
+                "MAIL_BOUNCED" => (
+                    Some(&[
+                        ("queue_id", 1),
+                        ("recipient", 2),
+                        ("orig_recipient", 3),
+                        ("relay", 4),
+                        ("dsn", 5),
+                        ("status", 6),
+                        ("reason", 7),
+                    ]),
+                    EventType::Mail(MailEvent::Bounced),
+                ),
+                "MAIL_QUEUED" => (
+                    Some(&[("queue_id", 1), ("sender", 2), ("size", 3), ("nrcpt", 4)]),
+                    EventType::Mail(MailEvent::Accepted),
+                ),
+                "MAIL_CLIENT" => (
+                    Some(&[
+                        ("queue_id", 1),
+                        ("client", 2),
+                        ("ip", 3),
+                        ("mechanism", 4),
+                        ("user", 5),
+                    ]),
+                    EventType::Mail(MailEvent::Received),
+                ),
+                "DOVECOT_LOGIN_FAILED" => (
+                    Some(&[
+                        ("protocol", 1),
+                        ("attempts", 2),
+                        ("user", 3),
+                        ("mechanism", 4),
+                        ("ip", 5),
+                    ]),
+                    EventType::Mail(MailEvent::AuthFailure),
+                ),
+                "DOVECOT_AUTH_FAILED" => (
+                    Some(&[("backend", 1), ("user", 2), ("ip", 3), ("reason", 4)]),
+                    EventType::Mail(MailEvent::AuthFailure),
+                ),
+                "MAIL_QUEUE_OTHER" => (
+                    Some(&[("queue_id", 1), ("msg", 2)]),
+                    EventType::Mail(MailEvent::Other),
+                ),
+                _ => (Some(&[("msg", 1)]), EventType::Mail(MailEvent::Other)),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Mail,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+pub fn parse_storage_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let journal_timestamp = entry_map
+        .get("_SOURCE_REALTIME_TIMESTAMP")
+        .cloned()
+        .unwrap_or_default();
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        STORAGE_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        STORAGE_REGEX.iter().collect()
+    };
+
+    let mut map = AHashMap::new();
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "MD_DEVICE_FAILED" => (
+                    Some(&[("event", 1), ("array", 2), ("device", 3)]),
+                    EventType::Storage(StorageEvent::DeviceFailed),
+                ),
+                "MD_ARRAY_DEGRADED" => (
+                    Some(&[("event", 1), ("array", 2)]),
+                    EventType::Storage(StorageEvent::ArrayDegraded),
+                ),
+                "MD_ARRAY_DISAPPEARED" => (
+                    Some(&[("event", 1), ("array", 2)]),
+                    EventType::Storage(StorageEvent::ArrayDisappeared),
+                ),
+                "MD_REBUILD_STARTED" => (
+                    Some(&[("event", 1), ("array", 2)]),
+                    EventType::Storage(StorageEvent::RebuildStarted),
+                ),
+                "MD_REBUILD_PROGRESS" => (
+                    Some(&[("percent", 1), ("array", 2)]),
+                    EventType::Storage(StorageEvent::RebuildProgress),
+                ),
+                "MD_REBUILD_FINISHED" => (
+                    Some(&[("event", 1), ("array", 2), ("mismatches", 3)]),
+                    EventType::Storage(StorageEvent::RebuildFinished),
+                ),
+                "MD_SPARE_ACTIVE" => (
+                    Some(&[("event", 1), ("array", 2), ("device", 3)]),
+                    EventType::Storage(StorageEvent::SpareActive),
+                ),
+                "KERNEL_MD_DISK_FAILURE" => (
+                    Some(&[("level", 1), ("array", 2), ("device", 3)]),
+                    EventType::Storage(StorageEvent::DeviceFailed),
+                ),
+                "KERNEL_MD_DEGRADED" => (
+                    Some(&[("level", 1), ("array", 2), ("active_devices", 3)]),
+                    EventType::Storage(StorageEvent::ArrayDegraded),
+                ),
+                "KERNEL_MD_RECOVERY_STARTED" => (
+                    Some(&[("operation", 1), ("array", 2)]),
+                    EventType::Storage(StorageEvent::RebuildStarted),
+                ),
+                "KERNEL_MD_RECOVERY_DONE" => (
+                    Some(&[("array", 1), ("operation", 2)]),
+                    EventType::Storage(StorageEvent::RebuildFinished),
+                ),
+                "LVM_RAID_DEVICE_FAILED" => (
+                    Some(&[("device_index", 1), ("level", 2), ("array", 3)]),
+                    EventType::Storage(StorageEvent::DeviceFailed),
+                ),
+                "LVM_THIN_POOL_FILLING" => (
+                    Some(&[("pool", 1), ("space", 2), ("percent", 3)]),
+                    EventType::Storage(StorageEvent::ThinPoolFilling),
+                ),
+                "LVM_THIN_POOL_LOW_WATER" => (
+                    Some(&[("dm_device", 1), ("space", 2)]),
+                    EventType::Storage(StorageEvent::ThinPoolFilling),
+                ),
+                "LVM_THIN_POOL_EXHAUSTED" => (
+                    Some(&[("dm_device", 1), ("mode", 2)]),
+                    EventType::Storage(StorageEvent::ThinPoolExhausted),
+                ),
+                _ => (Some(&[("msg", 1)]), EventType::Storage(StorageEvent::Other)),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Storage,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+pub fn parse_flatpak_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let journal_timestamp = entry_map
+        .get("_SOURCE_REALTIME_TIMESTAMP")
+        .cloned()
+        .unwrap_or_default();
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        FLATPAK_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        FLATPAK_REGEX.iter().collect()
+    };
+
+    // Tagged so flatpak installs stay distinguishable from native ones on Package filters
+    let mut map = AHashMap::from([("source".to_string(), "flatpak".to_string())]);
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "FLATPAK_INSTALLED" => (
+                    Some(&[
+                        ("installation", 1),
+                        ("ref", 2),
+                        ("kind", 3),
+                        ("pkg_name", 4),
+                        ("arch", 5),
+                        ("branch", 6),
+                        ("remote", 7),
+                    ]),
+                    EventType::Package(PkgEvent::Installed),
+                ),
+                "FLATPAK_UPDATED" => (
+                    Some(&[
+                        ("installation", 1),
+                        ("ref", 2),
+                        ("kind", 3),
+                        ("pkg_name", 4),
+                        ("arch", 5),
+                        ("branch", 6),
+                        ("remote", 7),
+                    ]),
+                    EventType::Package(PkgEvent::Upgraded),
+                ),
+                "FLATPAK_UNINSTALLED" => (
+                    Some(&[
+                        ("installation", 1),
+                        ("ref", 2),
+                        ("kind", 3),
+                        ("pkg_name", 4),
+                        ("arch", 5),
+                        ("branch", 6),
+                    ]),
+                    EventType::Package(PkgEvent::Removed),
+                ),
+                _ => (Some(&[("msg", 1)]), EventType::Package(PkgEvent::Other)),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Flatpak,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+const FWLOG_FIELDS: [(&str, &str); 9] = [
+    ("IN", "in_iface"),
+    ("OUT", "out_iface"),
+    ("SRC", "src_ip"),
+    ("DST", "dst_ip"),
+    ("PROTO", "proto"),
+    ("SPT", "src_port"),
+    ("DPT", "dst_port"),
+    ("LEN", "length"),
+    ("TTL", "ttl"),
+];
+
+pub fn parse_fwlog_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let journal_timestamp = entry_map
+        .get("_SOURCE_REALTIME_TIMESTAMP")
+        .cloned()
+        .unwrap_or_default();
+    let timestamp = format_syslog_timestamp(&journal_timestamp, TimestampUnit::Micros);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        FWLOG_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        FWLOG_REGEX.iter().collect()
+    };
+
+    let mut map = AHashMap::new();
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "FW_PACKET_DROPPED" => (
+                    Some(&[("prefix", 1)]),
+                    EventType::Firewall(FirewallEvent::PacketDropped),
+                ),
+                "FW_PACKET_ACCEPTED" => (
+                    Some(&[("prefix", 1)]),
+                    EventType::Firewall(FirewallEvent::PacketAccepted),
+                ),
+                "FW_PACKET_LOGGED" => (
+                    Some(&[("prefix", 1)]),
+                    EventType::Firewall(FirewallEvent::PacketLogged),
+                ),
+                _ => (
+                    Some(&[("msg", 1)]),
+                    EventType::Firewall(FirewallEvent::Other),
+                ),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+
+            for kv in FWLOG_KV_REGEX.captures_iter(s) {
+                let Some(&(_, fname)) = FWLOG_FIELDS.iter().find(|(key, _)| *key == &kv[1]) else {
+                    continue;
+                };
+                map.insert(fname.to_string(), kv[2].to_string());
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::FwLog,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+pub fn parse_display_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let timestamp = entry_timestamp(&entry_map);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        DISPLAY_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        DISPLAY_REGEX.iter().collect()
+    };
+
+    let mut map = AHashMap::new();
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "GPU_HANG" => (
+                    Some(&[("driver", 1), ("device", 2), ("process", 3), ("pid", 4)]),
+                    EventType::Display(DisplayEvent::GpuHang),
+                ),
+                "GPU_RING_TIMEOUT" => (
+                    Some(&[("driver", 1), ("ring", 2)]),
+                    EventType::Display(DisplayEvent::GpuHang),
+                ),
+                "GPU_RESET" => (
+                    Some(&[("driver", 1), ("device", 2), ("reason", 3), ("state", 4)]),
+                    EventType::Display(DisplayEvent::GpuReset),
+                ),
+                "NVIDIA_XID" => (
+                    Some(&[("device", 1), ("xid", 2), ("msg", 3)]),
+                    EventType::Display(DisplayEvent::GpuHang),
+                ),
+                "XORG_SEGFAULT" => (
+                    Some(&[("address", 1), ("signal", 2), ("signal_name", 3)]),
+                    EventType::Display(DisplayEvent::XorgCrash),
+                ),
+                "XORG_FATAL" => (
+                    Some(&[("msg", 1)]),
+                    EventType::Display(DisplayEvent::XorgCrash),
+                ),
+                "XORG_ERROR" => (
+                    Some(&[("driver", 1), ("msg", 2)]),
+                    EventType::Display(DisplayEvent::XorgError),
+                ),
+                "COMPOSITOR_CRASH" => (
+                    Some(&[("compositor", 1), ("signal", 2)]),
+                    EventType::Display(DisplayEvent::CompositorCrash),
+                ),
+                "XWAYLAND_LOST" => (None, EventType::Display(DisplayEvent::CompositorCrash)),
+                _ => (Some(&[("msg", 1)]), EventType::Display(DisplayEvent::Other)),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+            // The proprietary driver logs as NVRM rather than by module name
+            if *name == "NVIDIA_XID" {
+                map.insert("driver".to_string(), "nvidia".to_string());
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Display,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+// systemd-coredump attaches the crash details as journal fields, MESSAGE only repeats
+// some of them
+const COREDUMP_FIELDS: [(&str, &str); 8] = [
+    ("COREDUMP_EXE", "exe"),
+    ("COREDUMP_COMM", "comm"),
+    ("COREDUMP_PID", "pid"),
+    ("COREDUMP_UID", "uid"),
+    ("COREDUMP_GID", "gid"),
+    ("COREDUMP_SIGNAL_NAME", "signal"),
+    ("COREDUMP_SIGNAL", "signal_number"),
+    ("COREDUMP_UNIT", "unit"),
+];
+
+pub fn parse_coredump_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let timestamp = entry_timestamp(&entry_map);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        COREDUMP_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        COREDUMP_REGEX.iter().collect()
+    };
+
+    let mut map = AHashMap::new();
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "COREDUMP" => (
+                    Some(&[("pid", 1), ("comm", 2), ("uid", 3)]),
+                    EventType::Coredump(CoredumpEvent::CoreDumped),
+                ),
+                "COREDUMP_NO_CORE" => (
+                    Some(&[("pid", 1), ("comm", 2), ("uid", 3)]),
+                    EventType::Coredump(CoredumpEvent::CrashedWithoutCore),
+                ),
+                _ => (
+                    Some(&[("msg", 1)]),
+                    EventType::Coredump(CoredumpEvent::Other),
+                ),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+            for (key, fname) in COREDUMP_FIELDS {
+                if let Some(value) = entry_map.get(key) {
+                    map.insert(fname.to_string(), value.clone());
+                }
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Coredump,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+// systemd-resolved logs DNSSEC failures with the question, server and result as journal
+// fields too, which also cover lines whose MESSAGE doesn't spell them out
+const RESOLVED_FIELDS: [(&str, &str); 3] = [
+    ("DNS_SERVER", "server"),
+    ("DNSSEC_RESULT", "result"),
+    ("DNS_SERVER_FEATURE_LEVEL", "feature_level"),
+];
+
+pub fn parse_resolved_events(entry_map: Entry, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+    let timestamp = entry_timestamp(&entry_map);
+
+    let filtered_regexes: Vec<_> = if let Some(ev_types) = ev_type {
+        let names: Vec<&str> = ev_types
+            .iter()
+            .flat_map(|&s| str_to_regex_names(s).to_owned())
+            .collect();
+
+        RESOLVED_REGEX
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect()
+    } else {
+        RESOLVED_REGEX.iter().collect()
+    };
+
+    let mut map = AHashMap::new();
+    let s = entry_map.get("MESSAGE")?;
+
+    for (name, regex) in filtered_regexes {
+        if let Some(caps) = regex.captures(s) {
+            let (data, event_type): (Option<&[(&str, usize)]>, EventType) = match *name {
+                "RESOLVED_DNSSEC_FAILED" => (
+                    Some(&[("domain", 1), ("record_type", 2), ("result", 3)]),
+                    EventType::Dns(DnsEvent::DnssecFailure),
+                ),
+                "RESOLVED_SERVER_SWITCHED" => (
+                    Some(&[
+                        ("server_type", 1),
+                        ("server", 2),
+                        ("server_name", 3),
+                        ("interface", 4),
+                    ]),
+                    EventType::Dns(DnsEvent::ServerSwitched),
+                ),
+                "RESOLVED_FEATURE_DEGRADED" => (
+                    Some(&[
+                        ("feature_level", 1),
+                        ("previous_level", 2),
+                        ("server", 3),
+                        ("server_name", 4),
+                    ]),
+                    EventType::Dns(DnsEvent::FeatureDegraded),
+                ),
+                "RESOLVED_FEATURE_RESTORED" => (
+                    Some(&[("feature_level", 1), ("server", 2), ("server_name", 3)]),
+                    EventType::Dns(DnsEvent::FeatureRestored),
+                ),
+                "RESOLVED_QUERY_TIMEOUT" => (
+                    Some(&[("transaction", 1)]),
+                    EventType::Dns(DnsEvent::QueryTimeout),
+                ),
+                _ => (Some(&[("msg", 1)]), EventType::Dns(DnsEvent::Other)),
+            };
+
+            if let Some(fields) = data {
+                for &(fname, idx) in fields {
+                    if let Some(m) = caps.get(idx) {
+                        map.insert(fname.to_string(), m.as_str().to_string());
+                    }
+                }
+            }
+            for (key, fname) in RESOLVED_FIELDS {
+                if let Some(value) = entry_map.get(key) {
+                    map.entry(fname.to_string())
+                        .or_insert_with(|| value.clone());
+                }
+            }
+            // DNS_QUESTION is "<domain> <class> <type>"
+            if let Some((domain, record_type)) = entry_map
+                .get("DNS_QUESTION")
+                .and_then(|q| q.split_once(' '))
+                .map(|(domain, rest)| (domain, rest.rsplit(' ').next().unwrap_or_default()))
+            {
+                map.entry("domain".to_string())
+                    .or_insert_with(|| domain.to_string());
+                map.entry("record_type".to_string())
+                    .or_insert_with(|| record_type.to_string());
+            }
+
+            return Some(EventData {
+                timestamp,
+                service: Service::Resolved,
+                data: map,
+                event_type,
+                raw_msg: RawMsgType::Structured(entry_map),
+                matched_rule: Some(name.to_string()),
+            });
+        }
+    }
+    None
+}
+
+pub fn get_service_configs() -> AHashMap<&'static str, ServiceConfig> {
+    let mut map = AHashMap::new();
+    map.insert(
+        "pkgmanager.events",
+        ServiceConfig {
+            matches: None,
+            parser: ParserFunctionType::ParserFnForManual(parse_pkg_events),
+            regexes: vec![&PKG_EVENTS_REGEX],
+            named_rules: &[],
+            file_source: Some(pacman_log()),
+        },
+    );
+
+    map.insert(
+        "accesslog.events",
+        ServiceConfig {
+            matches: None,
+            parser: ParserFunctionType::ParserFnForManual(parse_access_log),
+            regexes: Vec::new(),
+            named_rules: &ACCESS_LOG_RULES,
+            file_source: Some(access_log()),
+        },
+    );
+
+    map.insert(
+        "sshd.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("_COMM", "sshd"),
+                ("_EXE", "/usr/sbin/sshd"),
+                ("_SYSTEMD_UNIT", "sshd.service"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_sshd_logs),
+            regexes: vec![&SSHD_REGEX, &PROTOCOL_MISMATCH],
+            named_rules: &[],
+            file_source: Some(auth_log()),
+        },
+    );
+
+    map.insert(
+        "sudo.events",
+        ServiceConfig {
+            matches: Some(vec![("_COMM", "su"), ("_COMM", "sudo")]),
+            parser: ParserFunctionType::ParserFn(parse_sudo_login_attempts),
+            regexes: vec![&SUDO_REGEX],
+            named_rules: &[],
+            file_source: Some(auth_log()),
+        },
+    );
+
+    map.insert(
+        "login.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("SYSLOG_IDENTIFIER", "systemd-logind"),
+                ("SYSLOG_IDENTIFIER", "gdm-password]"),
+                ("SYSLOG_IDENTIFIER", "gdm-autologin]"),
+                ("SYSLOG_IDENTIFIER", "gdm-fingerprint]"),
+                ("SYSLOG_IDENTIFIER", "gdm-smartcard]"),
+                ("SYSLOG_IDENTIFIER", "lightdm"),
+                ("SYSLOG_IDENTIFIER", "sddm"),
+                ("SYSLOG_IDENTIFIER", "sddm-helper"),
+                ("SYSLOG_IDENTIFIER", "sddm-greeter"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_login_attempts),
+            regexes: vec![&LOGIN_REGEXES],
+            named_rules: &[],
+            file_source: Some(auth_log()),
+        },
+    );
+
+    map.insert(
+        "firewalld.events",
+        ServiceConfig {
+            matches: Some(vec![("_SYSTEMD_UNIT", "firewalld.service")]),
+            parser: ParserFunctionType::ParserFn(parse_firewalld_events),
+            regexes: vec![&FIREWALLD_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "networkmanager.events",
+        ServiceConfig {
+            matches: Some(vec![("_SYSTEMD_UNIT", "NetworkManager.service")]),
+            parser: ParserFunctionType::ParserFn(parse_network_events),
+            regexes: vec![&NETWORK_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "kernel.events",
+        ServiceConfig {
+            // systemd-oomd is where memory pressure (PSI) shows up in the journal
+            matches: Some(vec![
+                ("_TRANSPORT", "kernel"),
+                ("SYSLOG_IDENTIFIER", "systemd-oomd"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_kernel_events),
+            regexes: vec![&KERNEL_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "userchange.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("_COMM", "useradd"),
+                ("_COMM", "groupadd"),
+                ("_COMM", "usermod"),
+                ("_COMM", "groupmod"),
+                ("_COMM", "passwd"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_user_change_events),
+            regexes: vec![
+                &USER_CREATION_REGEX,
+                &USER_DELETION_REGEX,
+                &USER_MODIFICATION_REGEX,
+            ],
+            named_rules: &[],
+            file_source: Some(auth_log()),
+        },
+    );
+
+    map.insert(
+        "systemd.events",
+        ServiceConfig {
+            matches: Some(vec![("SYSLOG_IDENTIFIER", "systemd")]),
+            parser: ParserFunctionType::ParserFn(parse_systemd_unit_events),
+            regexes: vec![&SYSTEMD_UNIT_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "fail2ban.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("SYSLOG_IDENTIFIER", "fail2ban"),
+                ("SYSLOG_IDENTIFIER", "fail2ban-server"),
+                ("_COMM", "fail2ban-server"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_fail2ban_events),
+            regexes: vec![&FAIL2BAN_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "timesync.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("_SYSTEMD_UNIT", "systemd-timesyncd.service"),
+                ("_SYSTEMD_UNIT", "chronyd.service"),
+                ("_SYSTEMD_UNIT", "chrony.service"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_timesync_events),
+            regexes: vec![&TIMESYNC_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "docker.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("_SYSTEMD_UNIT", "docker.service"),
+                ("_SYSTEMD_UNIT", "containerd.service"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_docker_events),
+            regexes: vec![&DOCKER_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "bluetooth.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("_SYSTEMD_UNIT", "bluetooth.service"),
+                ("SYSLOG_IDENTIFIER", "bluetoothd"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_bluetooth_events),
+            regexes: vec![&BLUETOOTH_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "apparmor.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("_TRANSPORT", "audit"),
+                ("SYSLOG_IDENTIFIER", "audit"),
+                ("_TRANSPORT", "kernel"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_apparmor_events),
+            regexes: vec![&APPARMOR_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "smartd.events",
+        ServiceConfig {
+            matches: Some(vec![("SYSLOG_IDENTIFIER", "smartd")]),
+            parser: ParserFunctionType::ParserFn(parse_smartd_events),
+            regexes: vec![&SMARTD_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "wifi.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("SYSLOG_IDENTIFIER", "wpa_supplicant"),
+                ("SYSLOG_IDENTIFIER", "iwd"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_wifi_events),
+            regexes: vec![&WIFI_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "power.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("_SYSTEMD_UNIT", "systemd-logind.service"),
+                ("SYSLOG_IDENTIFIER", "systemd-sleep"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_power_events),
+            regexes: vec![&POWER_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "mail.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("SYSLOG_IDENTIFIER", "postfix/smtpd"),
+                ("SYSLOG_IDENTIFIER", "postfix/submission/smtpd"),
+                ("SYSLOG_IDENTIFIER", "postfix/smtps/smtpd"),
+                ("SYSLOG_IDENTIFIER", "postfix/postscreen"),
+                ("SYSLOG_IDENTIFIER", "postfix/cleanup"),
+                ("SYSLOG_IDENTIFIER", "postfix/qmgr"),
+                ("SYSLOG_IDENTIFIER", "postfix/smtp"),
+                ("SYSLOG_IDENTIFIER", "postfix/lmtp"),
+                ("SYSLOG_IDENTIFIER", "postfix/local"),
+                ("SYSLOG_IDENTIFIER", "postfix/virtual"),
+                ("SYSLOG_IDENTIFIER", "postfix/pipe"),
+                ("SYSLOG_IDENTIFIER", "postfix/bounce"),
+                ("SYSLOG_IDENTIFIER", "dovecot"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_mail_events),
+            regexes: vec![&MAIL_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "storage.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("SYSLOG_IDENTIFIER", "mdadm"),
+                ("SYSLOG_IDENTIFIER", "dmeventd"),
+                ("SYSLOG_IDENTIFIER", "lvm"),
+                ("_TRANSPORT", "kernel"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_storage_events),
+            regexes: vec![&STORAGE_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "flatpak.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("MESSAGE_ID", "c7b39b1e006b464599465e105b361485"),
+                ("_COMM", "flatpak"),
+                ("_COMM", "flatpak-system-helper"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_flatpak_events),
+            regexes: vec![&FLATPAK_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "fwlog.events",
+        ServiceConfig {
+            matches: Some(vec![("_TRANSPORT", "kernel")]),
+            parser: ParserFunctionType::ParserFn(parse_fwlog_events),
+            regexes: vec![&FWLOG_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "display.events",
+        ServiceConfig {
+            matches: Some(vec![
+                ("_TRANSPORT", "kernel"),
+                ("_COMM", "Xorg"),
+                ("_COMM", "Xwayland"),
+                ("_COMM", "gnome-shell"),
+                ("_COMM", "kwin_wayland"),
+                ("_COMM", "kwin_x11"),
+                ("_COMM", "kwin_wayland_wrapper"),
+            ]),
+            parser: ParserFunctionType::ParserFn(parse_display_events),
+            regexes: vec![&DISPLAY_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "coredump.events",
+        ServiceConfig {
+            matches: Some(vec![("SYSLOG_IDENTIFIER", "systemd-coredump")]),
+            parser: ParserFunctionType::ParserFn(parse_coredump_events),
+            regexes: vec![&COREDUMP_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "resolved.events",
+        ServiceConfig {
+            matches: Some(vec![("_SYSTEMD_UNIT", "systemd-resolved.service")]),
+            parser: ParserFunctionType::ParserFn(parse_resolved_events),
+            regexes: vec![&RESOLVED_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    map.insert(
+        "configchange.events",
+        ServiceConfig {
+            matches: Some(vec![("_SYSTEMD_UNIT", "cronie.service")]),
+            parser: ParserFunctionType::ParserFn(parse_config_change_events),
+            regexes: vec![&CRON_REGEX],
+            named_rules: &[],
+            file_source: None,
+        },
+    );
+
+    // A disabled member service stays out of security.events too
+    let members: Vec<&ServiceConfig> = SECURITY_SERVICES
+        .iter()
+        .filter(|name| crate::config::config().service_enabled(name))
+        .filter_map(|name| map.get(name))
+        .collect();
+    let security = ServiceConfig {
+        matches: Some(
+            members
+                .iter()
+                .flat_map(|c| c.matches.clone().unwrap_or_default())
+                .collect(),
+        ),
+        parser: ParserFunctionType::ParserFn(parse_security_events),
+        regexes: members.iter().flat_map(|c| c.regexes.clone()).collect(),
+        named_rules: &[],
+        file_source: Some(auth_log()),
+    };
+    map.insert("security.events", security);
+
+    map
+}
+
+// The services this instance offers, after --enable-service/--disable-service
+pub fn enabled_service_configs() -> AHashMap<&'static str, ServiceConfig> {
+    let config = crate::config::config();
+    get_service_configs()
+        .into_iter()
+        .filter(|(name, _)| config.service_enabled(name))
+        .collect()
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ParseRequest {
+    pub service: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ParseResult {
+    pub event: Option<EventData>,
+    pub matched_rule: Option<String>,
+}
+
+pub fn parse_single_message(service_name: &str, message: &str) -> Result<ParseResult> {
+    let configs = enabled_service_configs();
+    let Some(config) = configs.get(service_name) else {
+        return Err(DrashtaError::unknown_service(service_name).into());
+    };
+
+    let event = match config.parser {
+        ParserFunctionType::ParserFn(parserfn) => {
+            let mut entry = Entry::new();
+            entry.insert("MESSAGE".to_string(), message.to_string());
+            parserfn(entry, None)
+        }
+        ParserFunctionType::ParserFnForManual(parserfn) => parserfn(message.to_string(), None),
+    };
+
+    let matched_rule = event.as_ref().and_then(|ev| ev.matched_rule.clone());
+
+    Ok(ParseResult {
+        event,
+        matched_rule,
+    })
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct EventContext {
+    pub before: Vec<EventData>,
+    pub event: Option<EventData>,
+    pub after: Vec<EventData>,
+}
+
+// Up to `window` parsed events on each side of the cursor, oldest first. Entries the
+// service's parser skips don't count towards the window.
+pub fn read_event_context(
+    service_name: &str,
+    cursor: CursorType,
+    window: usize,
+) -> Result<EventContext> {
+    let configs = enabled_service_configs();
+    let Some(config) = configs.get(service_name) else {
+        return Err(DrashtaError::unknown_service(service_name).into());
+    };
+
+    match (cursor.for_service(service_name)?, &config.parser) {
+        (CursorType::Journal(cursor), &ParserFunctionType::ParserFn(parserfn)) => {
+            read_journal_context(config, parserfn, &cursor, window)
+        }
+        (CursorType::Manual(cursor), _) => read_manual_context(service_name, &cursor, window),
+        _ => Err(anyhow!("Cursor type doesn't match {service_name}")),
+    }
+}
+
+fn read_journal_context(
+    config: &ServiceConfig,
+    parserfn: ParserFn,
+    cursor: &str,
+    window: usize,
+) -> Result<EventContext> {
+    let mut journal = open_journal()?;
+    add_service_matches(&mut journal, config, &[], None)?;
+    let parse = |journal: &Journal, data| {
+        parserfn(with_receive_time(journal, data), None).map(with_severity)
+    };
+
+    journal
+        .seek_cursor(cursor)
+        .map_err(|e| anyhow!("Invalid journal cursor `{cursor}`: {e}"))?;
+    let Some(data) = journal.next_entry()? else {
+        return Ok(EventContext::default());
+    };
+    if !journal.test_cursor(cursor)? {
+        anyhow::bail!("Cursor `{cursor}` is no longer in the journal");
+    }
+    let event = parse(&journal, data);
+
+    let mut after = Vec::new();
+    while after.len() < window {
+        let Some(data) = journal.next_entry()? else {
+            break;
+        };
+        after.extend(parse(&journal, data));
+    }
+
+    // previous_entry() right after seeking lands back on the cursor entry itself
+    journal.seek_cursor(cursor)?;
+    journal.previous_entry()?;
+    let mut before = Vec::new();
+    while before.len() < window {
+        let Some(data) = journal.previous_entry()? else {
+            break;
+        };
+        before.extend(parse(&journal, data));
+    }
+    before.reverse();
+
+    Ok(EventContext {
+        before,
+        event,
+        after,
+    })
+}
+
+fn read_manual_context(service_name: &str, cursor: &Cursor, window: usize) -> Result<EventContext> {
+    let (log_path, parser) = manual_source(service_name)?;
+    let Some(path) = rotated_log_path(log_path, cursor.file_index) else {
+        anyhow::bail!(
+            "Rotated log {}.{} no longer exists",
+            log_path.display(),
+            cursor.file_index
+        );
+    };
+
+    let (_, lines) = read_file_backward(&path, Some(cursor.offset))?;
+    let mut lines = lines.into_iter();
+    let event = lines.next().and_then(|line| parser.parse(line, None));
+    let mut before: Vec<_> = lines
+        .filter_map(|line| parser.parse(line, None))
+        .take(window)
+        .collect();
+    before.reverse();
+
+    let mut reader = open_log(&path)?;
+    reader.seek(SeekFrom::Start(cursor.offset))?;
+    let after = reader
+        .lines()
+        .skip(1)
+        .map_while(Result::ok)
+        .filter_map(|line| parser.parse(line, None))
+        .take(window)
+        .collect();
+
+    Ok(EventContext {
+        before,
+        event,
+        after,
+    })
+}
+
+pub fn list_services() -> Vec<ServiceInfo> {
+    let mut services: Vec<ServiceInfo> = enabled_service_configs()
+        .into_iter()
+        .map(|(name, config)| {
+            let rule_names: Vec<&str> = config.rule_names().collect();
+
+            let event_types = EVENT_TYPE_FILTERS
+                .iter()
+                .filter(|(_, names)| names.iter().any(|n| rule_names.contains(n)))
+                .map(|(filter, _)| *filter)
+                .collect();
+
+            ServiceInfo {
+                name,
+                manual: MANUAL_PARSE_EVENTS.contains(&name),
+                event_types,
+            }
+        })
+        .collect();
 
-    map.insert(
-        "sudo.events",
-        ServiceConfig {
-            matches: Some(vec![("_COMM", "su"), ("_COMM", "sudo")]),
-            parser: ParserFunctionType::ParserFn(parse_sudo_login_attempts),
-        },
-    );
+    services.sort_by_key(|s| s.name);
+    services
+}
 
-    map.insert(
-        "login.events",
-        ServiceConfig {
-            matches: Some(vec![("SYSLOG_IDENTIFIER", "systemd-logind")]),
-            parser: ParserFunctionType::ParserFn(parse_login_attempts),
-        },
-    );
+// The rule names of a service, in table order. security.events shares
+// UNKNOWN between its members, so names are only listed once
+pub fn service_rule_names(service_name: &str) -> Result<Vec<&'static str>> {
+    let configs = enabled_service_configs();
+    let Some(config) = configs.get(service_name) else {
+        return Err(DrashtaError::unknown_service(service_name).into());
+    };
+    let mut names: Vec<&'static str> = Vec::new();
+    for rule in config.rule_names() {
+        if !names.contains(&rule) {
+            names.push(rule);
+        }
+    }
+    Ok(names)
+}
 
-    map.insert(
-        "firewalld.events",
-        ServiceConfig {
-            matches: Some(vec![("_SYSTEMD_UNIT", "firewalld.service")]),
-            parser: ParserFunctionType::ParserFn(parse_firewalld_events),
-        },
-    );
+fn current_boot_id() -> Result<String> {
+    let id = std::fs::read_to_string("/proc/sys/kernel/random/boot_id")
+        .map_err(|e| anyhow!("Failed to read current boot id: {e}"))?;
+    Ok(id.trim().replace('-', ""))
+}
 
-    map.insert(
-        "networkmanager.events",
-        ServiceConfig {
-            matches: Some(vec![("_SYSTEMD_UNIT", "NetworkManager.service")]),
-            parser: ParserFunctionType::ParserFn(parse_network_events),
-        },
-    );
+// A --journal-dir journal's "current" boot is the one its last entry belongs to
+fn last_boot_id(journal: &mut Journal) -> Result<String> {
+    journal.seek_tail()?;
+    journal
+        .previous_entry()?
+        .and_then(|entry| entry.get("_BOOT_ID").cloned())
+        .ok_or_else(|| anyhow!("No journal entries found"))
+}
 
-    map.insert(
-        "kernel.events",
-        ServiceConfig {
-            matches: Some(vec![("_TRANSPORT", "kernel")]),
-            parser: ParserFunctionType::ParserFn(parse_kernel_events),
-        },
-    );
+pub fn resolve_boot_id(journal: &mut Journal, boot: i32) -> Result<String> {
+    if boot > 0 {
+        anyhow::bail!(
+            "Invalid boot offset {boot}, use 0 for the current boot or negative values for previous boots"
+        );
+    }
 
-    map.insert(
-        "userchange.events",
-        ServiceConfig {
-            matches: Some(vec![
-                ("_COMM", "useradd"),
-                ("_COMM", "groupadd"),
-                ("_COMM", "passwd"),
-            ]),
-            parser: ParserFunctionType::ParserFn(parse_user_change_events),
-        },
-    );
+    let mut boot_id = match crate::config::config().journal_dir {
+        Some(_) => last_boot_id(journal)?,
+        None => current_boot_id()?,
+    };
+    for _ in boot..0 {
+        journal.match_add("_BOOT_ID", boot_id.clone())?;
+        journal.seek_head()?;
+        let first = journal.next_entry()?;
+        let cursor = journal.cursor();
+        journal.match_flush()?;
+
+        if first.is_none() {
+            anyhow::bail!("No journal entries found for boot {boot_id}");
+        }
+        journal.seek_cursor(cursor?)?;
 
-    map.insert(
-        "configchange.events",
-        ServiceConfig {
-            matches: Some(vec![("_SYSTEMD_UNIT", "cronie.service")]),
-            parser: ParserFunctionType::ParserFn(parse_config_change_events),
-        },
-    );
+        let mut previous = None;
+        while let Some(data) = journal.previous_entry()? {
+            if data.get("_BOOT_ID").is_some_and(|id| *id != boot_id) {
+                previous = data.get("_BOOT_ID").cloned();
+                break;
+            }
+        }
 
-    map
+        let Some(id) = previous else {
+            anyhow::bail!("Boot offset {boot} is older than the oldest boot in the journal");
+        };
+        boot_id = id;
+    }
+
+    Ok(boot_id)
+}
+
+fn add_service_matches(
+    journal: &mut Journal,
+    config: &ServiceConfig,
+    match_fields: &[JournalMatch],
+    boot: Option<i32>,
+) -> Result<()> {
+    let boot_id = match boot {
+        Some(boot) => Some(resolve_boot_id(journal, boot)?),
+        None => None,
+    };
+
+    if let Some(values) = &config.matches {
+        for (field, value) in values {
+            journal.match_add(field, value.to_string())?;
+            journal.match_or()?;
+        }
+    }
+
+    // Different fields are ANDed by the journal, repeats of the same field are ORed
+    if !match_fields.is_empty() {
+        journal.match_and()?;
+        for (field, value) in match_fields {
+            journal.match_add(field, value.as_str())?;
+        }
+    }
+
+    if let Some(boot_id) = boot_id {
+        journal.match_and()?;
+        journal.match_add("_BOOT_ID", boot_id)?;
+    }
+
+    Ok(())
 }
 
 pub fn process_entries_in_parallel(
@@ -1584,42 +4691,54 @@ pub fn process_entries_in_parallel(
     let filter = &opts.filter;
     let tx = &opts.tx;
     let event_type = &opts.ev_type;
+    let ev_kinds = &opts.ev_kinds;
+    let field_filters = &opts.field_filters;
+    let service_name = opts.service_name;
 
     let ParserFunctionType::ParserFn(parserfn) = config.parser else {
         return Err(anyhow!("ParserFn required here"));
     };
 
-    let count = AtomicI32::new(0);
+    // Parsed in parallel, but collect keeps journal order so events are sent in the order
+    // they were read
+    let events: Vec<Option<EventData>> = data
+        .into_par_iter()
+        .map(|val| {
+            record_entry_read(service_name);
+            let ev = parserfn(val, event_type.clone()).map(with_severity)?;
+            record_event_parsed(service_name, &ev);
+
+            if let Some(filter_val) = filter
+                && !ev.raw_msg.matches(filter_val)
+            {
+                return None;
+            }
+            if !matches_event_kinds(&ev, ev_kinds) || !matches_fields(&ev, field_filters) {
+                return None;
+            }
+            Some(ev)
+        })
+        .collect();
 
-    data.par_iter().for_each(|val| {
-        let cur = count.load(Ordering::Relaxed);
-        if cur >= limit {
-            return;
+    // Runs on the drain's blocking thread, so waiting for room in the channel is fine and
+    // nothing is lost when the consumer is slow. A send only fails once it hung up
+    let mut count = 0;
+    for ev in events {
+        if count >= limit {
+            break;
         }
-
-        let ev = match parserfn(val.clone(), event_type.clone()) {
-            Some(ev) => ev,
-            None => return,
+        opts.entries_read.set(opts.entries_read.get() + 1);
+        let Some(ev) = ev else {
+            continue;
         };
-
-        if let Some(filter_val) = filter {
-            if !ev.raw_msg.contains_bytes(filter_val) {
-                return;
-            }
-        }
-
-        let ok = count
-            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |c| {
-                if c < limit { Some(c + 1) } else { None }
-            })
-            .is_ok();
-
-        if ok {
-            let _ = tx.try_send(ev);
+        if tx.blocking_send(ev).is_err() {
+            record_event_dropped(service_name);
+            break;
         }
-    });
+        count += 1;
+    }
 
-    Ok(count.load(Ordering::Relaxed))
+    Ok(count)
 }
 
 pub fn process_upto_n_entries(opts: ParserFuncArgs, config: &ServiceConfig) -> Result<String> {
@@ -1628,21 +4747,24 @@ pub fn process_upto_n_entries(opts: ParserFuncArgs, config: &ServiceConfig) -> R
 
     let mut remaining = opts.limit;
 
-    if let Some(values) = &config.matches {
-        for (field, value) in values {
-            journal.match_add(field, value.to_string())?;
-            journal.match_or()?;
-        }
-    }
+    add_service_matches(&mut journal, config, &opts.match_fields, opts.boot)?;
 
-    journal.seek_head()?;
+    match opts.time_range.from {
+        Some(from) => journal.seek_realtime_usec((from.max(0) as u64).saturating_mul(1_000_000))?,
+        None => journal.seek_head()?,
+    }
 
     while remaining > 0 {
         let Some(data) = journal.next_entry()? else {
             break;
         };
+        if let Ok(usec) = journal.timestamp_usec()
+            && opts.time_range.after((usec / 1_000_000) as i64)
+        {
+            break;
+        }
 
-        batch.push_back(data);
+        batch.push_back(with_receive_time(&journal, data));
 
         if batch.len() >= 100 {
             let current = std::mem::take(&mut batch);
@@ -1658,7 +4780,8 @@ pub fn process_upto_n_entries(opts: ParserFuncArgs, config: &ServiceConfig) -> R
         process_entries_in_parallel(batch, &opts, config, remaining)?;
     }
 
-    let cursor = journal.cursor()?;
+    // No cursor when nothing matched, the journal has no current entry
+    let cursor = journal.cursor().unwrap_or_default();
     Ok(cursor)
 }
 
@@ -1671,20 +4794,22 @@ pub fn process_older_logs(
     let mut journal = opts.journal.lock().unwrap();
     let mut batch = VecDeque::with_capacity(100);
 
-    if let Some(values) = &config.matches {
-        for (field, value) in values {
-            journal.match_add(field, value.to_string())?;
-            journal.match_or()?;
-        }
+    let stale = journal_cursor_stale(&mut journal, &cursor)?;
+    add_service_matches(&mut journal, config, &opts.match_fields, opts.boot)?;
+    if stale {
+        warn!("Cursor `{cursor}` is no longer in the journal, reading from the head");
+        opts.cursor_stale.set(true);
+        journal.seek_head()?;
+    } else {
+        journal.seek_cursor(&cursor)?;
+        journal.next_entry()?;
     }
-    journal.seek_cursor(&cursor)?;
-    journal.next_entry()?;
 
     let mut last_cursor = cursor.clone();
     while remaining > 0 {
         match journal.next_entry()? {
             Some(data) => {
-                batch.push_back(data);
+                batch.push_back(with_receive_time(&journal, data));
 
                 if batch.len() >= 100 {
                     let current = std::mem::take(&mut batch);
@@ -1698,7 +4823,7 @@ pub fn process_older_logs(
                 last_cursor = journal.cursor()?;
             }
             None => {
-                info!("No More Entries!");
+                debug!("No More Entries!");
                 break;
             }
         }
@@ -1715,41 +4840,47 @@ pub fn process_previous_logs(
     config: &ServiceConfig,
     cursor: String,
 ) -> Result<String> {
-    let filter = opts.filter;
+    let keyword = opts.filter;
     let limit = opts.limit;
     let tx = opts.tx;
     let event_type = opts.ev_type;
+    let ev_kinds = opts.ev_kinds;
+    let field_filters = opts.field_filters;
     let mut journal = opts.journal.lock().unwrap();
 
-    let mut keyword = String::new();
-    if let Some(filter) = filter {
-        keyword = filter;
-    }
     let ParserFunctionType::ParserFn(parserfn) = config.parser else {
         return Err(anyhow!("ParserFn required here"));
     };
 
-    if let Some(values) = &config.matches {
-        for (field, value) in values {
-            journal.match_add(field, value.to_string())?;
-            journal.match_or()?;
-        }
+    let stale = journal_cursor_stale(&mut journal, &cursor)?;
+    add_service_matches(&mut journal, config, &opts.match_fields, opts.boot)?;
+    if stale {
+        warn!("Cursor `{cursor}` is no longer in the journal, reading from the tail");
+        opts.cursor_stale.set(true);
+        journal.seek_tail()?;
+    } else {
+        journal.seek_cursor(&cursor)?;
     }
 
-    journal.seek_cursor(&cursor)?;
-
     let mut count = 0;
     let mut last_cursor = cursor.clone();
     while count < limit {
         match journal.previous_entry()? {
             Some(data) => {
                 count += 1;
-                if let Some(ev) = parserfn(data, event_type.clone()) {
-                    if !ev.raw_msg.contains_bytes(keyword.as_str()) {
+                record_entry_read(opts.service_name);
+                let data = with_receive_time(&journal, data);
+                if let Some(ev) = parserfn(data, event_type.clone()).map(with_severity) {
+                    record_event_parsed(opts.service_name, &ev);
+                    if !matches_keyword(&ev, &keyword)
+                        || !matches_event_kinds(&ev, &ev_kinds)
+                        || !matches_fields(&ev, &field_filters)
+                    {
                         continue;
                     }
                     if tx.blocking_send(ev).is_err() {
                         error!("Event Dropped!");
+                        record_event_dropped(opts.service_name);
                         continue;
                     }
                 }
@@ -1765,11 +4896,11 @@ pub fn process_service_logs(
     opts: ParserFuncArgs,
     cursor: Option<String>,
 ) -> Result<String, anyhow::Error> {
-    let configs = get_service_configs();
+    let configs = enabled_service_configs();
     let service_name = opts.service_name;
     let processlogtype = opts.processlogtype.clone();
     let Some(config) = configs.get(service_name) else {
-        ::anyhow::bail!("Unknown Service: {}", service_name);
+        return Err(DrashtaError::unknown_service(service_name).into());
     };
 
     let new_cursor = match (cursor, processlogtype) {
@@ -1786,201 +4917,501 @@ pub fn process_service_logs(
     Ok(new_cursor)
 }
 
+// The text a file cursor is checked against when paging: the whole line, or just its
+// MESSAGE for syslog lines parsed into journal fields
+fn cursor_data(ev: &EventData) -> String {
+    match &ev.raw_msg {
+        RawMsgType::Plain(s) => s.clone(),
+        RawMsgType::Structured(entry) => entry.get("MESSAGE").cloned().unwrap_or_default(),
+    }
+}
+
 pub fn process_manual_events_upto_n(opts: ParserFuncArgs) -> Result<Option<Cursor>> {
     let service_name = opts.service_name;
-    let filter = opts.filter.clone();
+    let keyword = opts.filter.clone();
     let ev_type = opts.ev_type.clone();
     let limit = opts.limit;
     let tx = opts.tx.clone();
 
-    let mut keyword = String::new();
-    if let Some(filter) = filter {
-        keyword = filter;
-    }
-
     let mut cursor: Option<Cursor> = None;
 
-    if service_name == "pkgmanager.events" {
-        let file_name = PathBuf::from("/var/log/pacman.log");
-        let file = File::open(file_name).unwrap();
-        let mut reader = BufReader::with_capacity(128 * 1024, file);
-        let mut count = 0;
-        let mut buf = String::new();
-
-        while reader.read_line(&mut buf).unwrap() > 0 && count < limit {
-            let offset = reader.stream_position()?;
-            if let Some(ev) = parse_pkg_events(buf.trim_end().to_string(), ev_type.clone()) {
-                if !ev.raw_msg.contains_bytes(keyword.as_str()) {
-                    continue;
-                }
-                if tx.blocking_send(ev.clone()).is_err() {
-                    error!("Event Dropped!");
-                }
-                count += 1;
-                if cursor.is_none() {
-                    let timestamp = ev.timestamp.clone();
-                    let mut data = String::new();
-                    if let RawMsgType::Plain(s) = ev.raw_msg.clone() {
-                        data = s;
-                    }
-                    cursor = Some(Cursor {
-                        timestamp,
-                        data,
-                        offset,
-                    })
-                }
+    let (log_path, parser) = manual_source(service_name)?;
+    let file = open_log_file(log_path)?;
+    let mut reader = BufReader::with_capacity(128 * 1024, file);
+    let mut count = 0;
+    let mut buf = String::new();
+
+    while reader.read_line(&mut buf)? > 0 && count < limit {
+        let offset = reader.stream_position()?;
+        record_entry_read(service_name);
+        opts.entries_read.set(opts.entries_read.get() + 1);
+        if let Some(ev) = parser.parse(buf.trim_end().to_string(), ev_type.clone()) {
+            record_event_parsed(service_name, &ev);
+            // Lines without a parsable time are kept, they can't be placed outside the range
+            let secs = event_datetime(&ev).map(|dt| dt.timestamp());
+            if secs.is_some_and(|secs| opts.time_range.after(secs)) {
+                break;
+            }
+            if secs.is_some_and(|secs| opts.time_range.before(secs))
+                || !matches_keyword(&ev, &keyword)
+                || !matches_event_kinds(&ev, &opts.ev_kinds)
+                || !matches_fields(&ev, &opts.field_filters)
+            {
+                buf.clear();
+                continue;
+            }
+            if tx.blocking_send(ev.clone()).is_err() {
+                error!("Event Dropped!");
+                record_event_dropped(service_name);
+            }
+            count += 1;
+            if cursor.is_none() {
+                cursor = Some(Cursor {
+                    timestamp: ev.timestamp.clone(),
+                    data: cursor_data(&ev),
+                    offset,
+                    file_index: 0,
+                })
             }
-            buf.clear();
         }
+        buf.clear();
     }
     Ok(cursor)
 }
 
 pub fn process_manual_events_next(opts: ParserFuncArgs, cursor: Cursor) -> Result<Option<Cursor>> {
     let service_name = opts.service_name;
-    let filter = opts.filter.clone();
+    let keyword = opts.filter.clone();
     let ev_type = opts.ev_type.clone();
     let limit = opts.limit;
     let tx = opts.tx.clone();
-
-    let mut keyword = String::new();
-    if let Some(filter) = filter {
-        keyword = filter;
-    }
     let mut new_cursor: Option<Cursor> = None;
     let mut count = 0;
 
-    if service_name == "pkgmanager.events" {
-        let patterns = [cursor.timestamp.as_bytes()];
+    let (log_path, parser) = manual_source(service_name)?;
+    // Parsers may normalize the timestamp (accesslog.events does), so the raw line is
+    // accepted as well
+    let patterns = [cursor.timestamp.as_bytes(), cursor.data.as_bytes()];
+
+    let Some(path) = rotated_log_path(log_path, cursor.file_index) else {
+        warn!(
+            "Rotated log {}.{} no longer exists, reading from the head",
+            log_path.display(),
+            cursor.file_index
+        );
+        opts.cursor_stale.set(true);
+        return process_manual_events_upto_n(opts);
+    };
+    let mut reader = open_log(&path)?;
+
+    let mut line = String::new();
+
+    reader.seek(std::io::SeekFrom::Start(cursor.offset))?;
+    debug!("Seeking from {}", cursor.offset);
+
+    let mut line_count = 0;
+
+    while reader.read_line(&mut line)? > 0 {
+        line_count += 1;
+        let offset = reader.stream_position()? - line.len() as u64;
+
+        if line_count == 1 {
+            if patterns
+                .iter()
+                .all(|pat| memmem::find(line.as_bytes(), pat).is_none())
+            {
+                warn!(
+                    "Cursor no longer matches {}, reading from the head",
+                    path.display()
+                );
+                opts.cursor_stale.set(true);
+                return process_manual_events_upto_n(opts);
+            }
+            line.clear();
+            continue;
+        }
+
+        record_entry_read(service_name);
+        if let Some(ev) = parser.parse(line.trim_end().to_string(), ev_type.clone()) {
+            record_event_parsed(service_name, &ev);
+            if !matches_keyword(&ev, &keyword)
+                || !matches_event_kinds(&ev, &opts.ev_kinds)
+                || !matches_fields(&ev, &opts.field_filters)
+            {
+                line.clear();
+                continue;
+            }
+            if tx.blocking_send(ev.clone()).is_err() {
+                error!("Event Dropped!");
+                record_event_dropped(service_name);
+                break;
+            }
 
-        let file = File::open("/var/log/pacman.log")?;
-        let mut reader = BufReader::new(&file);
+            count += 1;
 
-        let mut line = String::new();
+            new_cursor = Some(Cursor {
+                timestamp: ev.timestamp.clone(),
+                data: cursor_data(&ev),
+                offset,
+                file_index: cursor.file_index,
+            });
 
-        reader.seek(std::io::SeekFrom::Start(cursor.offset))?;
-        info!("Seeking from {}", cursor.offset);
+            if count >= limit {
+                break;
+            }
+        }
 
-        let mut line_count = 0;
+        line.clear();
+    }
+    Ok(new_cursor)
+}
 
-        while reader.read_line(&mut line)? > 0 {
-            line_count += 1;
-            let offset = reader.stream_position()? - line.len() as u64;
+pub fn process_manual_events_previous(
+    opts: ParserFuncArgs,
+    cursor: Cursor,
+) -> Result<Option<Cursor>> {
+    let service_name = opts.service_name;
+    let keyword = opts.filter.clone();
+    let ev_type = opts.ev_type.clone();
+    let limit = opts.limit;
+    let tx = opts.tx.clone();
+    let mut new_cursor: Option<Cursor> = None;
+    let (log_path, parser) = manual_source(service_name)?;
+    let patterns = [cursor.data.as_bytes()];
+    let mut file_index = cursor.file_index;
+    let (mut offset, mut lines) = match rotated_log_path(log_path, file_index) {
+        Some(path) => read_file_backward(&path, Some(cursor.offset))?,
+        None => (0, Vec::new()),
+    };
+    let mut count = 0;
+    let first_line = lines.first().map(String::as_str).unwrap_or_default();
+    if !patterns
+        .iter()
+        .all(|pat| memmem::find(first_line.as_bytes(), pat).is_some())
+    {
+        warn!(
+            "Cursor no longer matches {}, reading from the tail",
+            log_path.display()
+        );
+        opts.cursor_stale.set(true);
+        file_index = 0;
+        (offset, lines) = read_file_backward(log_path, None)?;
+    }
 
-            if line_count == 1 {
-                if patterns
-                    .iter()
-                    .all(|pat| memmem::find(line.as_bytes(), pat).is_none())
-                {
-                    error!("Line Mismatch!");
-                    break;
-                }
-                line.clear();
-                continue;
+    loop {
+        for line in lines {
+            if count >= limit {
+                break;
             }
-
-            if let Some(ev) = parse_pkg_events(line.trim_end().to_string(), ev_type.clone()) {
-                if !ev.raw_msg.contains_bytes(keyword.as_str()) {
+            record_entry_read(service_name);
+            if let Some(ev) = parser.parse(line.trim_end().to_string(), ev_type.clone()) {
+                record_event_parsed(service_name, &ev);
+                if !matches_keyword(&ev, &keyword)
+                    || !matches_event_kinds(&ev, &opts.ev_kinds)
+                    || !matches_fields(&ev, &opts.field_filters)
+                {
                     continue;
                 }
                 if tx.blocking_send(ev.clone()).is_err() {
-                    error!("Event Dropped!");
-                    break;
+                    record_event_dropped(service_name);
+                    continue;
+                } else {
+                    count += 1;
+                }
+
+                if new_cursor.is_none() {
+                    new_cursor = Some(Cursor {
+                        timestamp: ev.timestamp.clone(),
+                        data: cursor_data(&ev),
+                        offset,
+                        file_index,
+                    });
+                }
+            }
+        }
+
+        if count >= limit {
+            break;
+        }
+
+        file_index += 1;
+        let Some(path) = rotated_log_path(log_path, file_index) else {
+            break;
+        };
+        info!("Continuing into rotated log {}", path.display());
+        (offset, lines) = read_file_backward(&path, None)?;
+    }
+    Ok(new_cursor)
+}
+
+fn pacman_log() -> &'static Path {
+    &crate::config::config().pacman_log
+}
+
+fn access_log() -> &'static Path {
+    &crate::config::config().access_log
+}
+
+fn auth_log() -> &'static Path {
+    &crate::config::config().auth_log
+}
+
+// Parses one line of a flat-file source: either with a manual service's own parser, or
+// by rebuilding the journal fields from a syslog line for a journal service's file_source
+#[derive(Clone)]
+enum LineParser {
+    Manual(ParserFnForManual),
+    Syslog(ParserFn, Vec<&'static str>),
+}
+
+impl LineParser {
+    fn parse(&self, line: String, ev_type: Option<Vec<&str>>) -> Option<EventData> {
+        match self {
+            LineParser::Manual(parserfn) => parserfn(line, ev_type),
+            LineParser::Syslog(parserfn, identifiers) => {
+                let entry = syslog_entry(&line)?;
+                let identifier = entry.get("SYSLOG_IDENTIFIER")?;
+                if !identifiers.contains(&identifier.as_str()) {
+                    return None;
                 }
+                parserfn(entry, ev_type)
+            }
+        }
+    }
+}
+
+fn syslog_entry(line: &str) -> Option<Entry> {
+    let caps = SYSLOG_LINE_REGEX.captures(line)?;
+    let datetime = match (caps.get(1), caps.get(2)) {
+        (Some(ts), _) => syslog_datetime(ts.as_str(), Local::now()),
+        (_, Some(ts)) => DateTime::parse_from_rfc3339(ts.as_str())
+            .ok()
+            .map(|dt| dt.with_timezone(&Local)),
+        _ => None,
+    }?;
+
+    let mut entry = Entry::new();
+    entry.insert(
+        "_SOURCE_REALTIME_TIMESTAMP".to_string(),
+        datetime.timestamp_micros().to_string(),
+    );
+    entry.insert("_HOSTNAME".to_string(), caps[3].to_string());
+    entry.insert("SYSLOG_IDENTIFIER".to_string(), caps[4].to_string());
+    entry.insert("_COMM".to_string(), caps[4].to_string());
+    if let Some(pid) = caps.get(5) {
+        entry.insert("_PID".to_string(), pid.as_str().to_string());
+    }
+    entry.insert("MESSAGE".to_string(), caps[6].to_string());
+    Some(entry)
+}
+
+// A syslog line only carries the program name, so match it against the service's
+// _COMM / SYSLOG_IDENTIFIER values and its unit names without ".service"
+fn syslog_identifiers(config: &ServiceConfig) -> Vec<&'static str> {
+    config
+        .matches
+        .iter()
+        .flatten()
+        .filter_map(|&(field, value)| match field {
+            "_COMM" | "SYSLOG_IDENTIFIER" => Some(value),
+            "_SYSTEMD_UNIT" => value.strip_suffix(".service"),
+            _ => None,
+        })
+        .collect()
+}
+
+fn manual_source(service_name: &str) -> Result<(&'static Path, LineParser)> {
+    let configs = enabled_service_configs();
+    let Some(config) = configs.get(service_name) else {
+        return Err(DrashtaError::unknown_service(service_name).into());
+    };
+    match (&config.parser, config.file_source) {
+        (&ParserFunctionType::ParserFnForManual(parserfn), Some(path)) => {
+            Ok((path, LineParser::Manual(parserfn)))
+        }
+        (&ParserFunctionType::ParserFn(parserfn), Some(path)) => Ok((
+            path,
+            LineParser::Syslog(parserfn, syslog_identifiers(config)),
+        )),
+        _ => Err(anyhow!("{service_name} has no file source")),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum LogSource {
+    #[default]
+    Auto,
+    Journal,
+    File,
+}
+
+impl LogSource {
+    pub fn from_query(source: Option<&str>) -> Result<Self> {
+        match source.unwrap_or("auto") {
+            "auto" => Ok(LogSource::Auto),
+            "journal" => Ok(LogSource::Journal),
+            "file" => Ok(LogSource::File),
+            other => Err(anyhow!(
+                "Unknown source `{other}`, expected one of: auto, journal, file"
+            )),
+        }
+    }
+}
+
+// Manual services always read their file. Journal services with a file_source read it
+// when asked to, when paging with a file cursor, or (auto) when the journal holds
+// nothing for them, e.g. in containers where rsyslog is the only sink
+pub fn reads_file_source(
+    service_name: &str,
+    source: LogSource,
+    cursor: Option<&CursorType>,
+    boot: Option<i32>,
+) -> Result<bool> {
+    let configs = enabled_service_configs();
+    let Some(config) = configs.get(service_name) else {
+        return Err(DrashtaError::unknown_service(service_name).into());
+    };
+    if MANUAL_PARSE_EVENTS.contains(&service_name) {
+        return Ok(true);
+    }
+    if config.file_source.is_none() {
+        return match source {
+            LogSource::File => Err(anyhow!("{service_name} has no file source")),
+            _ => Ok(false),
+        };
+    }
+
+    match (source, cursor) {
+        (LogSource::File, _) => Ok(true),
+        (LogSource::Journal, _) => Ok(false),
+        (LogSource::Auto, Some(CursorType::Manual(_))) => Ok(true),
+        (LogSource::Auto, Some(CursorType::Journal(_) | CursorType::Composite(_))) => Ok(false),
+        (LogSource::Auto, None) => {
+            let mut journal = open_journal()?;
+            add_service_matches(&mut journal, config, &[], boot)?;
+            Ok(journal.next_entry()?.is_none())
+        }
+    }
+}
+
+pub fn manual_log_path(service_name: &str) -> Option<&'static Path> {
+    if !MANUAL_PARSE_EVENTS.contains(&service_name) {
+        return None;
+    }
+    enabled_service_configs().get(service_name)?.file_source
+}
+
+#[derive(Serialize, Debug)]
+pub struct SourceStatus {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<&'static Path>,
+    pub reachable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub fn check_sources() -> Vec<SourceStatus> {
+    let journal = open_journal_source();
+    let mut sources = vec![SourceStatus {
+        name: "journal".to_string(),
+        path: crate::config::config().journal_dir.as_deref(),
+        reachable: journal.is_ok(),
+        error: journal.err().map(|e| e.to_string()),
+    }];
+
+    for service_name in MANUAL_PARSE_EVENTS.iter() {
+        let Some(path) = manual_log_path(service_name) else {
+            continue;
+        };
+        let metadata = std::fs::metadata(path);
+        sources.push(SourceStatus {
+            name: service_name.to_string(),
+            path: Some(path),
+            reachable: metadata.is_ok(),
+            error: metadata.err().map(|e| e.to_string()),
+        });
+    }
 
-                count += 1;
+    sources
+}
 
-                let timestamp = ev.timestamp.clone();
-                let data = match ev.raw_msg.clone() {
-                    RawMsgType::Plain(s) => s,
-                    _ => String::new(),
-                };
-                new_cursor = Some(Cursor {
-                    timestamp,
-                    data,
-                    offset,
-                });
+// Maps the distro from /etc/os-release (ID, then ID_LIKE) to its package manager
+pub fn detect_package_manager() -> Option<&'static str> {
+    let os_release = std::fs::read_to_string("/etc/os-release").ok()?;
+    let field = |key: &str| {
+        os_release.lines().find_map(|line| {
+            line.strip_prefix(key)
+                .and_then(|v| v.strip_prefix('='))
+                .map(|v| v.trim_matches('"').to_string())
+        })
+    };
 
-                if count >= limit {
-                    break;
-                }
-            }
+    let ids = [field("ID"), field("ID_LIKE")].into_iter().flatten();
+    ids.flat_map(|v| v.split_whitespace().map(str::to_string).collect::<Vec<_>>())
+        .find_map(|id| match id.as_str() {
+            "arch" | "manjaro" | "endeavouros" => Some("pacman"),
+            "debian" | "ubuntu" => Some("apt"),
+            "fedora" | "rhel" | "centos" | "amzn" => Some("dnf"),
+            "opensuse" | "suse" | "sles" => Some("zypper"),
+            "alpine" => Some("apk"),
+            "gentoo" => Some("portage"),
+            "void" => Some("xbps"),
+            "nixos" => Some("nix"),
+            _ => None,
+        })
+}
 
-            line.clear();
-        }
+pub fn rotated_log_path(base: &Path, index: usize) -> Option<PathBuf> {
+    if index == 0 {
+        return Some(base.to_path_buf());
     }
-    Ok(new_cursor)
-}
 
-pub fn process_manual_events_previous(
-    opts: ParserFuncArgs,
-    cursor: Cursor,
-) -> Result<Option<Cursor>> {
-    let service_name = opts.service_name;
-    let filter = opts.filter.clone();
-    let ev_type = opts.ev_type.clone();
-    let limit = opts.limit;
-    let tx = opts.tx.clone();
-    let mut new_cursor: Option<Cursor> = None;
-    let mut keyword = String::new();
+    let with_suffix = |suffix: String| {
+        let mut name = base.as_os_str().to_owned();
+        name.push(suffix);
+        PathBuf::from(name)
+    };
 
-    if let Some(filter) = filter {
-        keyword = filter;
+    let plain = with_suffix(format!(".{index}"));
+    if plain.exists() {
+        return Some(plain);
     }
 
-    if service_name == "pkgmanager.events" {
-        let patterns = [cursor.timestamp.as_bytes(), cursor.data.as_bytes()];
-        let offset = cursor.offset;
-        let lines = read_file_backward("/var/log/pacman.log", offset)?;
-        let mut count = 0;
-        if patterns
-            .iter()
-            .all(|pat| memmem::find(lines.first().unwrap().as_bytes(), pat).is_some())
-        {
-            for line in lines {
-                if count >= limit {
-                    break;
-                }
-                if let Some(ev) = parse_pkg_events(line.trim_end().to_string(), ev_type.clone()) {
-                    if !ev.raw_msg.contains_bytes(keyword.as_str()) {
-                        continue;
-                    }
-                    if tx.blocking_send(ev.clone()).is_err() {
-                        continue;
-                    } else {
-                        count += 1;
-                    }
+    let gz = with_suffix(format!(".{index}.gz"));
+    gz.exists().then_some(gz)
+}
 
-                    if new_cursor.is_none() {
-                        let timestamp = ev.timestamp.clone();
-                        let data = match ev.raw_msg.clone() {
-                            RawMsgType::Plain(s) => s,
-                            _ => String::new(),
-                        };
-                        new_cursor = Some(Cursor {
-                            timestamp,
-                            data,
-                            offset,
-                        });
-                    }
-                }
-            }
-        } else {
-            error!("Line Mismatch!");
-        }
+trait LogReader: BufRead + Seek {}
+impl<T: BufRead + Seek> LogReader for T {}
+
+fn open_log(path: &Path) -> Result<Box<dyn LogReader>> {
+    let file = open_log_file(path)?;
+
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        let mut buf = Vec::new();
+        GzDecoder::new(file)
+            .read_to_end(&mut buf)
+            .map_err(|e| anyhow!("Failed to decompress {}: {e}", path.display()))?;
+        return Ok(Box::new(std::io::Cursor::new(buf)));
     }
-    Ok(new_cursor)
+
+    Ok(Box::new(BufReader::new(file)))
 }
 
-pub fn read_file_backward(path: &str, offset: u64) -> Result<Vec<String>> {
-    let mut file = File::open(path)?;
+// Returns the offset actually used (the end of the file when `offset` is None)
+// along with the line at that offset followed by every line before it, newest first.
+pub fn read_file_backward(path: &Path, offset: Option<u64>) -> Result<(u64, Vec<String>)> {
+    let mut reader = open_log(path)?;
     let chunk_size = 8192;
     let mut out = Vec::new();
     let mut partial_line = String::new();
+
+    let len = reader.seek(SeekFrom::End(0))?;
+    let offset = offset.map_or(len, |o| o.min(len));
     let mut current_pos = offset;
 
-    file.seek(std::io::SeekFrom::Start(offset))?;
-    let mut reader = BufReader::new(&file);
+    reader.seek(SeekFrom::Start(offset))?;
     let mut line_at_offset = String::new();
     reader.read_line(&mut line_at_offset)?;
 
@@ -1988,15 +5419,13 @@ pub fn read_file_backward(path: &str, offset: u64) -> Result<Vec<String>> {
         out.push(line_at_offset.trim_end_matches('\n').to_string());
     }
 
-    file = File::open(path)?;
-
     while current_pos > 0 {
         let read_size = chunk_size.min(current_pos as usize);
         current_pos -= read_size as u64;
 
         let mut buf = vec![0u8; read_size];
-        file.seek(std::io::SeekFrom::Start(current_pos))?;
-        file.read_exact(&mut buf)?;
+        reader.seek(SeekFrom::Start(current_pos))?;
+        reader.read_exact(&mut buf)?;
 
         let chunk = String::from_utf8_lossy(&buf).to_string();
 
@@ -2016,13 +5445,43 @@ pub fn read_file_backward(path: &str, offset: u64) -> Result<Vec<String>> {
         out.push(partial_line);
     }
 
-    Ok(out)
+    Ok((offset, out))
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub enum CursorType {
     Journal(String),
     Manual(Cursor),
+    // One position per service, for streams that read several services at once
+    Composite(BTreeMap<String, CursorType>),
+}
+
+impl CursorType {
+    // A composite cursor's position for `service_name`, any other cursor as is
+    pub fn for_service(self, service_name: &str) -> Result<CursorType, DrashtaError> {
+        match self {
+            CursorType::Composite(mut cursors) => cursors.remove(service_name).ok_or_else(|| {
+                DrashtaError::CursorParse(format!("the cursor has no position for {service_name}"))
+            }),
+            cursor => Ok(cursor),
+        }
+    }
+}
+
+impl fmt::Display for CursorType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CursorType::Journal(cursor) => write!(f, "Journal:{cursor}"),
+            CursorType::Manual(cursor) => {
+                let json = serde_json::to_string(cursor).map_err(|_| fmt::Error)?;
+                write!(f, "Manual:{json}")
+            }
+            CursorType::Composite(cursors) => {
+                let json = serde_json::to_string(cursors).map_err(|_| fmt::Error)?;
+                write!(f, "Composite:{json}")
+            }
+        }
+    }
 }
 
 impl FromStr for CursorType {
@@ -2036,22 +5495,27 @@ impl FromStr for CursorType {
             serde_json::from_str::<Cursor>(json_str)
                 .map(CursorType::Manual)
                 .map_err(|e| format!("Failed to parse Manual cursor: {e}"))
+        } else if let Some(val) = s.strip_prefix("Composite:") {
+            // Members are nested as their JSON form, so serde_json does all the escaping
+            serde_json::from_str::<BTreeMap<String, CursorType>>(val)
+                .map(CursorType::Composite)
+                .map_err(|e| format!("Failed to parse Composite cursor: {e}"))
         } else {
             Err(format!("Unknown cursor variant: {s}"))
         }
     }
 }
 
-pub fn deserialize_cursor<'de, D>(deserializer: D) -> Result<Option<CursorType>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s: Option<String> = Option::deserialize(deserializer)?;
-    match s {
-        Some(s) if !s.is_empty() => serde_json::from_str::<CursorType>(&s)
-            .map(Some)
-            .map_err(serde::de::Error::custom),
-        _ => Ok(None),
+// Cursors arrive as the JSON the `cursor` event carried, or in the prefixed
+// Journal:/Manual:/Composite: form
+pub fn parse_cursor(cursor: Option<&str>) -> Result<CursorType, DrashtaError> {
+    match cursor {
+        Some(s) if s.starts_with('{') => serde_json::from_str::<CursorType>(s)
+            .map_err(|e| DrashtaError::CursorParse(e.to_string())),
+        Some(s) if !s.is_empty() => s.parse::<CursorType>().map_err(DrashtaError::CursorParse),
+        _ => Err(DrashtaError::CursorParse(
+            "a cursor is required".to_string(),
+        )),
     }
 }
 
@@ -2060,9 +5524,19 @@ pub fn handle_service_event(opts: ParserFuncArgs) -> Result<Option<CursorType>>
     let service_name = opts.service_name;
     let cursor = opts.cursor.clone();
     let processlogtype = opts.processlogtype.clone();
-    let is_manual_service = MANUAL_PARSE_EVENTS.contains(&service_name);
+    let is_file_source = reads_file_source(service_name, opts.source, cursor.as_ref(), opts.boot)?;
+
+    if is_file_source && opts.boot.is_some() {
+        anyhow::bail!("The boot filter is not supported for {service_name}'s file source");
+    }
 
-    if is_manual_service {
+    if is_file_source && !opts.match_fields.is_empty() {
+        anyhow::bail!(
+            "Journal field matches (match_fields, max_priority) are not supported for {service_name}'s file source"
+        );
+    }
+
+    if is_file_source {
         match processlogtype {
             ProcessLogType::ProcessInitialLogs => {
                 if let Some(c) = process_manual_events_upto_n(opts)? {
@@ -2070,24 +5544,24 @@ pub fn handle_service_event(opts: ParserFuncArgs) -> Result<Option<CursorType>>
                 }
             }
             ProcessLogType::ProcessOlderLogs => {
-                if let Some(CursorType::Manual(c)) = cursor {
-                    if let Some(next_c) = process_manual_events_next(opts, c)? {
-                        cursor_type = Some(CursorType::Manual(next_c));
-                    }
+                if let Some(CursorType::Manual(c)) = cursor
+                    && let Some(next_c) = process_manual_events_next(opts, c)?
+                {
+                    cursor_type = Some(CursorType::Manual(next_c));
                 }
             }
             ProcessLogType::ProcessPreviousLogs => {
-                if let Some(CursorType::Manual(c)) = cursor {
-                    if let Some(prev_c) = process_manual_events_previous(opts, c)? {
-                        cursor_type = Some(CursorType::Manual(prev_c));
-                    }
+                if let Some(CursorType::Manual(c)) = cursor
+                    && let Some(prev_c) = process_manual_events_previous(opts, c)?
+                {
+                    cursor_type = Some(CursorType::Manual(prev_c));
                 }
             }
         }
     } else {
         match cursor {
             Some(CursorType::Journal(c)) => {
-                if let Ok(new_c) = handle_services!(
+                let new_c = handle_services!(
                     opts.clone(),
                     Some(c.clone()),
                     "sshd.events",
@@ -2098,13 +5572,31 @@ pub fn handle_service_event(opts: ParserFuncArgs) -> Result<Option<CursorType>>
                     "kernel.events",
                     "userchange.events",
                     "configchange.events",
+                    "systemd.events",
+                    "fail2ban.events",
+                    "timesync.events",
+                    "docker.events",
+                    "security.events",
+                    "bluetooth.events",
+                    "apparmor.events",
+                    "smartd.events",
+                    "wifi.events",
+                    "power.events",
+                    "mail.events",
+                    "storage.events",
+                    "flatpak.events",
+                    "fwlog.events",
+                    "display.events",
+                    "coredump.events",
+                    "resolved.events",
                     "pkgmanager.events",
-                ) {
+                )?;
+                if !new_c.is_empty() {
                     cursor_type = Some(CursorType::Journal(new_c));
                 }
             }
             None => {
-                if let Ok(new_c) = handle_services!(
+                let new_c = handle_services!(
                     opts,
                     None,
                     "sshd.events",
@@ -2115,8 +5607,26 @@ pub fn handle_service_event(opts: ParserFuncArgs) -> Result<Option<CursorType>>
                     "kernel.events",
                     "userchange.events",
                     "configchange.events",
+                    "systemd.events",
+                    "fail2ban.events",
+                    "timesync.events",
+                    "docker.events",
+                    "security.events",
+                    "bluetooth.events",
+                    "apparmor.events",
+                    "smartd.events",
+                    "wifi.events",
+                    "power.events",
+                    "mail.events",
+                    "storage.events",
+                    "flatpak.events",
+                    "fwlog.events",
+                    "display.events",
+                    "coredump.events",
+                    "resolved.events",
                     "pkgmanager.events",
-                ) {
+                )?;
+                if !new_c.is_empty() {
                     cursor_type = Some(CursorType::Journal(new_c));
                 }
             }
@@ -2126,148 +5636,324 @@ pub fn handle_service_event(opts: ParserFuncArgs) -> Result<Option<CursorType>>
     Ok(cursor_type)
 }
 
-const MAX_FAILED_EVENTS: usize = 5_000;
+// The last `limit` matching events of a file source, oldest first, and the offset a live
+// follow should continue from
+pub fn read_last_events_manual(
+    service_name: &str,
+    limit: usize,
+    filter: &Option<KeywordFilter>,
+    field_filters: &[FieldFilter],
+    ev_type: Option<Vec<&str>>,
+) -> Result<(Vec<EventData>, u64)> {
+    let (log_path, parser) = manual_source(service_name)?;
+    let (ev_type, ev_kinds) = split_event_type_filter(ev_type);
+
+    let file = open_log_file(log_path)?;
+    let mut reader = BufReader::with_capacity(128 * 1024, file);
+    let mut events = VecDeque::with_capacity(limit);
+    let mut buf = String::new();
+
+    while reader.read_line(&mut buf)? > 0 {
+        record_entry_read(service_name);
+        if let Some(ev) = parser.parse(buf.trim_end().to_string(), ev_type.clone()) {
+            record_event_parsed(service_name, &ev);
+            if matches_keyword(&ev, filter)
+                && matches_event_kinds(&ev, &ev_kinds)
+                && matches_fields(&ev, field_filters)
+            {
+                if events.len() >= limit {
+                    events.pop_front();
+                }
+                events.push_back(ev);
+            }
+        }
+        buf.clear();
+    }
+
+    let offset = reader.stream_position()?;
+    Ok((events.into(), offset))
+}
 
+#[allow(clippy::too_many_arguments)]
 pub fn read_journal_logs_manual(
     service_name: &str,
-    filter: Option<String>,
+    filter: Option<KeywordFilter>,
+    field_filters: Vec<FieldFilter>,
     ev_type: Option<Vec<&str>>,
+    from_offset: Option<u64>,
     tx: tokio::sync::broadcast::Sender<EventData>,
+    stop: &AtomicBool,
+    persist: bool,
 ) -> anyhow::Result<()> {
-    let configs = get_service_configs();
-    let mut failed_ev_buf = VecDeque::with_capacity(MAX_FAILED_EVENTS);
+    let (log_path, parser) = manual_source(service_name)?;
+    tail_log_file(
+        service_name,
+        log_path,
+        &parser,
+        filter,
+        field_filters,
+        ev_type,
+        from_offset,
+        tx,
+        stop,
+        persist,
+    )
+}
 
-    let Some(config) = configs.get(service_name) else {
-        anyhow::bail!("Unknown Service: {}", service_name);
-    };
+const TAILED_FILE_MASK: WatchMask = WatchMask::MODIFY
+    .union(WatchMask::MOVE_SELF)
+    .union(WatchMask::DELETE_SELF);
 
-    let ParserFunctionType::ParserFnForManual(parserfn) = config.parser else {
-        return Err(anyhow!("ParserFnForManual required here"));
-    };
+// Reads whatever was appended to `file` since `pos`, returning it and the new position
+fn read_appended(file: &mut File, pos: u64) -> Result<(String, u64)> {
+    file.seek(SeekFrom::Start(pos))?;
+    let mut buf = Vec::new();
+    let read = file.read_to_end(&mut buf)? as u64;
+    Ok((String::from_utf8_lossy(&buf).into_owned(), pos + read))
+}
 
-    let keyword = filter.unwrap_or_default();
+// Follows `log_path` from `from_offset` (or its current end) and sends every line
+// `parser` turns into a matching event. A truncated file (copytruncate) is read again
+// from the start; a file that was renamed or deleted (create-style logrotate) is read to
+// its end and the new file at `log_path` followed instead, which is why the parent
+// directory is watched as well. Returns once `stop` is set
+#[allow(clippy::too_many_arguments)]
+fn tail_log_file(
+    service_name: &str,
+    log_path: &Path,
+    parser: &LineParser,
+    filter: Option<KeywordFilter>,
+    field_filters: Vec<FieldFilter>,
+    ev_type: Option<Vec<&str>>,
+    from_offset: Option<u64>,
+    tx: tokio::sync::broadcast::Sender<EventData>,
+    stop: &AtomicBool,
+    persist: bool,
+) -> anyhow::Result<()> {
+    let max_failed_events = crate::config::config().buffer_size;
+    let poll_interval = Duration::from_millis(crate::config::config().poll_interval_ms);
+    let mut failed_ev_buf = VecDeque::with_capacity(max_failed_events);
+    let mut overflowed: u64 = 0;
 
-    let mut file = File::open("/var/log/pacman.log")?;
+    let keyword = filter;
+    let (ev_type, ev_kinds) = split_event_type_filter(ev_type);
+
+    let mut file = open_log_file(log_path)?;
     let mut inotify = Inotify::init()?;
-    inotify.watches().add(
-        "/var/log/pacman.log",
-        WatchMask::MODIFY | WatchMask::MOVE_SELF | WatchMask::DELETE_SELF | WatchMask::CREATE,
-    )?;
+    let mut file_watch = inotify.watches().add(log_path, TAILED_FILE_MASK)?;
+    if let Some(dir) = log_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        inotify
+            .watches()
+            .add(dir, WatchMask::CREATE | WatchMask::MOVED_TO)?;
+    }
 
     let mut buffer = [0u8; 4096];
-    let mut last_pos = file.seek(SeekFrom::End(0))?;
-    if service_name == "pkgmanager.events" {
-        loop {
-            let events = inotify.read_events_blocking(&mut buffer)?;
-            for _ev in events {
-                let new_len = file.metadata()?.len();
-
-                if new_len < last_pos {
-                    file = File::open("/var/log/pacman.log")?;
-                    last_pos = 0;
-                }
+    let mut last_pos = match from_offset {
+        Some(offset) => offset,
+        None => file.seek(SeekFrom::End(0))?,
+    };
+    loop {
+        // Events only say that something changed, the file itself says what. Polled
+        // rather than blocking, so a stop is noticed while the file is quiet
+        if stop.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        match inotify.read_events(&mut buffer) {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                sleep(poll_interval);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        }
 
-                if new_len > last_pos {
-                    let read_len = new_len - last_pos;
-
-                    file.seek(SeekFrom::Start(last_pos))?;
-                    let mut buf = Vec::with_capacity(8192);
-                    buf.resize(read_len as usize, 0);
-
-                    file.read_exact(&mut buf)?;
-
-                    let log_line = String::from_utf8_lossy(&buf);
-
-                    for line in log_line.lines() {
-                        if let Some(ev) = parserfn(line.to_string(), ev_type.clone()) {
-                            if !ev.raw_msg.contains_bytes(&keyword) {
-                                continue;
-                            }
-                            if tx.send(ev.clone()).is_err() {
-                                info!("No active receiver, buffering event");
-                                if failed_ev_buf.len() >= MAX_FAILED_EVENTS {
-                                    warn!(
-                                        "Buffer full with - {} events, dropping oldest to prevent memory increase",
-                                        failed_ev_buf.len()
-                                    );
-                                    failed_ev_buf.pop_front();
-                                }
-                                failed_ev_buf.push_back(ev);
-                            }
-                        }
-                    }
-                    if tx.receiver_count() > 0 && !failed_ev_buf.is_empty() {
-                        info!("Receiver reconnected, flushing buffering events...");
-                        let mut still_failed = VecDeque::new();
-                        while let Some(ev) = failed_ev_buf.pop_front() {
-                            if tx.send(ev.clone()).is_err() {
-                                still_failed.push_back(ev);
-                                break;
-                            }
-                        }
-                        failed_ev_buf = still_failed;
-                    }
+        let mut appended = String::new();
+        let replaced = std::fs::metadata(log_path)
+            .is_ok_and(|meta| meta.ino() != file.metadata().map_or(0, |m| m.ino()));
+        if replaced {
+            info!("{} was rotated, following the new file", log_path.display());
+            (appended, _) = read_appended(&mut file, last_pos)?;
+            file = open_log_file(log_path)?;
+            last_pos = 0;
+            let _ = inotify.watches().remove(file_watch);
+            file_watch = inotify.watches().add(log_path, TAILED_FILE_MASK)?;
+        }
 
-                    last_pos = new_len;
+        if file.metadata()?.len() < last_pos {
+            last_pos = 0;
+        }
+        let (text, new_pos) = read_appended(&mut file, last_pos)?;
+        appended.push_str(&text);
+        last_pos = new_pos;
+
+        for line in appended.lines() {
+            record_entry_read(service_name);
+            if let Some(ev) = parser.parse(line.to_string(), ev_type.clone()) {
+                record_event_parsed(service_name, &ev);
+                if !matches_keyword(&ev, &keyword)
+                    || !matches_event_kinds(&ev, &ev_kinds)
+                    || !matches_fields(&ev, &field_filters)
+                {
+                    continue;
+                }
+                if persist {
+                    persist_event(service_name, &ev);
+                }
+                if tx.send(ev.clone()).is_err() {
+                    debug!("No active receiver, buffering event");
+                    record_event_dropped(service_name);
+                    if failed_ev_buf.len() >= max_failed_events {
+                        overflowed += 1;
+                        warn!(
+                            "Buffer full with - {} events, dropping oldest to prevent memory increase ({overflowed} dropped so far)",
+                            failed_ev_buf.len()
+                        );
+                        failed_ev_buf.pop_front();
+                        record_buffer_overflow(service_name);
+                    }
+                    failed_ev_buf.push_back(ev);
+                }
+            }
+        }
+        if tx.receiver_count() > 0 && !failed_ev_buf.is_empty() {
+            info!("Receiver reconnected, flushing buffering events...");
+            let mut still_failed = VecDeque::new();
+            while let Some(ev) = failed_ev_buf.pop_front() {
+                if tx.send(ev.clone()).is_err() {
+                    still_failed.push_back(ev);
+                    break;
                 }
             }
+            failed_ev_buf = still_failed;
         }
+        set_buffer_fill(service_name, failed_ev_buf.len());
     }
+}
 
-    Ok(())
+// The last `limit` matching journal events, oldest first, read backwards from the tail.
+// The cursor is the newest entry seen, where a live follow should continue from
+pub fn read_last_events(
+    service_name: &str,
+    limit: usize,
+    filter: &Option<KeywordFilter>,
+    field_filters: &[FieldFilter],
+    match_fields: &[JournalMatch],
+    ev_type: Option<Vec<&str>>,
+) -> Result<(Vec<EventData>, Option<String>)> {
+    let configs = enabled_service_configs();
+    let Some(config) = configs.get(service_name) else {
+        return Err(DrashtaError::unknown_service(service_name).into());
+    };
+    let ParserFunctionType::ParserFn(parserfn) = config.parser else {
+        return Err(anyhow!("ParserFn required here"));
+    };
+
+    let mut journal = open_journal()?;
+    add_service_matches(&mut journal, config, match_fields, None)?;
+    let (ev_type, ev_kinds) = split_event_type_filter(ev_type);
+
+    journal.seek_tail()?;
+    let mut events = Vec::with_capacity(limit);
+    let mut newest_cursor = None;
+    while events.len() < limit {
+        let Some(data) = journal.previous_entry()? else {
+            break;
+        };
+        if newest_cursor.is_none() {
+            newest_cursor = Some(journal.cursor()?);
+        }
+        record_entry_read(service_name);
+        let data = with_receive_time(&journal, data);
+        if let Some(ev) = parserfn(data, ev_type.clone()).map(with_severity) {
+            record_event_parsed(service_name, &ev);
+            if matches_keyword(&ev, filter)
+                && matches_event_kinds(&ev, &ev_kinds)
+                && matches_fields(&ev, field_filters)
+            {
+                events.push(ev);
+            }
+        }
+    }
+
+    events.reverse();
+    Ok((events, newest_cursor))
 }
 
+// Follows the journal for `service_name` until `stop` is set. `persist` writes the events
+// to the --persist store as well
+#[allow(clippy::too_many_arguments)]
 pub fn read_journal_logs(
     service_name: &str,
-    filter: Option<String>,
+    filter: Option<KeywordFilter>,
+    field_filters: Vec<FieldFilter>,
+    match_fields: Vec<JournalMatch>,
     ev_type: Option<Vec<&str>>,
+    after_cursor: Option<String>,
     tx: tokio::sync::broadcast::Sender<EventData>,
+    stop: &AtomicBool,
+    persist: bool,
 ) -> anyhow::Result<()> {
-    let configs = get_service_configs();
-    let mut failed_ev_buf = VecDeque::with_capacity(MAX_FAILED_EVENTS);
+    let configs = enabled_service_configs();
+    let max_failed_events = crate::config::config().buffer_size;
+    let mut failed_ev_buf = VecDeque::with_capacity(max_failed_events);
+    let mut overflowed: u64 = 0;
 
     let Some(config) = configs.get(service_name) else {
-        anyhow::bail!("Unknown Service: {}", service_name);
+        return Err(DrashtaError::unknown_service(service_name).into());
     };
 
-    let mut journal: Journal = journal::OpenOptions::default()
-        .all_namespaces(true)
-        .open()?;
+    let mut journal = open_journal()?;
 
     let ParserFunctionType::ParserFn(parserfn) = config.parser else {
         return Err(anyhow!("ParserFn required here"));
     };
 
-    if let Some(values) = &config.matches {
-        for (field, val) in values {
-            journal.match_add(field, val.to_string())?;
-            journal.match_or()?;
-        }
-    }
-
-    let keyword = filter.unwrap_or_default();
+    add_service_matches(&mut journal, config, &match_fields, None)?;
 
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)?
-        .as_micros() as u64;
+    let keyword = filter;
+    let (ev_type, ev_kinds) = split_event_type_filter(ev_type);
 
-    journal.seek_realtime_usec(now)?;
+    match after_cursor {
+        Some(cursor) => {
+            journal.seek_cursor(&cursor)?;
+            journal.next_entry()?;
+        }
+        None => {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_micros() as u64;
+            journal.seek_realtime_usec(now)?;
+        }
+    }
+    let poll_interval = Duration::from_millis(crate::config::config().poll_interval_ms);
 
-    loop {
+    while !stop.load(Ordering::Relaxed) {
         while let Some(data) = journal.next_entry()? {
-            if let Some(ev) = parserfn(data, ev_type.clone()) {
-                if !ev.raw_msg.contains_bytes(&keyword) {
+            record_entry_read(service_name);
+            let data = with_receive_time(&journal, data);
+            if let Some(ev) = parserfn(data, ev_type.clone()).map(with_severity) {
+                record_event_parsed(service_name, &ev);
+                if !matches_keyword(&ev, &keyword)
+                    || !matches_event_kinds(&ev, &ev_kinds)
+                    || !matches_fields(&ev, &field_filters)
+                {
                     continue;
                 }
+                if persist {
+                    persist_event(service_name, &ev);
+                }
 
                 if tx.send(ev.clone()).is_err() {
-                    info!("No active receiver, buffering event...");
-                    if failed_ev_buf.len() >= MAX_FAILED_EVENTS {
+                    debug!("No active receiver, buffering event...");
+                    record_event_dropped(service_name);
+                    if failed_ev_buf.len() >= max_failed_events {
+                        overflowed += 1;
                         warn!(
-                            "Buffer full with - {} events, dropping oldest to prevent memory increase",
+                            "Buffer full with - {} events, dropping oldest to prevent memory increase ({overflowed} dropped so far)",
                             failed_ev_buf.len()
                         );
                         failed_ev_buf.pop_front();
+                        record_buffer_overflow(service_name);
                     }
                     failed_ev_buf.push_back(ev);
                 }
@@ -2287,7 +5973,635 @@ pub fn read_journal_logs(
 
             failed_ev_buf = still_failed;
         }
+        set_buffer_fill(service_name, failed_ev_buf.len());
+
+        // Returns as soon as journald appends, the timeout bounds how long buffered
+        // events wait for a reconnecting client and how late a stop is noticed
+        journal.wait(Some(poll_interval))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(fields: &[(&str, &str)]) -> Entry {
+        fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn entry_datetime_falls_back_to_receive_time() {
+        let received = 1_760_608_801_000_000;
+        let e = entry(&[("__REALTIME_TIMESTAMP", "1760608801000000")]);
+        assert_eq!(
+            entry_datetime(&e),
+            Local.timestamp_micros(received).single()
+        );
+    }
+
+    #[test]
+    fn entry_datetime_prefers_source_realtime() {
+        let e = entry(&[
+            ("_SOURCE_REALTIME_TIMESTAMP", "1760608800000000"),
+            ("__REALTIME_TIMESTAMP", "1760608801000000"),
+            ("SYSLOG_TIMESTAMP", "Oct 16 09:00:00"),
+        ]);
+        assert_eq!(
+            entry_datetime(&e),
+            Local.timestamp_micros(1_760_608_800_000_000).single()
+        );
+    }
+
+    #[test]
+    fn entry_datetime_takes_year_from_receive_time() {
+        let received = Local.with_ymd_and_hms(2026, 1, 1, 0, 0, 5).unwrap();
+        let e = entry(&[
+            (
+                "__REALTIME_TIMESTAMP",
+                &received.timestamp_micros().to_string(),
+            ),
+            ("SYSLOG_TIMESTAMP", "Dec 31 23:59:58 "),
+        ]);
+        let dt = entry_datetime(&e).unwrap();
+        assert_eq!((dt.year(), dt.month(), dt.day()), (2025, 12, 31));
+    }
+
+    fn formatted(dt: Option<DateTime<Local>>) -> String {
+        dt.unwrap().format("%b %e %H:%M:%S").to_string()
+    }
+
+    #[test]
+    fn format_syslog_timestamp_micros_around_2001() {
+        // Below the old 1e15 magnitude threshold, used to be read as millis
+        for micros in [978_307_200_000_000_i64, 999_999_999_999_999] {
+            assert_eq!(
+                format_syslog_timestamp(&micros.to_string(), TimestampUnit::Micros),
+                formatted(Local.timestamp_micros(micros).single())
+            );
+        }
+    }
+
+    #[test]
+    fn format_syslog_timestamp_seconds_after_2286() {
+        // Above the old 1e10 threshold, used to be read as millis
+        for secs in [9_999_999_999_i64, 10_000_000_000, 10_000_000_001] {
+            assert_eq!(
+                format_syslog_timestamp(&secs.to_string(), TimestampUnit::Seconds),
+                formatted(Local.timestamp_opt(secs, 0).single())
+            );
+        }
+    }
+
+    #[test]
+    fn format_syslog_timestamp_millis() {
+        let millis = 1_760_608_800_123_i64;
+        assert_eq!(
+            format_syslog_timestamp(&millis.to_string(), TimestampUnit::Millis),
+            formatted(Local.timestamp_millis_opt(millis).single())
+        );
+    }
+
+    #[test]
+    fn format_syslog_timestamp_rejects_garbage() {
+        assert_eq!(
+            format_syslog_timestamp("", TimestampUnit::Micros),
+            "invalid"
+        );
+        assert_eq!(
+            format_syslog_timestamp("12ab", TimestampUnit::Seconds),
+            "invalid"
+        );
+        assert_eq!(
+            format_syslog_timestamp(&i64::MAX.to_string(), TimestampUnit::Seconds),
+            "invalid"
+        );
+    }
+
+    #[test]
+    fn kernel_events_use_wall_clock_time() {
+        let received = 1_760_608_801_000_000_i64;
+        let e = entry(&[
+            ("MESSAGE", "EXT4-fs (sda1): Remounting filesystem read-only"),
+            ("_SOURCE_BOOTTIME_TIMESTAMP", "12345678"),
+            ("__REALTIME_TIMESTAMP", &received.to_string()),
+        ]);
+        let ev = parse_kernel_events(e, None).unwrap();
+        assert_eq!(
+            ev.timestamp,
+            formatted(Local.timestamp_micros(received).single())
+        );
+    }
+
+    #[test]
+    fn display_kernel_lines_use_receive_time() {
+        let received = 1_760_608_801_000_000_i64;
+        let e = entry(&[
+            (
+                "MESSAGE",
+                "i915 0000:00:02.0: [drm] GPU HANG: ecode 12:1:85dffffb, in Xorg [1234]",
+            ),
+            ("_TRANSPORT", "kernel"),
+            ("__REALTIME_TIMESTAMP", &received.to_string()),
+        ]);
+        let ev = parse_display_events(e, None).unwrap();
+        assert_eq!(
+            ev.timestamp,
+            formatted(Local.timestamp_micros(received).single())
+        );
+    }
+
+    #[test]
+    fn entry_datetime_without_any_time_is_none() {
+        assert_eq!(entry_datetime(&entry(&[("MESSAGE", "x")])), None);
+    }
+
+    #[test]
+    fn parallel_parsing_keeps_entry_order() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1000);
+        let opts = ParserFuncArgs::try_new(
+            "sshd.events",
+            tx,
+            500,
+            ProcessLogType::ProcessInitialLogs,
+            None,
+            Vec::new(),
+            Vec::new(),
+            None,
+            None,
+            None,
+            LogSource::Journal,
+        )
+        .unwrap();
+        let config = &get_service_configs()["sshd.events"];
+        let data: VecDeque<Entry> = (0..500)
+            .map(|i| {
+                entry(&[
+                    (
+                        "MESSAGE",
+                        &format!("Failed password for user{i} from 10.0.0.1 port {i} ssh2"),
+                    ),
+                    ("__REALTIME_TIMESTAMP", "1760608801000000"),
+                ])
+            })
+            .collect();
+
+        let sent = process_entries_in_parallel(data, &opts, config, 300).unwrap();
+
+        assert_eq!(sent, 300);
+        assert_eq!(opts.entries_read().get(), 300);
+        for i in 0..300 {
+            let ev = rx.try_recv().unwrap();
+            assert_eq!(ev.data["user"], format!("user{i}"));
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    fn message(msg: &str) -> Entry {
+        entry(&[
+            ("MESSAGE", msg),
+            ("__REALTIME_TIMESTAMP", "1760608801000000"),
+        ])
+    }
+
+    const VPN_HEADER: &str = r#"vpn[0x55b5e39a6190,5d2b5f35-2d6e-4f5e-9b2f-8e5d2a1a7a10,"office"]"#;
+
+    #[test]
+    fn network_vpn_connecting() {
+        let msg = format!("<info>  [1760608800.1234] {VPN_HEADER}: starting openvpn");
+        let ev = parse_network_events(message(&msg), None).unwrap();
+        assert_eq!(ev.event_type, EventType::Network(NetworkEvent::VpnEvent));
+        assert_eq!(ev.data["uuid"], "5d2b5f35-2d6e-4f5e-9b2f-8e5d2a1a7a10");
+        assert_eq!(ev.data["connection"], "office");
+        assert_eq!(ev.data["vpn_type"], "openvpn");
+
+        let msg = format!(
+            "<info>  [1760608800.2345] {VPN_HEADER}: VPN plugin: state changed: starting (3)"
+        );
+        let ev = parse_network_events(message(&msg), None).unwrap();
+        assert_eq!(ev.data["state"], "connecting");
+    }
+
+    #[test]
+    fn network_vpn_connected() {
+        let msg = format!(
+            "<info>  [1760608801.5678] {VPN_HEADER}: VPN plugin: state changed: started (4)"
+        );
+        let ev = parse_network_events(message(&msg), None).unwrap();
+        assert_eq!(ev.data["connection"], "office");
+        assert_eq!(ev.data["state"], "connected");
+        assert_eq!(ev.data["plugin_state"], "started");
+    }
+
+    #[test]
+    fn network_vpn_failed() {
+        let msg = format!(
+            "<warn>  [1760608802.0001] {VPN_HEADER}: VPN connection: failed to connect: 'Connect timer expired' (9)"
+        );
+        let ev = parse_network_events(message(&msg), None).unwrap();
+        assert_eq!(ev.data["connection"], "office");
+        assert_eq!(ev.data["state"], "failed");
+        assert_eq!(ev.data["reason"], "Connect timer expired");
+    }
+
+    #[test]
+    fn faillock_lock_captures_user() {
+        let msg = "pam_faillock(sshd:auth): Consecutive login failures for user alice account temporarily locked";
+        let ev = parse_login_attempts(message(msg), None).unwrap();
+        assert_eq!(ev.matched_rule.as_deref(), Some("FAILLOCK_LOCKED"));
+        assert_eq!(ev.event_type, EventType::Auth(AuthEvent::Failure));
+        assert_eq!(ev.data["user"], "alice");
+        assert_eq!(ev.data["locked"], "true");
+    }
+
+    #[test]
+    fn faillock_lock_captures_failure_count() {
+        let msg = "pam_faillock(login:auth): The account of bob is locked due to 5 failed logins";
+        let ev = parse_login_attempts(message(msg), None).unwrap();
+        assert_eq!(ev.matched_rule.as_deref(), Some("FAILLOCK_LOCKED_COUNT"));
+        assert_eq!(ev.data["user"], "bob");
+        assert_eq!(ev.data["failures"], "5");
+        assert_eq!(ev.data["locked"], "true");
+
+        let msg = "pam_faillock(sudo:auth): The account is locked due to 3 failed logins";
+        let ev = parse_login_attempts(message(msg), None).unwrap();
+        assert_eq!(ev.data["failures"], "3");
+        assert_eq!(ev.data["locked"], "true");
+    }
+
+    #[test]
+    fn faillock_other_lines_fall_back_to_info() {
+        let msg = "pam_faillock(sshd:auth): User unknown";
+        let ev = parse_login_attempts(message(msg), None).unwrap();
+        assert_eq!(ev.matched_rule.as_deref(), Some("FAILL0CK"));
+        assert_eq!(ev.event_type, EventType::Auth(AuthEvent::Info));
+        assert_eq!(ev.data["msg"], "User unknown");
+        assert!(!ev.data.contains_key("locked"));
+    }
+
+    fn user_change(msg: &str) -> EventData {
+        parse_user_change_events(message(msg), None).unwrap()
+    }
+
+    #[test]
+    fn usermod_group_membership() {
+        let ev = user_change("add 'alice' to group 'wheel'");
+        assert_eq!(ev.matched_rule.as_deref(), Some("USERMOD_GROUP_MEMBER"));
+        assert_eq!(ev.event_type, EventType::User(UserEvent::ModifyUser));
+        assert_eq!(ev.data["name"], "alice");
+        assert_eq!(ev.data["group"], "wheel");
+        assert_eq!(ev.data["action"], "add_to_group");
+        assert!(!ev.data.contains_key("shadow"));
+
+        let ev = user_change("delete 'alice' from shadow group 'wheel'");
+        assert_eq!(ev.data["action"], "remove_from_group");
+        assert_eq!(ev.data["shadow"], "true");
+    }
+
+    #[test]
+    fn usermod_rename() {
+        let ev = user_change("change user name 'alice' to 'alicia'");
+        assert_eq!(ev.matched_rule.as_deref(), Some("USERMOD_RENAME"));
+        assert_eq!(ev.data["name"], "alicia");
+        assert_eq!(ev.data["from"], "alice");
+        assert_eq!(ev.data["to"], "alicia");
+        assert_eq!(ev.data["action"], "rename");
+    }
+
+    #[test]
+    fn usermod_change() {
+        let ev = user_change("change user 'alice' shell from '/bin/bash' to '/bin/zsh'");
+        assert_eq!(ev.matched_rule.as_deref(), Some("USERMOD_CHANGE"));
+        assert_eq!(ev.event_type, EventType::User(UserEvent::ModifyUser));
+        assert_eq!(ev.data["name"], "alice");
+        assert_eq!(ev.data["from"], "/bin/bash");
+        assert_eq!(ev.data["to"], "/bin/zsh");
+        assert_eq!(ev.data["action"], "change_shell");
+
+        let ev = user_change("change user 'alice' UID from '1000' to '1500'");
+        assert_eq!(ev.data["action"], "change_uid");
+    }
+
+    #[test]
+    fn usermod_password() {
+        let ev = user_change("lock user 'alice' password");
+        assert_eq!(ev.matched_rule.as_deref(), Some("USERMOD_PASSWORD"));
+        assert_eq!(ev.data["name"], "alice");
+        assert_eq!(ev.data["action"], "lock");
+
+        let ev = user_change("change user 'alice' password");
+        assert_eq!(ev.data["action"], "change_password");
+    }
+
+    #[test]
+    fn groupmod_change() {
+        let ev = user_change("group changed in /etc/group (group devs/1001, new name: developers)");
+        assert_eq!(ev.matched_rule.as_deref(), Some("GROUPMOD_CHANGE"));
+        assert_eq!(ev.event_type, EventType::User(UserEvent::ModifyGroup));
+        assert_eq!(ev.data["name"], "devs");
+        assert_eq!(ev.data["gid"], "1001");
+        assert_eq!(ev.data["from"], "devs");
+        assert_eq!(ev.data["to"], "developers");
+        assert_eq!(ev.data["action"], "rename");
+
+        let ev = user_change("group changed in /etc/group (group devs/1001, new gid: 2001)");
+        assert_eq!(ev.data["from"], "1001");
+        assert_eq!(ev.data["to"], "2001");
+        assert_eq!(ev.data["action"], "change_gid");
+    }
+
+    #[test]
+    fn user_change_reclassified_rules() {
+        let ev = user_change("groupmod: name=devs, new name=developers");
+        assert_eq!(ev.matched_rule.as_deref(), Some("MODIFY_GROUP"));
+        assert_eq!(ev.event_type, EventType::User(UserEvent::ModifyGroup));
+
+        let ev = user_change("delete home directory: /home/alice");
+        assert_eq!(ev.matched_rule.as_deref(), Some("DELETE_USER_HOME"));
+        assert_eq!(ev.event_type, EventType::User(UserEvent::DeleteUser));
+
+        let ev = user_change("shadow file updated for user alice");
+        assert_eq!(ev.matched_rule.as_deref(), Some("USER_SHADOW_UPDATED"));
+        assert_eq!(ev.event_type, EventType::User(UserEvent::Info));
+
+        let ev = user_change("passwd[4242]: password changed for alice");
+        assert_eq!(ev.matched_rule.as_deref(), Some("USER_PASSWD_CHANGE"));
+        assert_eq!(ev.event_type, EventType::User(UserEvent::PasswdChange));
+        assert_eq!(ev.data["user"], "alice");
+    }
+
+    #[test]
+    fn missing_capture_groups_are_skipped() {
+        let regex = Regex::new(r"^\((\S+)\)\s+CMD(?:\s+\((.+)\))?$").unwrap();
+        let caps = regex.captures("(root) CMD").unwrap();
+        let mut map = AHashMap::new();
+        insert_captures(&mut map, &caps, &[("user", 1), ("cron_cmd", 2), ("pid", 3)]);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map["user"], "root");
+    }
+
+    #[test]
+    fn cron_lines_parse_and_odd_shapes_are_skipped() {
+        let ev =
+            parse_config_change_events(message("(root) CMD (run-parts /etc/cron.hourly)"), None)
+                .unwrap();
+        assert_eq!(ev.event_type, EventType::Config(ConfigEvent::CmdRun));
+        assert_eq!(ev.data["user"], "root");
+        assert_eq!(ev.data["cron_cmd"], "run-parts /etc/cron.hourly");
+
+        assert!(parse_config_change_events(message("(root) CMD"), None).is_none());
+    }
+
+    #[test]
+    fn sshd_publickey_login_captures_key() {
+        let msg = "Accepted publickey for alice from 192.168.1.20 port 51234 ssh2: ED25519 SHA256:3tJq9Wb1yQh0kq8cF2mI0dKcYc5xR0m1pQwE7o1bZkA";
+        let ev = parse_sshd_logs(message(msg), None).unwrap();
+        assert_eq!(ev.matched_rule.as_deref(), Some("AUTH_SUCCESS"));
+        assert_eq!(ev.event_type, EventType::Auth(AuthEvent::Success));
+        assert_eq!(ev.data["method"], "publickey");
+        assert_eq!(ev.data["user"], "alice");
+        assert_eq!(ev.data["ip"], "192.168.1.20");
+        assert_eq!(ev.data["port"], "51234");
+        assert_eq!(ev.data["key_type"], "ED25519");
+        assert_eq!(
+            ev.data["key_fingerprint"],
+            "SHA256:3tJq9Wb1yQh0kq8cF2mI0dKcYc5xR0m1pQwE7o1bZkA"
+        );
+    }
+
+    #[test]
+    fn sshd_password_login_has_no_key() {
+        let msg = "Accepted password for bob from 2001:db8::5 port 40022 ssh2";
+        let ev = parse_sshd_logs(message(msg), None).unwrap();
+        assert_eq!(ev.matched_rule.as_deref(), Some("AUTH_SUCCESS"));
+        assert_eq!(ev.data["method"], "password");
+        assert_eq!(ev.data["user"], "bob");
+        assert_eq!(ev.data["ip"], "2001:db8::5");
+        assert!(!ev.data.contains_key("key_type"));
+        assert!(!ev.data.contains_key("key_fingerprint"));
+    }
+
+    #[test]
+    fn apparmor_denied_captures_profile_and_path() {
+        let msg = r#"audit: type=1400 audit(1760608800.123:456): apparmor="DENIED" operation="open" class="file" profile="/usr/sbin/cupsd" name="/etc/shadow" pid=1234 comm="cupsd" requested_mask="r" denied_mask="r" fsuid=0 ouid=0"#;
+        let ev = parse_apparmor_events(message(msg), None).unwrap();
+        assert_eq!(ev.event_type, EventType::AppArmor(AppArmorEvent::Denied));
+        assert_eq!(ev.data["operation"], "open");
+        assert_eq!(ev.data["profile"], "/usr/sbin/cupsd");
+        assert_eq!(ev.data["path"], "/etc/shadow");
+        assert_eq!(ev.data["comm"], "cupsd");
+        assert_eq!(ev.data["pid"], "1234");
+        assert_eq!(ev.data["denied_mask"], "r");
+        assert_eq!(ev.data["class"], "file");
+    }
+
+    #[test]
+    fn apparmor_allowed_in_complain_mode() {
+        let msg = r#"audit: type=1400 audit(1760608801.000:457): apparmor="ALLOWED" operation="exec" profile="firefox" name="/usr/bin/lsb_release" pid=2345 comm="firefox" requested_mask="x" denied_mask="x" fsuid=1000 ouid=0"#;
+        let ev = parse_apparmor_events(message(msg), None).unwrap();
+        assert_eq!(ev.event_type, EventType::AppArmor(AppArmorEvent::Allowed));
+        assert_eq!(ev.data["operation"], "exec");
+        assert_eq!(ev.data["profile"], "firefox");
+        assert_eq!(ev.data["path"], "/usr/bin/lsb_release");
+        assert_eq!(ev.data["fsuid"], "1000");
+    }
+
+    #[test]
+    fn power_suspend() {
+        let ev = parse_power_events(message("The system will suspend now!"), None).unwrap();
+        assert_eq!(ev.event_type, EventType::Power(PowerEvent::Suspend));
+        assert_eq!(ev.data["state"], "suspend");
+
+        let ev = parse_power_events(message("Entering sleep state 'suspend'..."), None).unwrap();
+        assert_eq!(ev.matched_rule.as_deref(), Some("SYSTEM_SUSPEND"));
+        assert_eq!(ev.data["state"], "suspend");
+    }
+
+    #[test]
+    fn power_resume() {
+        let ev = parse_power_events(message("System returned from sleep state."), None).unwrap();
+        assert_eq!(ev.event_type, EventType::Power(PowerEvent::Resume));
+        assert!(!ev.data.contains_key("operation"));
+
+        let ev = parse_power_events(message("Operation 'sleep' finished."), None).unwrap();
+        assert_eq!(ev.event_type, EventType::Power(PowerEvent::Resume));
+        assert_eq!(ev.data["operation"], "sleep");
+    }
+
+    #[test]
+    fn sudo_command_run() {
+        let msg = "  alice : TTY=pts/0 ; PWD=/home/alice ; USER=root ; COMMAND=/usr/bin/systemctl restart nginx";
+        let ev = parse_sudo_login_attempts(message(msg), None).unwrap();
+        assert_eq!(ev.matched_rule.as_deref(), Some("COMMAND_RUN"));
+        assert_eq!(ev.event_type, EventType::Auth(AuthEvent::CommandRun));
+        assert_eq!(ev.data["invoking_user"], "alice");
+        assert_eq!(ev.data["tty"], "pts/0");
+        assert_eq!(ev.data["pwd"], "/home/alice");
+        assert_eq!(ev.data["target_user"], "root");
+        assert_eq!(ev.data["command"], "/usr/bin/systemctl restart nginx");
+        assert_eq!(ev.data["binary"], "/usr/bin/systemctl");
+        assert!(!ev.data.contains_key("target_group"));
+    }
+
+    #[test]
+    fn sudo_command_run_with_group_and_env() {
+        let msg = "bob : TTY=unknown ; PWD=/srv/my app ; USER=postgres ; GROUP=postgres ; ENV=PGDATA=/var/lib/pgsql ; COMMAND=/usr/bin/psql -c 'select 1'";
+        let ev = parse_sudo_login_attempts(message(msg), None).unwrap();
+        assert_eq!(ev.event_type, EventType::Auth(AuthEvent::CommandRun));
+        assert_eq!(ev.data["pwd"], "/srv/my app");
+        assert_eq!(ev.data["target_user"], "postgres");
+        assert_eq!(ev.data["target_group"], "postgres");
+        assert_eq!(ev.data["binary"], "/usr/bin/psql");
+    }
+
+    #[test]
+    fn storage_rules() {
+        type Fields = &'static [(&'static str, &'static str)];
+        let cases: &[(&str, &str, Fields)] = &[
+            (
+                "mdadm: Fail event detected on md device /dev/md0, component device /dev/sdb1",
+                "MD_DEVICE_FAILED",
+                &[
+                    ("event", "Fail"),
+                    ("array", "/dev/md0"),
+                    ("device", "/dev/sdb1"),
+                ],
+            ),
+            (
+                "DegradedArray event detected on md device /dev/md127",
+                "MD_ARRAY_DEGRADED",
+                &[("event", "DegradedArray"), ("array", "/dev/md127")],
+            ),
+            (
+                "mdadm: DeviceDisappeared event detected on md device /dev/md1",
+                "MD_ARRAY_DISAPPEARED",
+                &[("array", "/dev/md1")],
+            ),
+            (
+                "mdadm: RebuildStarted event detected on md device /dev/md0",
+                "MD_REBUILD_STARTED",
+                &[("array", "/dev/md0")],
+            ),
+            (
+                "mdadm: Rebuild40 event detected on md device /dev/md0",
+                "MD_REBUILD_PROGRESS",
+                &[("percent", "40"), ("array", "/dev/md0")],
+            ),
+            (
+                "mdadm: RebuildFinished event detected on md device /dev/md0, component device  mismatches found: 12 (on raid level 1)",
+                "MD_REBUILD_FINISHED",
+                &[("array", "/dev/md0"), ("mismatches", "12")],
+            ),
+            (
+                "mdadm: SpareActive event detected on md device /dev/md0, component device /dev/sdc1",
+                "MD_SPARE_ACTIVE",
+                &[("array", "/dev/md0"), ("device", "/dev/sdc1")],
+            ),
+            (
+                "md/raid1:md0: Disk failure on sdb1, disabling device.",
+                "KERNEL_MD_DISK_FAILURE",
+                &[("level", "raid1"), ("array", "md0"), ("device", "sdb1")],
+            ),
+            (
+                "md/raid1:md0: Operation continuing on 1 devices.",
+                "KERNEL_MD_DEGRADED",
+                &[("array", "md0"), ("active_devices", "1")],
+            ),
+            (
+                "md: recovery of RAID array md0",
+                "KERNEL_MD_RECOVERY_STARTED",
+                &[("operation", "recovery"), ("array", "md0")],
+            ),
+            (
+                "md: md0: recovery done.",
+                "KERNEL_MD_RECOVERY_DONE",
+                &[("array", "md0"), ("operation", "recovery")],
+            ),
+            (
+                "Device #1 of raid1 array, vg0-lv_data, has failed.",
+                "LVM_RAID_DEVICE_FAILED",
+                &[
+                    ("device_index", "1"),
+                    ("level", "raid1"),
+                    ("array", "vg0-lv_data"),
+                ],
+            ),
+            (
+                "WARNING: Thin pool vg0-pool-tpool data is now 85.12% full.",
+                "LVM_THIN_POOL_FILLING",
+                &[
+                    ("pool", "vg0-pool-tpool"),
+                    ("space", "data"),
+                    ("percent", "85.12"),
+                ],
+            ),
+            (
+                "device-mapper: thin: 253:2: reached low water mark for metadata device: sending event.",
+                "LVM_THIN_POOL_LOW_WATER",
+                &[("dm_device", "253:2"), ("space", "metadata")],
+            ),
+            (
+                "device-mapper: thin: 253:2: switching pool to out-of-data-space (queue IO) mode",
+                "LVM_THIN_POOL_EXHAUSTED",
+                &[("dm_device", "253:2"), ("mode", "out-of-data-space")],
+            ),
+        ];
+
+        for (line, rule, fields) in cases {
+            let ev = parse_storage_events(message(line), None)
+                .unwrap_or_else(|| panic!("no event for {line}"));
+            assert_eq!(ev.matched_rule.as_deref(), Some(*rule), "{line}");
+            for (field, value) in *fields {
+                assert_eq!(
+                    ev.data.get(*field).map(String::as_str),
+                    Some(*value),
+                    "{line}"
+                );
+            }
+        }
+        assert!(
+            parse_storage_events(message("usb 1-1: new high-speed USB device"), None).is_none()
+        );
+    }
+
+    #[test]
+    fn access_log_classified_by_status() {
+        let line = |status: &str| {
+            format!(
+                r#"203.0.113.9 - - [16/Oct/2026:10:00:00 +0000] "GET /index.html HTTP/1.1" {status} 512 "-" "curl/8.0""#
+            )
+        };
+        let cases = [
+            ("200", "HTTP_SUCCESS", HttpEvent::Success),
+            ("301", "HTTP_REDIRECT", HttpEvent::Redirect),
+            ("404", "HTTP_CLIENT_ERROR", HttpEvent::ClientError),
+            ("503", "HTTP_SERVER_ERROR", HttpEvent::ServerError),
+            ("101", "HTTP_OTHER", HttpEvent::Other),
+        ];
+        for (status, rule, kind) in cases {
+            let ev = parse_access_log(line(status), None).unwrap();
+            assert_eq!(ev.matched_rule.as_deref(), Some(rule));
+            assert_eq!(ev.event_type, EventType::Http(kind));
+            assert_eq!(ev.data["status"], status);
+            assert_eq!(ev.data["method"], "GET");
+            assert_eq!(ev.data["path"], "/index.html");
+            assert_eq!(ev.data["user_agent"], "curl/8.0");
+            assert!(!ev.data.contains_key("referer"));
+        }
+
+        assert!(parse_access_log(line("200"), Some(vec!["HttpServerError"])).is_none());
+        assert!(parse_access_log(line("503"), Some(vec!["HttpServerError"])).is_some());
+        assert!(parse_access_log(line("101"), Some(vec!["HttpSuccess"])).is_none());
+    }
 
-        sleep(Duration::from_millis(500));
+    #[test]
+    fn login_timestamp_falls_back_to_receive_time() {
+        let msg = "pam_faillock(sshd:auth): Consecutive login failures for user alice account temporarily locked";
+        let ev = parse_login_attempts(message(msg), None).unwrap();
+        let received = Local.timestamp_micros(1_760_608_801_000_000).unwrap();
+        assert_eq!(ev.timestamp, received.format("%b %e %H:%M:%S").to_string());
     }
 }
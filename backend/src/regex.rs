@@ -1,16 +1,23 @@
 use once_cell::sync::Lazy;
-use regex::Regex;
+use regex::{Regex, RegexSet};
+
+pub type RegexTable = Lazy<Vec<(&'static str, Regex)>>;
 
 pub static SSHD_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
     vec![
-            ("AUTH_SUCCESS", Regex::new(r"(?x)^Accepted\s+(\w+)\s+for\s+(\S+)\s+from\s+([0-9A-Fa-f:.]+)\s+port\s+(\d+)(?:\s+ssh\d*)?\s*$").unwrap()),
+            ("AUTH_SUCCESS", Regex::new(r"(?x)^Accepted\s+(\w+)\s+for\s+(\S+)\s+from\s+([0-9A-Fa-f:.]+)\s+port\s+(\d+)(?:\s+ssh\d*)?(?::\s+([A-Za-z0-9-]+)\s+((?:SHA256|MD5):\S+)(?:\s+.*)?)?\s*$").unwrap()),
             ("AUTH_FAILURE", Regex::new(r"(?x)^Failed\s+(\w+)\s+for\s+(?:invalid\s+user\s+)?(\S+)\s+from\s+([0-9A-Fa-f:.]+)\s+port\s+(\d+)(?:\s+ssh\d*)?\s*$").unwrap()),
-            ("SESSION_OPENED", Regex::new(r"(?x)^pam_unix\(sshd:session\):\s+session\s+opened(?:\s+for\s+user\s+(\S+))?").unwrap()),
+            ("SESSION_OPENED", Regex::new(r"(?x)^pam_unix\(sshd:session\):\s+session\s+opened(?:\s+for\s+user\s+([^\s(]+)(?:\(uid=(\d+)\))?)?").unwrap()),
             ("SESSION_CLOSED", Regex::new(r"(?x)^pam_unix\(sshd:session\):\s+session\s+closed(?:\s+for\s+user\s+(\S+))?").unwrap()),
-            ("CONNECTION_CLOSED", Regex::new(r"(?x)^Connection\s+(?:closed|reset)(?:\s+by(?:\s+authenticating\s+user)?\s+(\S+))?\s+([0-9A-Fa-f:.]+)\s+port\s+(\d+)(?:\s+\[([^\]]+)\])?\s*$").unwrap()),
+            ("CONNECTION_CLOSED", Regex::new(r"(?x)^Connection\s+(?:closed|reset)(?:\s+by)?(?:\s+(?:authenticating|invalid)\s+user\s+(\S+))?\s+([0-9A-Fa-f:.]+)\s+port\s+(\d+)(?:\s+\[([^\]]+)\])?\s*$").unwrap()),
             ("RECEIVED_DISCONNECT", Regex::new(r"(?x)^Received\s+disconnect\s+from\s+([0-9A-Fa-f:.]+)(?:\s+port\s+(\d+))?:\s*(\d+):\s*(.+?)(?:\s+\[preauth\])?\s*$").unwrap()),
             ("NEGOTIATION_FAILURE", Regex::new(r"(?x)^Unable\s+to\s+negotiate(?:\s+with)?\s+([0-9A-Fa-f:.]+)(?:\s+port\s+(\d+))?:\s*(?:no\s+matching|no\s+matching\s+.*\s+found|no matching .* found).*$").unwrap()),
             ("TOO_MANY_AUTH", Regex::new(r"(?x)^(?:Disconnecting:|Disconnected:)?\s*Too\s+many\s+authentication\s+failures(?:\s+for\s+(?:invalid\s+user\s+)?(\S+))?\s*(?:\[preauth\])?\s*$").unwrap()),
+            ("MAXSTARTUPS_BEGIN", Regex::new(r"(?x)^(?:error:\s+)?beginning\s+MaxStartups\s+throttling\s*$").unwrap()),
+            ("MAXSTARTUPS_END", Regex::new(r"(?x)^(?:error:\s+)?exited\s+MaxStartups\s+throttling(?:\s+after\s+([\d:]+),\s+(\d+)\s+connections?\s+dropped)?\s*$").unwrap()),
+            ("MAXSTARTUPS_DROP", Regex::new(r"(?x)^(?:error:\s+)?drop\s+connection\s+\#(\d+)\s+from\s+\[([0-9A-Fa-f:.]+)\]:(\d+)\s+on\s+\[([0-9A-Fa-f:.]+)\]:(\d+)\s+(past\s+MaxStartups|penalty:\s+.+?)\s*$").unwrap()),
+            ("PREAUTH_TIMEOUT", Regex::new(r"(?x)^(?:fatal:\s+)?Timeout\s+before\s+authentication\s+for\s+(?:connection\s+from\s+)?([0-9A-Fa-f:.]+)(?:\s+to\s+[0-9A-Fa-f:.]+,)?(?:\s+port\s+(\d+))?").unwrap()),
+            ("PREAUTH_DISCONNECT", Regex::new(r"(?x)^Disconnected\s+from(?:\s+(?:invalid|authenticating)\s+user\s+(\S+))?\s+([0-9A-Fa-f:.]+)\s+port\s+(\d+)\s+\[preauth\]\s*$").unwrap()),
             ("WARNING", Regex::new(r"(?x)^(?:warning:|WARNING:|error:|fatal:)?\s*(.+\S)\s*$").unwrap()),
             ("UNKNOWN", Regex::new(r"(?s)^(.*\S.*)$").unwrap()),
         ]
@@ -88,7 +95,7 @@ pub static PROTOCOL_MISMATCH: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
 
 pub static SUDO_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
     vec![
-            ("COMMAND_RUN", Regex::new(r"(?x)^(\w+)\s+:\s+TTY=(\S+)\s+;\s+PWD=(\S+)\s+;\s+USER=(\S+)\s+;\s+COMMAND=(/usr/bin/su.*)$").unwrap()),
+            ("COMMAND_RUN", Regex::new(r"(?x)^(\S+)\s+:\s+TTY=(\S+)\s+;\s+PWD=(.+?)\s+;\s+USER=(\S+)\s+;(?:\s+GROUP=(\S+)\s+;)?(?:\s+ENV=.*?\s+;)?\s+COMMAND=((\S+).*)$").unwrap()),
             ("SESSION_OPENED_SUDO", Regex::new(r"^pam_unix\(sudo:session\): session opened for user (\w+)\(uid=(\d+)\) by (\w+)\(uid=(\d+)\)$").unwrap()),
             ("SESSION_OPENED_SU", Regex::new(r"^pam_unix\(su:session\): session opened for user (\w+)\(uid=(\d+)\) by (\w+)\(uid=(\d+)\)$").unwrap()),
             ("SESSION_CLOSED", Regex::new(r"^pam_unix\(sudo:session\):\s+session closed for user (\S+)$").unwrap()),
@@ -110,9 +117,17 @@ pub static LOGIN_REGEXES: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
             "AUTH_USER_UNKNOWN",
             Regex::new(r"pam_unix\([^:]+:auth\): .*user .* unknown").unwrap(),
         ),
+        (
+            "FAILLOCK_LOCKED",
+            Regex::new(r"pam_faillock\([^:]+:auth\):\s+Consecutive login failures for user (\S+) account temporarily locked").unwrap(),
+        ),
+        (
+            "FAILLOCK_LOCKED_COUNT",
+            Regex::new(r"pam_faillock\([^:]+:auth\):\s+(?:The\s+)?account(?:\s+(?:of|for)\s+(\S+))?\s+is\s+(?:temporarily\s+)?locked\s+due\s+to\s+(\d+)\s+failed\s+logins").unwrap(),
+        ),
         (
             "FAILL0CK",
-            Regex::new(r"pam_faillock\([^:]+:auth\):.*").unwrap(),
+            Regex::new(r"pam_faillock\([^:]+:auth\):\s*(.*)").unwrap(),
         ),
         (
             "ACCOUNT_EXPIRED",
@@ -140,11 +155,15 @@ pub static LOGIN_REGEXES: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
         ),
         (
             "SDDM_LOGIN_SUCCESS",
-            Regex::new(r"Authentication for user (\S+) successful").unwrap(),
+            Regex::new(r#"Authentication for user\s+"?([^"\s]+)"?\s+successful"#).unwrap(),
         ),
         (
             "SDDM_LOGIN_FAILURE",
-            Regex::new(r"Authentication failed for user (\S+)").unwrap(),
+            Regex::new(r#"Authentication failed for user\s+"?([^"\s]+)"?"#).unwrap(),
+        ),
+        (
+            "DM_SESSION_STARTED",
+            Regex::new(r"Starting (Wayland|X11) (?:user )?session: (.+)$").unwrap(),
         ),
         (
             "FAILED_PASSWORD_SSH",
@@ -212,6 +231,26 @@ pub static USER_DELETION_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
 
 pub static USER_MODIFICATION_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
     vec![
+        (
+            "USERMOD_GROUP_MEMBER",
+            Regex::new(r"^(add|delete) '([^']+)' (?:to|from) (shadow )?group '([^']+)'$").unwrap(),
+        ),
+        (
+            "USERMOD_RENAME",
+            Regex::new(r"^change user name '([^']+)' to '([^']+)'$").unwrap(),
+        ),
+        (
+            "USERMOD_CHANGE",
+            Regex::new(r"^change user '([^']+)' (UID|GID|shell|home|expiration|inactive) from '([^']*)' to '([^']*)'$").unwrap(),
+        ),
+        (
+            "USERMOD_PASSWORD",
+            Regex::new(r"^(lock|unlock|change) user '([^']+)' password$").unwrap(),
+        ),
+        (
+            "GROUPMOD_CHANGE",
+            Regex::new(r"^group changed in (\S+) \(group ([^/]+)/(\d+)(?:, new name: ([^,)]+))?(?:, new gid: (\d+))?(?:, new password)?\)$").unwrap(),
+        ),
         (
             "MODIFY_USER",
             Regex::new(r"^usermod: name=(\S+),.*$").unwrap(),
@@ -256,6 +295,25 @@ pub static PKG_EVENTS_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
     ]
 });
 
+// Combined Log Format, as written by nginx and apache:
+// addr ident user [time] "request" status bytes "referer" "user agent"
+// One regex matches every line and parse_access_log names the rule after the status
+// class, so accesslog.events lists ACCESS_LOG_RULES instead of a regex table
+pub static CLF_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"^(\S+) \S+ (\S+) \[([^\]]+)\] "((\S+) (\S+?)(?: ([^"\s]+))?|(?:[^"\\]|\\.)*)" (\d{3}) (\d+|-)(?: "((?:[^"\\]|\\.)*)" "((?:[^"\\]|\\.)*)")?"#,
+    )
+    .unwrap()
+});
+
+pub const ACCESS_LOG_RULES: [&str; 5] = [
+    "HTTP_SUCCESS",
+    "HTTP_REDIRECT",
+    "HTTP_CLIENT_ERROR",
+    "HTTP_SERVER_ERROR",
+    "HTTP_OTHER",
+];
+
 pub static CRON_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
     vec![
         (
@@ -453,7 +511,8 @@ pub static NETWORK_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
             Regex::new(
                 r"(?x)
                 ^<(?P<level>info|warn|error)>\s+\[\s*(?P<ts>\d+\.\d+)\]\s+
-                (?:vpn-connection|vpn):\s+
+                (?:vpn-connection|vpn)
+                (?:\[0x[0-9a-f]+,(?P<uuid>[0-9a-f-]+),\x22(?P<name>(?:[^\x22\\]|\\.)*)\x22(?:,\d+)?\])?:\s+
                 (?P<msg>.*)
                 "
             ).unwrap(),
@@ -586,7 +645,8 @@ pub static NETWORK_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
             "VPN_ERROR",
             Regex::new(r"(?x)
                 ^<(?P<level>error|warn)>\s+\[\s*(?P<ts>\d+\.\d+)\]\s+
-                (?:vpn-connection|vpn):\s+
+                (?:vpn-connection|vpn)
+                (?:\[0x[0-9a-f]+,(?P<uuid>[0-9a-f-]+),\x22(?P<name>(?:[^\x22\\]|\\.)*)\x22(?:,\d+)?\])?:\s+
                 (?P<msg>.*)$
             ").unwrap(),
         ),
@@ -640,19 +700,36 @@ pub static FIREWALLD_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
 pub static KERNEL_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
     vec![
             ("KERNEL_PANIC", Regex::new(r"(?x)^(?:Kernel\s+panic|kernel\s+panic)\s*[-:]\s*(.+?)(?:\s+CPU:\s*(\d+))?\s*$").unwrap()),
-            ("OOM_KILL", Regex::new(r"(?x)^(?:Out\s+of\s+memory|OOM\s+killer):\s*(?:Kill(?:ed|ing))?\s+process\s+(\d+)\s+\(([^\)]+)\)(?:\s+score\s+(\d+))?\s*").unwrap()),
+            ("OOM_KILL", Regex::new(r"(?x)^(?:Out\s+of\s+memory|Memory\s+cgroup\s+out\s+of\s+memory|OOM\s+killer):\s*(?:Kill(?:ed|ing)?)?\s+process\s+(\d+)\s+\(([^\)]+)\)(?:\s+score\s+(\d+))?(?:,?\s+total-vm:(\d+)kB)?(?:,\s+anon-rss:(\d+)kB)?(?:,\s+file-rss:(\d+)kB)?(?:,\s+shmem-rss:(\d+)kB)?(?:,\s+UID:(\d+))?(?:\s+pgtables:(\d+)kB)?(?:\s+oom_score_adj:(-?\d+))?\s*").unwrap()),
             ("SEGFAULT", Regex::new(r"(?x)^([^\[]+)\[(\d+)\]:\s+segfault\s+at\s+([0-9a-f]+)\s+ip\s+([0-9a-f]+)\s+sp\s+([0-9a-f]+)\s+error\s+(\d+)(?:\s+in\s+([^\[]+))?\s*").unwrap()),
             ("USB_ERROR", Regex::new(r"(?x)^usb\s+([\d\-\.]+):\s+(.+?),\s+error\s+(-?\d+)\s*$").unwrap()),
             ("USB_DESCRIPTOR_ERROR", Regex::new(r"(?x)^usb\s+([\d\-\.]+):\s+device\s+(?:descriptor|not\s+accepting\s+address)\s+(.+?),\s+error\s+(-?\d+)\s*$").unwrap()),
             ("USB_DEVICE_EVENT", Regex::new(r"(?x)^usb\s+([\d\-\.]+):\s+(New\s+USB\s+device\s+found|USB\s+disconnect),\s+(.+?)(?:\s+idVendor=([0-9a-f]+),\s+idProduct=([0-9a-f]+))?\s*$").unwrap()),
             ("DISK_ERROR", Regex::new(r"(?x)^(?:end_request|blk_update_request):\s+(?:I/O\s+error|critical\s+(?:medium|target)\s+error),\s+dev\s+(\S+),\s+sector\s+(\d+)(?:\s+op\s+([^\s]+))?\s*").unwrap()),
+            ("FS_REMOUNT_RO", Regex::new(r"(?x)^(EXT[234]|F2FS)-fs(?:\s+error)?\s+\((?:device\s+)?([^\)]+)\):\s+(?:(.*\S)\s+)??(?:error:\s+)?[Rr]emounting\s+filesystem\s+read-only").unwrap()),
+            ("BTRFS_FORCED_RO", Regex::new(r"(?x)^(BTRFS)\s+\w+\s+\(device\s+([^\):\s]+)[^\)]*\):\s+forced\s+readonly").unwrap()),
+            ("XFS_SHUTDOWN", Regex::new(r"(?x)^(XFS)\s+\(([^\)]+)\):\s+(?:(.*?\S)\s+)??(?:Shutting\s+down\s+filesystem|Filesystem\s+has\s+been\s+shut\s+down)(?:\s+(?:due\s+to\s+)?(.+?))?\.?\s*$").unwrap()),
             ("FS_MOUNT", Regex::new(r"(?x)^(?:EXT[234]|XFS|BTRFS|F2FS|VFAT|NTFS|ZFS)-fs\s+\(([^\)]+)\):\s+(mounted|unmounted|remounted)\s*(.+?)?\s*$").unwrap()),
             ("FS_ERROR", Regex::new(r"(?x)^(?:EXT[234]|XFS|BTRFS|F2FS|NTFS|ZFS)-fs\s+(?:error|warning)\s+\(device\s+([^\)]+)\):(?:\s+(.+))?\s*$").unwrap()),
+            ("THERMAL_THROTTLE_START", Regex::new(r"(?x)^(?:mce:\s+)?CPU(\d+):\s+(Core|Package)\s+temperature\s+(?:is\s+)?above\s+threshold,\s+cpu\s+clock\s+(?:is\s+)?throttled(?:\s+\(total\s+events\s+=\s+(\d+)\))?").unwrap()),
+            ("THERMAL_THROTTLE_STOP", Regex::new(r"(?x)^(?:mce:\s+)?CPU(\d+):\s+(Core|Package)\s+temperature/speed\s+normal").unwrap()),
             ("CPU_ERROR", Regex::new(r"(?x)^(?:CPU|cpu)\s*(\d+)?:?\s+(?:temperature|Machine\s+Check\s+Exception|MCE|hardware\s+error)\s*(.+?)\s*$").unwrap()),
             ("MEMORY_ERROR", Regex::new(r"(?x)^(?:EDAC|Memory)\s+(?:error|CE|UE):?\s*(.+?)(?:\s+at\s+address\s+([0-9a-fx]+))?\s*$").unwrap()),
             ("DEVICE_DETECTED", Regex::new(r"(?x)^(?:Found|Detected|Registered)\s+(?:device|hardware):\s+(.+?)(?:\s+at\s+([0-9a-fx:]+))?\s*$").unwrap()),
             ("DRIVER_EVENT", Regex::new(r"(?x)^(?:Loading|Unloading|Loaded|Unloaded)\s+(?:module|driver):\s+([^\s]+)(?:\s+(.+))?\s*$").unwrap()),
             ("NET_INTERFACE", Regex::new(r"(?x)^([a-z0-9]+):\s+(?:link\s+(?:up|down)|renamed\s+from\s+([a-z0-9]+)|NIC\s+Link\s+is\s+(?:Up|Down))\s*(?:at\s+(\d+)\s*(?:Mbps|Gbps))?\s*").unwrap()),
+            ("NET_SYN_FLOOD", Regex::new(r"(?x)^(?:TCP:\s+)?(?:request_sock_(TCP(?:v6)?):\s+)?[Pp]ossible\s+SYN\s+flooding\s+on\s+port\s+(\S+?)\.\s+(Sending\s+cookies|Dropping\s+request)").unwrap()),
+            ("NET_CONNTRACK_FULL", Regex::new(r"(?x)^nf_conntrack:\s+(?:nf_conntrack:\s+)?table\s+full,\s+dropping\s+packet").unwrap()),
+            ("NET_MARTIAN_SOURCE", Regex::new(r"(?x)^IPv4:\s+martian\s+source\s+(\S+)\s+from\s+(\S+),\s+on\s+dev\s+(\S+)").unwrap()),
+            ("NET_MARTIAN_DESTINATION", Regex::new(r"(?x)^IPv4:\s+martian\s+destination\s+(\S+)\s+from\s+(\S+),\s+dev\s+(\S+)").unwrap()),
+            ("NET_TCP_PRESSURE", Regex::new(r"(?x)^TCP:\s+(out\s+of\s+memory|too\s+many\s+orphaned\s+sockets)").unwrap()),
+            ("NET_NEIGHBOUR_OVERFLOW", Regex::new(r"(?x)^neighbou?r:\s+(\S+):\s+neighbor\s+table\s+overflow").unwrap()),
+            ("MEM_ALLOC_STALL", Regex::new(r"(?x)^(\S+?):\s+page\s+allocation\s+stalls\s+for\s+(\d+)ms,\s+order:(\d+)").unwrap()),
+            ("MEM_ALLOC_FAILURE", Regex::new(r"(?x)^(\S+?):\s+page\s+allocation\s+failure:\s+order:(\d+),\s+mode:(0x[0-9a-f]+)(?:\(([^)]*)\))?").unwrap()),
+            ("ZRAM_ALLOC_FAILURE", Regex::new(r"(?x)^(zram\d*):\s+Error\s+allocating\s+memory\s+for\s+compressed\s+page:\s+(\d+),\s+size=(\d+)").unwrap()),
+            ("OOMD_MEMORY_PRESSURE", Regex::new(r"(?x)^Memory\s+pressure\s+for\s+(\S+)\s+is\s+([\d.]+)%\s+>\s+([\d.]+)%\s+for\s+>\s+(\S+)").unwrap()),
+            ("OOMD_PRESSURE_KILL", Regex::new(r"(?x)^Killed\s+(\S+)\s+due\s+to\s+memory\s+pressure\s+for\s+(\S+)\s+being\s+([\d.]+)%\s+>\s+([\d.]+)%\s+for\s+>\s+(\S+)").unwrap()),
+            ("OOMD_SWAP_KILL", Regex::new(r"(?x)^Killed\s+(\S+)\s+due\s+to\s+swap\s+used\s+\((\d+)\)\s+/\s+total\s+\((\d+)\)\s+being\s+more\s+than\s+([\d.]+)%").unwrap()),
             ("PCI_DEVICE", Regex::new(r"(?x)^pci\s+([0-9a-f:\.]+):\s+(.+?)\s*$").unwrap()),
             ("ACPI_EVENT", Regex::new(r"(?x)^ACPI:?\s+(.+?)(?:\s+\[([^\]]+)\])?\s*$").unwrap()),
             ("THERMAL_EVENT", Regex::new(r"(?x)^(?:thermal|Thermal|Critical\s+temperature):?\s+(?:CPU|cpu|GPU|gpu|zone\s*(\d+))?\s*(.+?)(?:\s+temperature:?\s+([0-9\.]+)(?:\s*°?C)?)?\s*$").unwrap()),
@@ -676,168 +753,714 @@ pub static KERNEL_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
         ]
 });
 
-pub fn str_to_regex_names(ev: &str) -> &'static [&'static str] {
-    match ev {
-        "Success" => &["AUTH_SUCCESS", "SDDM_LOGIN_SUCCESS"],
-        "Failure" => &[
+pub static SYSTEMD_UNIT_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        (
+            "UNIT_STARTED",
+            Regex::new(r"^Started\s+(.+?)(?:\s+-\s+(.+?))?\.?$").unwrap(),
+        ),
+        (
+            "UNIT_STOPPED",
+            Regex::new(r"^Stopped\s+(.+?)(?:\s+-\s+(.+?))?\.?$").unwrap(),
+        ),
+        (
+            "UNIT_STARTING",
+            Regex::new(r"^Starting\s+(.+?)(?:\s+-\s+(.+?))?\.\.\.$").unwrap(),
+        ),
+        (
+            "UNIT_STOPPING",
+            Regex::new(r"^Stopping\s+(.+?)(?:\s+-\s+(.+?))?\.\.\.$").unwrap(),
+        ),
+        (
+            "UNIT_RELOADED",
+            Regex::new(r"^Reloaded\s+(.+?)(?:\s+-\s+(.+?))?\.?$").unwrap(),
+        ),
+        (
+            "UNIT_START_FAILED",
+            Regex::new(r"^Failed\s+to\s+start\s+(.+?)(?:\s+-\s+(.+?))?\.?$").unwrap(),
+        ),
+        (
+            "UNIT_FAILED",
+            Regex::new(r"^(\S+):\s+Failed\s+with\s+result\s+'([^']+)'\.?$").unwrap(),
+        ),
+        (
+            "UNIT_PROCESS_EXITED",
+            Regex::new(r"^(\S+):\s+Main\s+process\s+exited,\s+code=(\w+),\s+status=(\S+)$")
+                .unwrap(),
+        ),
+        (
+            "UNIT_DEACTIVATED",
+            Regex::new(r"^(\S+):\s+Deactivated\s+successfully\.?$").unwrap(),
+        ),
+        ("UNKNOWN", Regex::new(r"(?s)^(.*\S.*)$").unwrap()),
+    ]
+});
+
+pub static FAIL2BAN_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        (
+            "F2B_BAN",
+            Regex::new(r"(?x)(?:(NOTICE|INFO|WARNING)\s+)?\[([^\]]+)\]\s+Ban\s+([0-9A-Fa-f:.]+)\s*$").unwrap(),
+        ),
+        (
+            "F2B_UNBAN",
+            Regex::new(r"(?x)(?:(NOTICE|INFO|WARNING)\s+)?\[([^\]]+)\]\s+Unban\s+([0-9A-Fa-f:.]+)\s*$").unwrap(),
+        ),
+        (
+            "F2B_FOUND",
+            Regex::new(r"(?x)(?:(NOTICE|INFO|WARNING)\s+)?\[([^\]]+)\]\s+Found\s+([0-9A-Fa-f:.]+)(?:\s+-\s+(.+?))?\s*$").unwrap(),
+        ),
+        (
+            "F2B_ALREADY_BANNED",
+            Regex::new(r"(?x)(?:(NOTICE|INFO|WARNING)\s+)?\[([^\]]+)\]\s+([0-9A-Fa-f:.]+)\s+already\s+banned\s*$").unwrap(),
+        ),
+        ("UNKNOWN", Regex::new(r"(?s)^(.*\S.*)$").unwrap()),
+    ]
+});
+
+pub static TIMESYNC_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        ("TIME_SYNCED", Regex::new(r"(?x)^(?:Initial\s+synchronization|Synchronized)\s+to\s+time\s+server\s+(\[[^\]]+\]|[^\s:]+)(?::(\d+))?\s+\(([^)]+)\)\.?\s*$").unwrap()),
+        ("CHRONY_SOURCE_SELECTED", Regex::new(r"(?x)^Selected\s+source\s+(\S+)(?:\s+\(([^)]+)\))?\s*$").unwrap()),
+        ("CLOCK_STEPPED", Regex::new(r"(?x)^System\s+clock\s+was\s+stepped\s+by\s+(-?[0-9.]+)\s+seconds?\s*$").unwrap()),
+        ("CLOCK_SLEWED", Regex::new(r"(?x)^System\s+clock\s+wrong\s+by\s+(-?[0-9.]+)\s+seconds?(?:,\s+adjustment\s+started)?\s*$").unwrap()),
+        ("TIME_SERVER_TIMEOUT", Regex::new(r"(?x)^Timed\s+out\s+waiting\s+for\s+reply\s+from\s+(\[[^\]]+\]|[^\s:]+)(?::(\d+))?\s+\(([^)]+)\)\.?\s*$").unwrap()),
+        ("CHRONY_NO_SOURCES", Regex::new(r"(?x)^Can't\s+synchronise:\s+(.+?)\s*$").unwrap()),
+        ("UNKNOWN", Regex::new(r"(?s)^(.*\S.*)$").unwrap()),
+    ]
+});
+
+pub static DOCKER_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        ("CONTAINER_OOM", Regex::new(r"(?x)(?:TaskOOM\s+event\s+&TaskOOM\{ContainerID:([0-9a-f]+)|\b(?:container|id)=([0-9a-f]{12,64})\b.*\btopic=/tasks/oom\b)").unwrap()),
+        ("CONTAINER_START", Regex::new(r#"(?x)(?:StartContainer\s+for\s+\\?"([0-9a-f]+)\\?"\s+returns\s+successfully|msg="starting\s+signal\s+loop"\s.*\bpath=\S+/([0-9a-f]{12,64})\b|\b(?:container|id)=([0-9a-f]{12,64})\b.*\btopic=/tasks/start\b)"#).unwrap()),
+        ("CONTAINER_STOP", Regex::new(r#"(?x)StopContainer\s+for\s+\\?"([0-9a-f]+)\\?"\s+returns\s+successfully"#).unwrap()),
+        ("CONTAINER_KILL", Regex::new(r"(?x)Container\s+([0-9a-f]{12,64})\s+failed\s+to\s+exit\s+within\s+(\d+)\s*s(?:econds)?\s+of\s+signal\s+(\d+)").unwrap()),
+        ("CONTAINER_DIE", Regex::new(r#"(?x)(?:msg="shim\s+disconnected"\s.*\bid=([0-9a-f]{12,64})\b|\b(?:container|id)=([0-9a-f]{12,64})\b.*\btopic=/tasks/(?:exit|delete)\b)"#).unwrap()),
+        ("IMAGE_PULLED", Regex::new(r#"(?x)PullImage\s+\\?"([^"\\]+)\\?"\s+returns\s+image\s+reference\s+\\?"([^"\\]+)\\?""#).unwrap()),
+        ("DOCKER_DAEMON_LOG", Regex::new(r#"(?x)^time="([^"]+)"\s+level=(\w+)\s+msg="((?:[^"\\]|\\.)*)""#).unwrap()),
+        ("UNKNOWN", Regex::new(r"(?s)^(.*\S.*)$").unwrap()),
+    ]
+});
+
+pub static BLUETOOTH_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        ("BT_DEVICE_CONNECTED", Regex::new(r"(?x)^(?:\[CHG\]\s+)?(?:\S+\.c:\S+\(\)\s+)?Device\s+([0-9A-Fa-f]{2}(?::[0-9A-Fa-f]{2}){5})(?:\s+\(([^)]*)\))?\s+(?:Connected:\s+yes|connected)\s*$").unwrap()),
+        ("BT_DEVICE_DISCONNECTED", Regex::new(r"(?x)^(?:\[CHG\]\s+)?(?:\S+\.c:\S+\(\)\s+)?(?:Device\s+)?([0-9A-Fa-f]{2}(?::[0-9A-Fa-f]{2}){5})(?:\s+\(([^)]*)\))?:?\s+(?:Connected:\s+no|disconnected)(?:,?\s+reason\s+(\d+))?\s*$").unwrap()),
+        ("BT_DEVICE_PAIRED", Regex::new(r"(?x)^(?:\[CHG\]\s+)?(?:\S+\.c:\S+\(\)\s+)?Device\s+([0-9A-Fa-f]{2}(?::[0-9A-Fa-f]{2}){5})(?:\s+\(([^)]*)\))?\s+(?:Paired:\s+yes|paired|bonded)\s*$").unwrap()),
+        ("BT_BONDING_FAILED", Regex::new(r"(?x)^(?:Bonding\s+failed\s+for\s+([0-9A-Fa-f]{2}(?::[0-9A-Fa-f]{2}){5})(?::\s+(.+?))?|(?:\S+\.c:)?device_bonding_failed\(\)\s+status\s+(\d+))\s*$").unwrap()),
+        ("BT_PROFILE_CONNECT_FAILED", Regex::new(r"(?x)^(?:\[CHG\]\s+)?(?:\S+\.c:\S+\(\)\s+)?(\S+)\s+profile\s+connect\s+failed\s+for\s+([0-9A-Fa-f]{2}(?::[0-9A-Fa-f]{2}){5}):\s+(.+?)\s*$").unwrap()),
+        ("BT_AGENT_REQUEST", Regex::new(r"(?xi)^(?:\[CHG\]\s+)?(?:\S+\.c:\S+\(\)\s+)?(?:agent\s+)?request(?:ing)?\s+(confirmation|pin\s*code|passkey|authorization)(?:\s+(?:for|from)\s+([0-9A-Fa-f]{2}(?::[0-9A-Fa-f]{2}){5}))?").unwrap()),
+        ("BT_NO_AGENT", Regex::new(r"(?x)^(?:\[CHG\]\s+)?(?:\S+\.c:\S+\(\)\s+)?Authentication\s+attempt\s+without\s+agent\s*$").unwrap()),
+        ("UNKNOWN", Regex::new(r"(?s)^(.*\S.*)$").unwrap()),
+    ]
+});
+
+pub static APPARMOR_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        (
+            "APPARMOR_DENIED",
+            Regex::new(r#"\bapparmor="?DENIED"?"#).unwrap(),
+        ),
+        (
+            "APPARMOR_ALLOWED",
+            Regex::new(r#"\bapparmor="?ALLOWED"?"#).unwrap(),
+        ),
+        (
+            "APPARMOR_STATUS",
+            Regex::new(r#"\bapparmor="?STATUS"?"#).unwrap(),
+        ),
+        ("UNKNOWN", Regex::new(r#"\bapparmor="?(\w+)"?"#).unwrap()),
+    ]
+});
+
+// Details pulled out of the message of a vpn-connection[...] line: the plugin service
+// (org.freedesktop.NetworkManager.openvpn) or "starting openvpn", plugin state changes
+// and failure reasons
+pub static VPN_TYPE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:org\.freedesktop\.NetworkManager\.|\bstarting\s+)([a-z][\w-]*)").unwrap()
+});
+pub static VPN_STATE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\bstate\s+changed:\s+(\w+)").unwrap());
+pub static VPN_FAILURE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\bfailed(?:\s+to\s+\w+)?:\s+'?(.*?)'?(?:\s+\(\d+\))?$").unwrap());
+
+pub static FWLOG_KV_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b([A-Z]+)=(\S+)").unwrap());
+
+pub static APPARMOR_KV_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\b(\w+)=("[^"]*"|\S+)"#).unwrap());
+
+// "Oct 16 10:00:00 host sshd[123]: msg", or with an RFC 3339 timestamp as newer rsyslog writes
+pub static SYSLOG_LINE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:(\w{3}\s+\d{1,2} \d{2}:\d{2}:\d{2})|(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})))\s+(\S+)\s+([^\s\[:]+)(?:\[(\d+)\])?:\s?(.*)$").unwrap()
+});
+
+pub static SMARTD_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        ("SMART_PENDING_SECTORS", Regex::new(r"^Device:\s+(\S+?)(?:\s+\[([^\]]+)\])?,\s+(\d+)\s+(Currently\s+unreadable\s+\(pending\)\s+sectors)").unwrap()),
+        ("SMART_UNCORRECTABLE_SECTORS", Regex::new(r"^Device:\s+(\S+?)(?:\s+\[([^\]]+)\])?,\s+(\d+)\s+(Offline\s+uncorrectable\s+sectors)").unwrap()),
+        ("SMART_TEMPERATURE", Regex::new(r"^Device:\s+(\S+?)(?:\s+\[([^\]]+)\])?,\s+Temperature\s+(\d+)\s+Celsius\s+reached\s+((?:critical\s+)?limit)\s+of\s+(\d+)\s+Celsius").unwrap()),
+        ("SMART_SELF_TEST_FAILED", Regex::new(r"^Device:\s+(\S+?)(?:\s+\[([^\]]+)\])?,\s+(?:previous\s+self-test\s+completed\s+with\s+error(?:\s+\(([^)]+)\))?|Self-Test\s+Log\s+error\s+count\s+increased\s+from\s+\d+\s+to\s+(\d+))").unwrap()),
+        ("SMART_HEALTH_FAILED", Regex::new(r"^Device:\s+(\S+?)(?:\s+\[([^\]]+)\])?,\s+(FAILED\s+SMART\s+self-check|Critical\s+Warning\s+\(0x[0-9a-fA-F]+\):\s+.+?)\.?(?:\s+BACK\s+UP\s+DATA\s+NOW!)?$").unwrap()),
+        ("SMART_ATTRIBUTE_CHANGED", Regex::new(r"^Device:\s+(\S+?)(?:\s+\[([^\]]+)\])?,\s+SMART\s+(?:Prefailure|Usage)\s+Attribute:\s+(\d+)\s+(\S+)\s+changed\s+from\s+(\d+)\s+to\s+(\d+)").unwrap()),
+        ("UNKNOWN", Regex::new(r"(?s)^(.*\S.*)$").unwrap()),
+    ]
+});
+
+pub static WIFI_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        ("WIFI_WRONG_KEY", Regex::new(r#"^(\S+): CTRL-EVENT-SSID-TEMP-DISABLED id=\d+ ssid="(.*)" auth_failures=(\d+) duration=\d+ reason=(\S+)"#).unwrap()),
+        ("WIFI_HANDSHAKE_FAILED", Regex::new(r"^(\S+): WPA: 4-Way Handshake failed - (.+?)\s*$").unwrap()),
+        ("WIFI_AUTH_TIMEOUT", Regex::new(r"^(\S+): Authentication with ([0-9a-fA-F]{2}(?::[0-9a-fA-F]{2}){5}) timed out").unwrap()),
+        ("WIFI_ASSOC_REJECT", Regex::new(r"^(\S+): CTRL-EVENT-(?:ASSOC|AUTH)-REJECT (?:bssid=)?([0-9a-fA-F]{2}(?::[0-9a-fA-F]{2}){5}).*?\bstatus_code=(\d+)").unwrap()),
+        ("WIFI_DISCONNECTED", Regex::new(r"^(\S+): CTRL-EVENT-DISCONNECTED bssid=([0-9a-fA-F]{2}(?::[0-9a-fA-F]{2}){5}) reason=(\d+)(?: (locally_generated)=1)?").unwrap()),
+        ("IWD_DEAUTH", Regex::new(r"^(?:(\S+): )?Received Deauthentication event, reason: (\d+), from_ap: (\w+)").unwrap()),
+        ("WIFI_CONNECTED", Regex::new(r"^(\S+): CTRL-EVENT-CONNECTED - Connection to ([0-9a-fA-F]{2}(?::[0-9a-fA-F]{2}){5}) completed").unwrap()),
+        ("WIFI_ASSOCIATING", Regex::new(r"^(\S+): (?:SME: )?Trying to (?:associate|authenticate) with (?:([0-9a-fA-F]{2}(?::[0-9a-fA-F]{2}){5}) \(SSID='(.*?)'|SSID '(.*?)')").unwrap()),
+        ("UNKNOWN", Regex::new(r"(?s)^(.*\S.*)$").unwrap()),
+    ]
+});
+
+// No catch-all here, the rest of logind's messages belong to login.events
+pub static POWER_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        ("LID_CLOSED", Regex::new(r"^Lid closed\.$").unwrap()),
+        ("LID_OPENED", Regex::new(r"^Lid opened\.$").unwrap()),
+        ("POWER_BUTTON", Regex::new(r"^(Power|Suspend|Hibernate|Reboot) key pressed(?: (short|long))?\.$").unwrap()),
+        ("SYSTEM_SUSPEND", Regex::new(r"^(?:The system will (suspend|hibernate|hybrid-sleep|suspend-then-hibernate) now!|Entering sleep state '([^']+)'\.\.\.)$").unwrap()),
+        ("SYSTEM_RESUME", Regex::new(r"^(?:System returned from sleep state|System resumed|Operation '(sleep)' finished)\.?$").unwrap()),
+        ("SYSTEM_POWEROFF", Regex::new(r"^(?:The system will power off now!|System is powering down\.)$").unwrap()),
+        ("SYSTEM_REBOOT", Regex::new(r"^(?:The system will reboot now!|System is rebooting\.)$").unwrap()),
+        ("DOCK_CHANGE", Regex::new(r"^(Docked|Undocked)\.$").unwrap()),
+    ]
+});
+
+pub static MAIL_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        ("MAIL_SASL_AUTH_FAILED", Regex::new(r"^warning: ([^\[\s]+)\[([^\]]+)\]: SASL (\S+) authentication failed:?\s*(.*)$").unwrap()),
+        ("MAIL_REJECT", Regex::new(r"^(NOQUEUE|[0-9A-F]{6,}|[0-9B-DF-HJ-NP-TV-Zb-df-hj-np-tv-z]{10,}): (?:milter-)?reject: (\S+) from ([^\[\s]+)\[([^\]]+)\](?::\d+)?: (?:(\d{3}) )?(?:(\d\.\d+\.\d+) )?(.+?); from=<([^>]*)>(?: to=<([^>]*)>)?").unwrap()),
+        ("MAIL_DELIVERED", Regex::new(r"^([0-9A-F]{6,}|[0-9B-DF-HJ-NP-TV-Zb-df-hj-np-tv-z]{10,}): to=<([^>]*)>,(?: orig_to=<([^>]*)>,)? relay=([^,]+), .*?\bdsn=([\d.]+), status=(sent) \((.*)\)$").unwrap()),
+        ("MAIL_DEFERRED", Regex::new(r"^([0-9A-F]{6,}|[0-9B-DF-HJ-NP-TV-Zb-df-hj-np-tv-z]{10,}): to=<([^>]*)>,(?: orig_to=<([^>]*)>,)? relay=([^,]+), .*?\bdsn=([\d.]+), status=(deferred) \((.*)\)$").unwrap()),
+        ("MAIL_BOUNCED", Regex::new(r"^([0-9A-F]{6,}|[0-9B-DF-HJ-NP-TV-Zb-df-hj-np-tv-z]{10,}): to=<([^>]*)>,(?: orig_to=<([^>]*)>,)? relay=([^,]+), .*?\bdsn=([\d.]+), status=(bounced|expired) \((.*)\)$").unwrap()),
+        ("MAIL_QUEUED", Regex::new(r"^([0-9A-F]{6,}|[0-9B-DF-HJ-NP-TV-Zb-df-hj-np-tv-z]{10,}): from=<([^>]*)>, size=(\d+), nrcpt=(\d+) \(queue active\)").unwrap()),
+        ("MAIL_CLIENT", Regex::new(r"^([0-9A-F]{6,}|[0-9B-DF-HJ-NP-TV-Zb-df-hj-np-tv-z]{10,}): client=([^\[\s]+)\[([^\]]+)\](?::\d+)?(?:, sasl_method=([^,]+), sasl_username=(\S+))?").unwrap()),
+        ("DOVECOT_LOGIN_FAILED", Regex::new(r"^(\w+)-login: (?:Disconnected|Aborted login|Login aborted):? \(auth failed, (\d+) attempts?[^)]*\): (?:user=<([^>]*)>, )?(?:method=([^,]+), )?rip=([^,]+)").unwrap()),
+        ("DOVECOT_AUTH_FAILED", Regex::new(r"^auth(?:-worker)?(?:\(\d+\))?: (\w+(?:-\w+)?)\(([^,]*),([^,)]+)[^)]*\): (unknown user.*|Password mismatch.*|pam_authenticate\(\) failed: .+)$").unwrap()),
+        ("MAIL_QUEUE_OTHER", Regex::new(r"^([0-9A-F]{6,}|[0-9B-DF-HJ-NP-TV-Zb-df-hj-np-tv-z]{10,}): (.+)$").unwrap()),
+        ("UNKNOWN", Regex::new(r"(?s)^(.*\S.*)$").unwrap()),
+    ]
+});
+
+// No catch-all, every kernel line passes through here and only md / dm lines belong
+pub static STORAGE_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        ("MD_DEVICE_FAILED", Regex::new(r"^(?:mdadm: )?(Fail|FailSpare) event detected on md device ([^,\s]+)(?:, component device (\S+))?\s*$").unwrap()),
+        ("MD_ARRAY_DEGRADED", Regex::new(r"^(?:mdadm: )?(DegradedArray) event detected on md device ([^,\s]+)(?:,.*)?$").unwrap()),
+        ("MD_ARRAY_DISAPPEARED", Regex::new(r"^(?:mdadm: )?(DeviceDisappeared) event detected on md device ([^,\s]+)(?:,.*)?$").unwrap()),
+        ("MD_REBUILD_STARTED", Regex::new(r"^(?:mdadm: )?(RebuildStarted) event detected on md device ([^,\s]+)(?:,.*)?$").unwrap()),
+        ("MD_REBUILD_PROGRESS", Regex::new(r"^(?:mdadm: )?Rebuild(\d+) event detected on md device ([^,\s]+)(?:,.*)?$").unwrap()),
+        ("MD_REBUILD_FINISHED", Regex::new(r"^(?:mdadm: )?(RebuildFinished) event detected on md device ([^,\s]+)(?:,.*?mismatches found: (\d+))?.*$").unwrap()),
+        ("MD_SPARE_ACTIVE", Regex::new(r"^(?:mdadm: )?(SpareActive) event detected on md device ([^,\s]+), component device (\S+)").unwrap()),
+        ("KERNEL_MD_DISK_FAILURE", Regex::new(r"^md/(raid\d+):(\S+): Disk failure on (\S+?), disabling device").unwrap()),
+        ("KERNEL_MD_DEGRADED", Regex::new(r"^md/(raid\d+):(\S+): Operation continuing on (\d+) devices?").unwrap()),
+        ("KERNEL_MD_RECOVERY_STARTED", Regex::new(r"^md: (recovery|resync|reshape) of RAID array (\S+)").unwrap()),
+        ("KERNEL_MD_RECOVERY_DONE", Regex::new(r"^md: (\S+): (recovery|resync|reshape) done").unwrap()),
+        ("LVM_RAID_DEVICE_FAILED", Regex::new(r"^Device #(\d+) of (\S+) array, (\S+), has failed").unwrap()),
+        ("LVM_THIN_POOL_FILLING", Regex::new(r"^WARNING: Thin pool (\S+) (data|metadata) is now ([\d.]+)% full").unwrap()),
+        ("LVM_THIN_POOL_LOW_WATER", Regex::new(r"^device-mapper: thin: (\S+): reached low water mark for (data|metadata) device").unwrap()),
+        ("LVM_THIN_POOL_EXHAUSTED", Regex::new(r"^device-mapper: thin: (\S+): switching pool to (out-of-data-space|out-of-metadata-space|read-only|fail)").unwrap()),
+    ]
+});
+
+pub static FLATPAK_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        (
+            "FLATPAK_INSTALLED",
+            Regex::new(r"^(\S+): Installed ((app|runtime)/([^/\s]+)/([^/\s]+)/(\S+)) from (\S+)")
+                .unwrap(),
+        ),
+        (
+            "FLATPAK_UPDATED",
+            Regex::new(r"^(\S+): Updated ((app|runtime)/([^/\s]+)/([^/\s]+)/(\S+)) from (\S+)")
+                .unwrap(),
+        ),
+        (
+            "FLATPAK_UNINSTALLED",
+            Regex::new(r"^(\S+): Uninstalled ((app|runtime)/([^/\s]+)/([^/\s]+)/(\S+))").unwrap(),
+        ),
+        ("UNKNOWN", Regex::new(r"(?s)^(.*\S.*)$").unwrap()),
+    ]
+});
+
+// LOG / nft log lines are the prefix followed by IN= OUT= ... SRC= DST= ... PROTO= key=value
+// pairs. The verdict is only known from the prefix the rule author chose, so it is guessed
+// from words like BLOCK or ACCEPT. No catch-all, every kernel line passes through here
+pub static FWLOG_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        (
+            "FW_PACKET_DROPPED",
+            Regex::new(
+                r"(?i)^(.*?(?:block|drop|reject|deny|invalid).*?)\s*\bIN=\S* OUT=\S*.*\bSRC=",
+            )
+            .unwrap(),
+        ),
+        (
+            "FW_PACKET_ACCEPTED",
+            Regex::new(r"(?i)^(.*?(?:allow|accept).*?)\s*\bIN=\S* OUT=\S*.*\bSRC=").unwrap(),
+        ),
+        (
+            "FW_PACKET_LOGGED",
+            Regex::new(r"^(.*?)\s*\bIN=\S* OUT=\S*.*\bSRC=").unwrap(),
+        ),
+    ]
+});
+
+// Kernel lines name the DRM driver and PCI device of the GPU, Xorg prefixes its errors
+// with (EE). No catch-all, every kernel line passes through here
+pub static DISPLAY_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        ("GPU_HANG", Regex::new(r"^(\w+)\s+([0-9a-f]{4}:[0-9a-f]{2}:[0-9a-f]{2}\.[0-9a-f]):\s+\[drm\]\s+(?:\*ERROR\*\s+)?GPU\s+HANG\b(?:.*?\bin\s+(\S+)\s+\[(\d+)\])?").unwrap()),
+        ("GPU_RING_TIMEOUT", Regex::new(r"^\[drm:\w+\s+\[(\w+)\]\]\s+\*ERROR\*\s+(?:ring\s+(\S+)\s+timeout|Hangcheck\s+timer\s+elapsed)").unwrap()),
+        ("GPU_RESET", Regex::new(r"^(\w+)\s+([0-9a-f]{4}:[0-9a-f]{2}:[0-9a-f]{2}\.[0-9a-f]):\s+(?:\[drm\]\s+|amdgpu:\s+)?(?:Resetting\s+chip\s+for\s+(.+?)|GPU\s+reset(?:\(\d+\))?\s+(begin|succeeded|failed))!?$").unwrap()),
+        ("NVIDIA_XID", Regex::new(r"^NVRM:\s+Xid\s+\(PCI:([0-9a-f:.]+)\):\s+(\d+),\s*(.*)").unwrap()),
+        ("XORG_SEGFAULT", Regex::new(r"^\(EE\)\s+(?:Segmentation\s+fault\s+at\s+address\s+(0x[0-9a-f]+)|Caught\s+signal\s+(\d+)\s+\(([^)]+)\))").unwrap()),
+        ("XORG_FATAL", Regex::new(r"^\(EE\)\s+Fatal\s+server\s+error:?\s*(.*\S)?").unwrap()),
+        ("XORG_ERROR", Regex::new(r"^\(EE\)\s+(?:(\w+)\(\d+\):\s+)?(.*\S)").unwrap()),
+        ("COMPOSITOR_CRASH", Regex::new(r"(?i)^(GNOME\s+Shell|KWin|Mutter|Xwayland)\s+(?:has\s+)?crashed\b(?:.*?\bsignal\s+(\d+))?").unwrap()),
+        ("XWAYLAND_LOST", Regex::new(r"Connection\s+to\s+xwayland\s+lost").unwrap()),
+    ]
+});
+
+pub static COREDUMP_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        (
+            "COREDUMP",
+            Regex::new(r"^Process\s+(\d+)\s+\((.*?)\)\s+of\s+user\s+(\d+)\s+dumped\s+core")
+                .unwrap(),
+        ),
+        (
+            "COREDUMP_NO_CORE",
+            Regex::new(
+                r"^Process\s+(\d+)\s+\((.*?)\)\s+of\s+user\s+(\d+)\s+terminated\s+abnormally",
+            )
+            .unwrap(),
+        ),
+        ("UNKNOWN", Regex::new(r"(?s)^(.*\S.*)$").unwrap()),
+    ]
+});
+
+pub static RESOLVED_REGEX: Lazy<Vec<(&str, Regex)>> = Lazy::new(|| {
+    vec![
+        ("RESOLVED_DNSSEC_FAILED", Regex::new(r"(?x)^DNSSEC\s+validation\s+failed\s+for\s+question\s+(\S+)\s+(?:IN|CH|HS|ANY)\s+(\S+):\s+(\S+?)\.?\s*$").unwrap()),
+        ("RESOLVED_SERVER_SWITCHED", Regex::new(r"(?x)^Switching\s+to\s+(?:(system|fallback)\s+)?DNS\s+server\s+([^\s\#]+?)(?:\#(\S+?))?(?:\s+for\s+interface\s+(\S+?))?\.?\s*$").unwrap()),
+        ("RESOLVED_FEATURE_DEGRADED", Regex::new(r"(?x)^Using\s+degraded\s+feature\s+set\s+(\S+)\s+instead\s+of\s+(\S+)\s+for\s+DNS\s+server\s+([^\s\#]+?)(?:\#(\S+?))?\.?\s*$").unwrap()),
+        ("RESOLVED_FEATURE_RESTORED", Regex::new(r"(?x)^Grace\s+period\s+over,\s+resuming\s+full\s+feature\s+set\s+\((\S+)\)\s+for\s+DNS\s+server\s+([^\s\#]+?)(?:\#(\S+?))?\.?\s*$").unwrap()),
+        ("RESOLVED_QUERY_TIMEOUT", Regex::new(r"(?x)^Timeout\s+reached\s+on\s+transaction\s+(\d+)\.?\s*$").unwrap()),
+        ("UNKNOWN", Regex::new(r"(?s)^(.*\S.*)$").unwrap()),
+    ]
+});
+
+fn regex_set(table: &[(&str, Regex)]) -> RegexSet {
+    RegexSet::new(table.iter().map(|(_, regex)| regex.as_str())).unwrap()
+}
+
+pub static SSHD_REGEX_SET: Lazy<RegexSet> = Lazy::new(|| regex_set(&SSHD_REGEX));
+pub static PROTOCOL_MISMATCH_SET: Lazy<RegexSet> = Lazy::new(|| regex_set(&PROTOCOL_MISMATCH));
+pub static NETWORK_REGEX_SET: Lazy<RegexSet> = Lazy::new(|| regex_set(&NETWORK_REGEX));
+pub static FIREWALLD_REGEX_SET: Lazy<RegexSet> = Lazy::new(|| regex_set(&FIREWALLD_REGEX));
+pub static KERNEL_REGEX_SET: Lazy<RegexSet> = Lazy::new(|| regex_set(&KERNEL_REGEX));
+pub static SYSTEMD_UNIT_REGEX_SET: Lazy<RegexSet> = Lazy::new(|| regex_set(&SYSTEMD_UNIT_REGEX));
+pub static BLUETOOTH_REGEX_SET: Lazy<RegexSet> = Lazy::new(|| regex_set(&BLUETOOTH_REGEX));
+
+pub fn candidate_rules<'a>(
+    table: &'a [(&'static str, Regex)],
+    set: &RegexSet,
+    msg: &str,
+) -> impl Iterator<Item = &'a (&'static str, Regex)> {
+    set.matches(msg).into_iter().map(move |idx| &table[idx])
+}
+
+pub static EVENT_TYPE_FILTERS: &[(&str, &[&str])] = &[
+    ("Success", &["AUTH_SUCCESS", "SDDM_LOGIN_SUCCESS"]),
+    (
+        "Failure",
+        &[
             "AUTH_FAILURE",
             "SDDM_LOGIN_FAILURE",
             "FAILED_PASSWORD_SSH",
             "INVALID_USER_ATTEMPT",
         ],
-        "SessionOpened" => &[
+    ),
+    (
+        "SessionOpened",
+        &[
             "SESSION_OPENED",
             "SYSTEMD_NEW_SESSION",
             "SYSTEMD_SESSION_OPENED_UID",
         ],
-        "SessionClosed" => &[
+    ),
+    (
+        "SessionClosed",
+        &[
             "SESSION_CLOSED",
             "SYSTEMD_SESSION_CLOSED",
             "SYSTEMD_SESSION_CLOSED_UID",
         ],
-
-        "ConnectionClosed" => &["CONNECTION_CLOSED"],
-        "TooManyAuthFailures" => &["TOO_MANY_AUTH"],
-        "Warning" => &[
+    ),
+    ("DisplaySessionStarted", &["DM_SESSION_STARTED"]),
+    (
+        "ConnectionClosed",
+        &["CONNECTION_CLOSED", "PREAUTH_DISCONNECT"],
+    ),
+    (
+        "ConnectionThrottled",
+        &[
+            "MAXSTARTUPS_BEGIN",
+            "MAXSTARTUPS_END",
+            "MAXSTARTUPS_DROP",
+            "PREAUTH_TIMEOUT",
+        ],
+    ),
+    ("TooManyAuthFailures", &["TOO_MANY_AUTH"]),
+    (
+        "Warning",
+        &[
             "WARNING",
             "NM_WARNING",
             "DEVICE_ACTIVATION_WARN",
             "MANAGER_WARN",
             "SUDO_WARNING",
         ],
-        "Info" => &["RECEIVED_DISCONNECT", "NEGOTIATION_FAILURE", "INFO"],
-        "Other" => &["UNKNOWN", "GENERIC"],
-        "Unknown" => &["UNKNOWN"],
-
-        // SUDO Events
-        "IncorrectPassword" => &["INCORRECT_PASSWORD"],
-        "AuthError" => &["AUTH_ERROR"],
-        "CmdRun" => &["COMMAND_RUN"],
-        "SessionOpenedSudo" => &["SESSION_OPENED_SUDO", "SESSION_OPENED_SU"],
-        "NotInSudoers" => &["NOT_IN_SUDOERS"],
-
-        // Login Events
-        "AuthUserUnknown" => &["AUTH_USER_UNKNOWN"],
-        "FaillockUserUnknown" => &["FAILL0CK"],
-        "NoLoginRefused" => &["NOLOGIN_REFUSED"],
-        "AccountExpired" => &["ACCOUNT_EXPIRED"],
-        "AccountLocked" => &["ACCOUNT_LOCKED"],
-        "PasswordChanged" => &["PASSWORD_CHANGED"],
-
-        // User Creation Events
-        "NewUser" => &["NEW_USER"],
-        "NewGroup" => &["NEW_GROUP"],
-        "GroupAddedEtcGroup" => &["GROUP_ADDED_ETC_GROUP"],
-        "GroupAddedEtcGshadow" => &["GROUP_ADDED_ETC_GSHADOW"],
-
-        // User Deletion Events
-        "DeleteUser" => &["DELETE_USER"],
-        "DeleteUserHome" => &["DELETE_USER_HOME"],
-        "DeleteUserMail" => &["DELETE_USER_MAIL"],
-        "DeleteGroup" => &["DELETE_GROUP"],
-
-        // User Modification Events
-        "ModifyUser" => &["MODIFY_USER"],
-        "ModifyGroup" => &["MODIFY_GROUP"],
-        "PasswdChange" => &["USER_PASSWD_CHANGE"],
-        "ShadowUpdated" => &["USER_SHADOW_UPDATED"],
-
-        // Package Events
-        "PkgInstalled" => &["INSTALLED"],
-        "PkgRemoved" => &["REMOVED"],
-        "PkgUpgraded" => &["UPGRADED"],
-        "PkgDowngraded" => &["DOWNGRADED"],
-        "PkgReinstalled" => &["REINSTALLED"],
-
-        // Cron Events
-        "CronCmd" => &["CRON_CMD"],
-        "CronReload" => &["CRON_RELOAD"],
-        "CronErrorBadCommand" => &["CRON_ERROR_BAD_COMMAND"],
-        "CronErrorBadMinute" => &["CRON_ERROR_BAD_MINUTE"],
-        "CronErrorOther" => &["CRON_ERROR_OTHER"],
-        "CronDenied" => &["CRON_DENIED"],
-        "CronSessionOpen" => &["CRON_SESSION_OPEN"],
-        "CronSessionClose" => &["CRON_SESSION_CLOSE"],
-
-        // Network Manager Events
-        "DeviceActivation" => &["DEVICE_ACTIVATION"],
-        "DeviceStateChange" => &["DEVICE_STATE_CHANGE"],
-        "ConnectionActivated" => &["CONNECTION_ACTIVATED"],
-        "ConnectionDeactivated" => &["CONNECTION_DEACTIVATED"],
-        "ManagerState" => &["MANAGER_STATE"],
-        "DhcpEvent" => &["DHCP_EVENT"],
-        "DhcpInit" => &["DHCP_INIT"],
-        "PolicySet" => &["POLICY_SET"],
-        "SupplicantState" => &["SUPPLICANT_STATE"],
-        "WifiScan" => &["WIFI_SCAN"],
-        "PlatformError" => &["PLATFORM_ERROR"],
-        "SettingsConnection" => &["SETTINGS_CONNECTION"],
-        "DnsConfig" => &["DNS_CONFIG"],
-        "VpnEvent" => &["VPN_EVENT"],
-        "FirewallEvent" => &["FIREWALL_EVENT"],
-        "AgentRequest" => &["AGENT_REQUEST"],
-        "ConnectivityCheck" => &["CONNECTIVITY_CHECK"],
-        "Dispatcher" => &["DISPATCHER"],
-        "LinkEvent" => &["LINK_EVENT"],
-        "VirtualDevice" => &["VIRTUAL_DEVICE"],
-        "Audit" => &["AUDIT"],
-        "Systemd" => &["SYSTEMD"],
-
-        // Firewalld Events
-        "FirewalldServiceStarted" => &["SERVICE_STARTED"],
-        "FirewalldServiceStopped" => &["SERVICE_STOPPED"],
-        "FirewalldConfigReloaded" => &["CONFIG_RELOADED"],
-        "FirewalldZoneChanged" => &["ZONE_CHANGED"],
-        "FirewalldServiceModified" => &["SERVICE_MODIFIED"],
-        "FirewalldPortModified" => &["PORT_MODIFIED"],
-        "FirewalldRuleApplied" => &["RULE_APPLIED"],
-        "FirewalldIptablesCommand" => &["IPTABLES_COMMAND"],
-        "FirewalldInterfaceBinding" => &["INTERFACE_BINDING"],
-        "FirewalldCommandFailed" => &["COMMAND_FAILED"],
-        "FirewalldOperationStatus" => &["OPERATION_STATUS"],
-        "FirewalldModuleMessage" => &["MODULE_MSG"],
-        "FirewalldDBusMessage" => &["DBUS_MSG"],
-
-        // Kernel Events
-        "KernelPanic" => &["KERNEL_PANIC"],
-        "OomKill" => &["OOM_KILL"],
-        "Segfault" => &["SEGFAULT"],
-        "UsbError" => &["USB_ERROR"],
-        "UsbDescriptorError" => &["USB_DESCRIPTOR_ERROR"],
-        "UsbDeviceEvent" => &["USB_DEVICE_EVENT"],
-        "DiskError" => &["DISK_ERROR"],
-        "FsMount" => &["FS_MOUNT"],
-        "FsError" => &["FS_ERROR"],
-        "CpuError" => &["CPU_ERROR"],
-        "MemoryError" => &["MEMORY_ERROR"],
-        "DeviceDetected" => &["DEVICE_DETECTED"],
-        "DriverEvent" => &["DRIVER_EVENT"],
-        "NetInterface" => &["NET_INTERFACE"],
-        "PciDevice" => &["PCI_DEVICE"],
-        "AcpiEvent" => &["ACPI_EVENT"],
-        "ThermalEvent" => &["THERMAL_EVENT"],
-        "DmaError" => &["DMA_ERROR"],
-        "AuditEvent" => &["AUDIT_EVENT"],
-        "KernelTaint" => &["KERNEL_TAINT"],
-        "FirmwareLoad" => &["FIRMWARE_LOAD"],
-        "IrqEvent" => &["IRQ_EVENT"],
-        "TaskKilled" => &["TASK_KILLED"],
-        "RcuStall" => &["RCU_STALL"],
-        "Watchdog" => &["WATCHDOG"],
-        "BootEvent" => &["BOOT_EVENT"],
-        "Emerg" => &["EMERG"],
-        "Alert" => &["ALERT"],
-        "Critical" => &["CRITICAL"],
-        "Error" => &["ERROR"],
-        "Notice" => &["NOTICE"],
-
-        // Protocol Mismatch Events
-        "InvalidProtocolId" => &["INVALID_PROTOCOL_ID"],
-        "BadProtocolVersion" => &["BAD_PROTOCOL_VERSION"],
-        "MajorVersionDiff" => &["MAJOR_VERSION_DIFF"],
-        "BannerOrDispatchError" => &["BANNER_OR_DISPATCH_ERROR"],
-        "SocketReadFailure" => &["SOCKET_READ_FAILURE"],
-
-        _ => &[],
-    }
+    ),
+    (
+        "Info",
+        &["RECEIVED_DISCONNECT", "NEGOTIATION_FAILURE", "INFO"],
+    ),
+    ("Other", &["UNKNOWN", "GENERIC"]),
+    ("Unknown", &["UNKNOWN"]),
+    // SUDO Events
+    ("IncorrectPassword", &["INCORRECT_PASSWORD"]),
+    ("AuthError", &["AUTH_ERROR"]),
+    ("CmdRun", &["COMMAND_RUN"]),
+    (
+        "SessionOpenedSudo",
+        &["SESSION_OPENED_SUDO", "SESSION_OPENED_SU"],
+    ),
+    ("NotInSudoers", &["NOT_IN_SUDOERS"]),
+    // Login Events
+    ("AuthUserUnknown", &["AUTH_USER_UNKNOWN"]),
+    ("FaillockUserUnknown", &["FAILL0CK"]),
+    ("NoLoginRefused", &["NOLOGIN_REFUSED"]),
+    ("AccountExpired", &["ACCOUNT_EXPIRED"]),
+    (
+        "AccountLocked",
+        &["ACCOUNT_LOCKED", "FAILLOCK_LOCKED", "FAILLOCK_LOCKED_COUNT"],
+    ),
+    ("PasswordChanged", &["PASSWORD_CHANGED"]),
+    // User Creation Events
+    ("NewUser", &["NEW_USER"]),
+    ("NewGroup", &["NEW_GROUP"]),
+    ("GroupAddedEtcGroup", &["GROUP_ADDED_ETC_GROUP"]),
+    ("GroupAddedEtcGshadow", &["GROUP_ADDED_ETC_GSHADOW"]),
+    // User Deletion Events
+    ("DeleteUser", &["DELETE_USER"]),
+    ("DeleteUserHome", &["DELETE_USER_HOME"]),
+    ("DeleteUserMail", &["DELETE_USER_MAIL"]),
+    ("DeleteGroup", &["DELETE_GROUP"]),
+    // User Modification Events
+    (
+        "ModifyUser",
+        &[
+            "MODIFY_USER",
+            "USERMOD_GROUP_MEMBER",
+            "USERMOD_RENAME",
+            "USERMOD_CHANGE",
+            "USERMOD_PASSWORD",
+        ],
+    ),
+    ("ModifyGroup", &["MODIFY_GROUP", "GROUPMOD_CHANGE"]),
+    ("PasswdChange", &["USER_PASSWD_CHANGE"]),
+    ("ShadowUpdated", &["USER_SHADOW_UPDATED"]),
+    // Package Events
+    ("PkgInstalled", &["INSTALLED", "FLATPAK_INSTALLED"]),
+    ("PkgRemoved", &["REMOVED", "FLATPAK_UNINSTALLED"]),
+    ("PkgUpgraded", &["UPGRADED", "FLATPAK_UPDATED"]),
+    ("PkgDowngraded", &["DOWNGRADED"]),
+    ("PkgReinstalled", &["REINSTALLED"]),
+    // Cron Events
+    ("CronCmd", &["CRON_CMD"]),
+    ("CronReload", &["CRON_RELOAD"]),
+    ("CronErrorBadCommand", &["CRON_ERROR_BAD_COMMAND"]),
+    ("CronErrorBadMinute", &["CRON_ERROR_BAD_MINUTE"]),
+    ("CronErrorOther", &["CRON_ERROR_OTHER"]),
+    ("CronDenied", &["CRON_DENIED"]),
+    ("CronSessionOpen", &["CRON_SESSION_OPEN"]),
+    ("CronSessionClose", &["CRON_SESSION_CLOSE"]),
+    // Network Manager Events
+    ("DeviceActivation", &["DEVICE_ACTIVATION"]),
+    ("DeviceStateChange", &["DEVICE_STATE_CHANGE"]),
+    ("ConnectionActivated", &["CONNECTION_ACTIVATED"]),
+    ("ConnectionDeactivated", &["CONNECTION_DEACTIVATED"]),
+    ("ManagerState", &["MANAGER_STATE"]),
+    ("DhcpEvent", &["DHCP_EVENT"]),
+    ("DhcpInit", &["DHCP_INIT"]),
+    ("PolicySet", &["POLICY_SET"]),
+    ("SupplicantState", &["SUPPLICANT_STATE"]),
+    ("WifiScan", &["WIFI_SCAN"]),
+    ("PlatformError", &["PLATFORM_ERROR"]),
+    ("SettingsConnection", &["SETTINGS_CONNECTION"]),
+    ("DnsConfig", &["DNS_CONFIG"]),
+    ("VpnEvent", &["VPN_EVENT"]),
+    ("FirewallEvent", &["FIREWALL_EVENT"]),
+    ("AgentRequest", &["AGENT_REQUEST"]),
+    ("ConnectivityCheck", &["CONNECTIVITY_CHECK"]),
+    ("Dispatcher", &["DISPATCHER"]),
+    ("LinkEvent", &["LINK_EVENT"]),
+    ("VirtualDevice", &["VIRTUAL_DEVICE"]),
+    ("Audit", &["AUDIT"]),
+    ("Systemd", &["SYSTEMD"]),
+    // Firewalld Events
+    ("FirewalldServiceStarted", &["SERVICE_STARTED"]),
+    ("FirewalldServiceStopped", &["SERVICE_STOPPED"]),
+    ("FirewalldConfigReloaded", &["CONFIG_RELOADED"]),
+    ("FirewalldZoneChanged", &["ZONE_CHANGED"]),
+    ("FirewalldServiceModified", &["SERVICE_MODIFIED"]),
+    ("FirewalldPortModified", &["PORT_MODIFIED"]),
+    ("FirewalldRuleApplied", &["RULE_APPLIED"]),
+    ("FirewalldIptablesCommand", &["IPTABLES_COMMAND"]),
+    ("FirewalldInterfaceBinding", &["INTERFACE_BINDING"]),
+    ("FirewalldCommandFailed", &["COMMAND_FAILED"]),
+    ("FirewalldOperationStatus", &["OPERATION_STATUS"]),
+    ("FirewalldModuleMessage", &["MODULE_MSG"]),
+    ("FirewalldDBusMessage", &["DBUS_MSG"]),
+    // Kernel Events
+    ("KernelPanic", &["KERNEL_PANIC"]),
+    (
+        "OomKill",
+        &["OOM_KILL", "OOMD_PRESSURE_KILL", "OOMD_SWAP_KILL"],
+    ),
+    ("Segfault", &["SEGFAULT"]),
+    ("UsbError", &["USB_ERROR"]),
+    ("UsbDescriptorError", &["USB_DESCRIPTOR_ERROR"]),
+    ("UsbDeviceEvent", &["USB_DEVICE_EVENT"]),
+    ("DiskError", &["DISK_ERROR"]),
+    ("FsMount", &["FS_MOUNT"]),
+    ("FsError", &["FS_ERROR"]),
+    (
+        "FsReadOnly",
+        &["FS_REMOUNT_RO", "BTRFS_FORCED_RO", "XFS_SHUTDOWN"],
+    ),
+    ("CpuError", &["CPU_ERROR"]),
+    ("MemoryError", &["MEMORY_ERROR"]),
+    ("DeviceDetected", &["DEVICE_DETECTED"]),
+    ("DriverEvent", &["DRIVER_EVENT"]),
+    ("NetInterface", &["NET_INTERFACE"]),
+    (
+        "NetStack",
+        &[
+            "NET_SYN_FLOOD",
+            "NET_CONNTRACK_FULL",
+            "NET_MARTIAN_SOURCE",
+            "NET_MARTIAN_DESTINATION",
+            "NET_TCP_PRESSURE",
+            "NET_NEIGHBOUR_OVERFLOW",
+        ],
+    ),
+    ("SynFlood", &["NET_SYN_FLOOD"]),
+    ("ConntrackFull", &["NET_CONNTRACK_FULL"]),
+    (
+        "MartianPacket",
+        &["NET_MARTIAN_SOURCE", "NET_MARTIAN_DESTINATION"],
+    ),
+    (
+        "MemoryPressure",
+        &[
+            "MEM_ALLOC_STALL",
+            "MEM_ALLOC_FAILURE",
+            "ZRAM_ALLOC_FAILURE",
+            "OOMD_MEMORY_PRESSURE",
+        ],
+    ),
+    ("PciDevice", &["PCI_DEVICE"]),
+    ("AcpiEvent", &["ACPI_EVENT"]),
+    (
+        "ThermalEvent",
+        &[
+            "THERMAL_EVENT",
+            "THERMAL_THROTTLE_START",
+            "THERMAL_THROTTLE_STOP",
+        ],
+    ),
+    ("DmaError", &["DMA_ERROR"]),
+    ("AuditEvent", &["AUDIT_EVENT"]),
+    ("KernelTaint", &["KERNEL_TAINT"]),
+    ("FirmwareLoad", &["FIRMWARE_LOAD"]),
+    ("IrqEvent", &["IRQ_EVENT"]),
+    ("TaskKilled", &["TASK_KILLED"]),
+    ("RcuStall", &["RCU_STALL"]),
+    ("Watchdog", &["WATCHDOG"]),
+    ("BootEvent", &["BOOT_EVENT"]),
+    ("Emerg", &["EMERG"]),
+    ("Alert", &["ALERT"]),
+    ("Critical", &["CRITICAL"]),
+    ("Error", &["ERROR"]),
+    ("Notice", &["NOTICE"]),
+    // Systemd Unit Events
+    ("UnitStarted", &["UNIT_STARTED"]),
+    ("UnitStopped", &["UNIT_STOPPED"]),
+    ("UnitStarting", &["UNIT_STARTING"]),
+    ("UnitStopping", &["UNIT_STOPPING"]),
+    ("UnitReloaded", &["UNIT_RELOADED"]),
+    ("UnitStartFailed", &["UNIT_START_FAILED"]),
+    ("UnitFailed", &["UNIT_FAILED"]),
+    ("UnitProcessExited", &["UNIT_PROCESS_EXITED"]),
+    ("UnitDeactivated", &["UNIT_DEACTIVATED"]),
+    // Fail2ban Events
+    ("F2bBan", &["F2B_BAN"]),
+    ("F2bUnban", &["F2B_UNBAN"]),
+    ("F2bFound", &["F2B_FOUND"]),
+    ("F2bAlreadyBanned", &["F2B_ALREADY_BANNED"]),
+    // Timesync Events
+    ("TimeSynced", &["TIME_SYNCED", "CHRONY_SOURCE_SELECTED"]),
+    ("ClockStepped", &["CLOCK_STEPPED"]),
+    ("ClockSlewed", &["CLOCK_SLEWED"]),
+    (
+        "TimeSyncLost",
+        &["TIME_SERVER_TIMEOUT", "CHRONY_NO_SOURCES"],
+    ),
+    // Container Events
+    ("ContainerStart", &["CONTAINER_START"]),
+    ("ContainerStop", &["CONTAINER_STOP", "CONTAINER_KILL"]),
+    ("ContainerDie", &["CONTAINER_DIE"]),
+    ("ContainerOom", &["CONTAINER_OOM"]),
+    ("ImagePull", &["IMAGE_PULLED"]),
+    // Bluetooth Events
+    ("BluetoothConnected", &["BT_DEVICE_CONNECTED"]),
+    ("BluetoothDisconnected", &["BT_DEVICE_DISCONNECTED"]),
+    ("BluetoothPaired", &["BT_DEVICE_PAIRED"]),
+    ("BluetoothPairingFailed", &["BT_BONDING_FAILED"]),
+    ("BluetoothConnectFailed", &["BT_PROFILE_CONNECT_FAILED"]),
+    (
+        "BluetoothAgentRequest",
+        &["BT_AGENT_REQUEST", "BT_NO_AGENT"],
+    ),
+    // AppArmor Events
+    ("AppArmorDenied", &["APPARMOR_DENIED"]),
+    ("AppArmorAllowed", &["APPARMOR_ALLOWED"]),
+    ("AppArmorStatus", &["APPARMOR_STATUS"]),
+    // Smartd Events
+    ("PendingSectors", &["SMART_PENDING_SECTORS"]),
+    ("UncorrectableSectors", &["SMART_UNCORRECTABLE_SECTORS"]),
+    ("DiskTemperature", &["SMART_TEMPERATURE"]),
+    ("SelfTestFailed", &["SMART_SELF_TEST_FAILED"]),
+    ("DiskHealthFailed", &["SMART_HEALTH_FAILED"]),
+    ("SmartAttributeChanged", &["SMART_ATTRIBUTE_CHANGED"]),
+    // Access Log Events
+    ("HttpSuccess", &["HTTP_SUCCESS"]),
+    ("HttpRedirect", &["HTTP_REDIRECT"]),
+    ("HttpClientError", &["HTTP_CLIENT_ERROR"]),
+    ("HttpServerError", &["HTTP_SERVER_ERROR"]),
+    // Wifi Events
+    (
+        "WifiAuthFailure",
+        &[
+            "WIFI_WRONG_KEY",
+            "WIFI_HANDSHAKE_FAILED",
+            "WIFI_AUTH_TIMEOUT",
+            "WIFI_ASSOC_REJECT",
+        ],
+    ),
+    ("WifiDisconnected", &["WIFI_DISCONNECTED", "IWD_DEAUTH"]),
+    ("WifiConnected", &["WIFI_CONNECTED"]),
+    ("WifiAssociating", &["WIFI_ASSOCIATING"]),
+    // Power Events
+    ("LidSwitch", &["LID_CLOSED", "LID_OPENED"]),
+    ("PowerButton", &["POWER_BUTTON"]),
+    ("Suspend", &["SYSTEM_SUSPEND"]),
+    ("Resume", &["SYSTEM_RESUME"]),
+    ("Shutdown", &["SYSTEM_POWEROFF", "SYSTEM_REBOOT"]),
+    // Mail Events
+    ("MailReceived", &["MAIL_CLIENT"]),
+    ("MailAccepted", &["MAIL_QUEUED"]),
+    ("MailDelivered", &["MAIL_DELIVERED"]),
+    ("MailDeferred", &["MAIL_DEFERRED"]),
+    ("MailBounced", &["MAIL_BOUNCED"]),
+    ("MailRejected", &["MAIL_REJECT"]),
+    (
+        "MailAuthFailure",
+        &[
+            "MAIL_SASL_AUTH_FAILED",
+            "DOVECOT_LOGIN_FAILED",
+            "DOVECOT_AUTH_FAILED",
+        ],
+    ),
+    // Storage Events
+    (
+        "StorageDeviceFailed",
+        &[
+            "MD_DEVICE_FAILED",
+            "KERNEL_MD_DISK_FAILURE",
+            "LVM_RAID_DEVICE_FAILED",
+        ],
+    ),
+    (
+        "StorageArrayDegraded",
+        &[
+            "MD_ARRAY_DEGRADED",
+            "MD_ARRAY_DISAPPEARED",
+            "KERNEL_MD_DEGRADED",
+        ],
+    ),
+    (
+        "StorageRebuild",
+        &[
+            "MD_REBUILD_STARTED",
+            "MD_REBUILD_PROGRESS",
+            "MD_REBUILD_FINISHED",
+            "MD_SPARE_ACTIVE",
+            "KERNEL_MD_RECOVERY_STARTED",
+            "KERNEL_MD_RECOVERY_DONE",
+        ],
+    ),
+    (
+        "StorageThinPool",
+        &[
+            "LVM_THIN_POOL_FILLING",
+            "LVM_THIN_POOL_LOW_WATER",
+            "LVM_THIN_POOL_EXHAUSTED",
+        ],
+    ),
+    // Firewall Packet Log Events
+    ("PacketDropped", &["FW_PACKET_DROPPED"]),
+    ("PacketAccepted", &["FW_PACKET_ACCEPTED"]),
+    ("PacketLogged", &["FW_PACKET_LOGGED"]),
+    // Display Events
+    ("GpuHang", &["GPU_HANG", "GPU_RING_TIMEOUT", "NVIDIA_XID"]),
+    ("GpuReset", &["GPU_RESET"]),
+    ("XorgCrash", &["XORG_SEGFAULT", "XORG_FATAL"]),
+    ("XorgError", &["XORG_ERROR"]),
+    ("CompositorCrash", &["COMPOSITOR_CRASH", "XWAYLAND_LOST"]),
+    // Coredump Events
+    ("CoreDumped", &["COREDUMP"]),
+    ("CrashedWithoutCore", &["COREDUMP_NO_CORE"]),
+    // Resolved Events
+    ("DnssecFailure", &["RESOLVED_DNSSEC_FAILED"]),
+    ("DnsServerSwitched", &["RESOLVED_SERVER_SWITCHED"]),
+    ("DnsFeatureDegraded", &["RESOLVED_FEATURE_DEGRADED"]),
+    ("DnsFeatureRestored", &["RESOLVED_FEATURE_RESTORED"]),
+    ("DnsQueryTimeout", &["RESOLVED_QUERY_TIMEOUT"]),
+    // Protocol Mismatch Events
+    ("InvalidProtocolId", &["INVALID_PROTOCOL_ID"]),
+    ("BadProtocolVersion", &["BAD_PROTOCOL_VERSION"]),
+    ("MajorVersionDiff", &["MAJOR_VERSION_DIFF"]),
+    ("BannerOrDispatchError", &["BANNER_OR_DISPATCH_ERROR"]),
+    ("SocketReadFailure", &["SOCKET_READ_FAILURE"]),
+];
+
+pub fn str_to_regex_names(ev: &str) -> &'static [&'static str] {
+    EVENT_TYPE_FILTERS
+        .iter()
+        .find(|(name, _)| *name == ev)
+        .map(|(_, names)| *names)
+        .unwrap_or(&[])
 }
@@ -1,75 +1,410 @@
-use crate::events::{drain_older_logs, drain_previous_logs, drain_upto_n_entries, receive_data};
-use crate::parser::EventData;
+use crate::alert::{self, AlertRule, Webhook};
+use crate::config::config;
+use crate::events::{
+    LiveState, StreamLimit, drain_older_logs, drain_previous_logs, drain_upto_n_entries,
+    get_bundle, get_context, get_facets, get_histogram, get_history, get_rule_stats, parse_message,
+    receive_data, replay_events, search_events,
+};
+use crate::follow::{exportable_services, spawn_followers};
+use crate::metrics::{self, render_metrics};
+use crate::otlp::{self, OtlpEndpoint};
+use crate::parser::{
+    EventData, MANUAL_PARSE_EVENTS, ServiceInfo, check_sources, detect_package_manager,
+    list_services, manual_log_path,
+};
+use crate::schema::event_schema;
+use crate::shutdown;
+use crate::store;
+use crate::syslog::{self, SyslogTarget};
 use axum::Json;
 use axum::extract::State;
-use axum::{Router, routing::get};
+use axum::http::{HeaderValue, StatusCode, Uri, header};
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use axum_server::Handle;
+use axum_server::tls_rustls::RustlsConfig;
+use log::{error, info, warn};
 use serde::Serialize;
-use tower_http::cors::{Any, CorsLayer};
+use serde_json::json;
+use std::path::Path;
+use std::time::Duration;
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tower_http::services::ServeDir;
 
+#[derive(Serialize)]
+struct ManualSource {
+    name: &'static str,
+    path: &'static Path,
+}
+
 #[derive(Serialize)]
 struct ConfigResponse {
     port: u16,
+    version: &'static str,
+    manual_sources: Vec<ManualSource>,
+    persist: bool,
+    metrics: bool,
+    resolve_ptr: bool,
+    max_limit: i32,
+    max_connections: usize,
+    package_manager: Option<&'static str>,
+    tls: bool,
 }
 
 async fn get_config(State(port): State<u16>) -> Json<ConfigResponse> {
-    Json(ConfigResponse { port })
+    let config = config();
+    let manual_sources = MANUAL_PARSE_EVENTS
+        .iter()
+        .filter_map(|&name| manual_log_path(name).map(|path| ManualSource { name, path }))
+        .collect();
+
+    Json(ConfigResponse {
+        port,
+        version: env!("CARGO_PKG_VERSION"),
+        manual_sources,
+        persist: config.persist.is_some(),
+        metrics: config.metrics,
+        resolve_ptr: config.resolve_ptr,
+        max_limit: config.max_limit,
+        max_connections: config.max_connections,
+        package_manager: detect_package_manager(),
+        tls: config.tls_cert.is_some(),
+    })
+}
+
+async fn get_healthz() -> (StatusCode, Json<serde_json::Value>) {
+    let sources = tokio::task::spawn_blocking(check_sources)
+        .await
+        .unwrap_or_default();
+    let healthy = !sources.is_empty() && sources.iter().all(|s| s.reachable);
+
+    let status = if healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let body = json!({
+        "status": if healthy { "ok" } else { "unavailable" },
+        "sources": sources,
+    });
+    (status, Json(body))
 }
 
-const CYAN: &str = "\x1b[36m";
-const RESET: &str = "\x1b[0m";
+async fn get_services() -> Json<Vec<ServiceInfo>> {
+    Json(list_services())
+}
+
+async fn get_schema() -> Json<serde_json::Value> {
+    Json(event_schema())
+}
+
+async fn get_metrics() -> ([(header::HeaderName, &'static str); 1], String) {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render_metrics(),
+    )
+}
+
+// An origin is exactly scheme://host[:port], which is what browsers send and what
+// CorsLayer compares against byte for byte
+pub fn parse_cors_origin(origin: &str) -> anyhow::Result<HeaderValue> {
+    let uri: Uri = origin
+        .parse()
+        .map_err(|e| anyhow::anyhow!("`{origin}` is not a valid origin: {e}"))?;
+    let valid = matches!(uri.scheme_str(), Some("http" | "https"))
+        && uri.authority().is_some_and(|a| !a.host().is_empty())
+        && uri.path_and_query().is_none_or(|p| p.as_str() == "/")
+        && !origin.ends_with('/');
+    if !valid {
+        anyhow::bail!("`{origin}` is not a valid origin, expected scheme://host[:port]");
+    }
+    Ok(HeaderValue::from_str(origin)?)
+}
+
+async fn load_tls(cert: &Path, key: &Path) -> anyhow::Result<RustlsConfig> {
+    // Only the ring provider is compiled in, so it has to be picked explicitly
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let read = |path: &Path| {
+        std::fs::read(path).map_err(|e| anyhow::anyhow!("Can't read {}: {e}", path.display()))
+    };
+    let (cert_pem, key_pem) = (read(cert)?, read(key)?);
+    // rustls reports a file without PEM blocks as "peer sent no certificates"
+    if !String::from_utf8_lossy(&cert_pem).contains("-----BEGIN CERTIFICATE-----") {
+        anyhow::bail!("No PEM certificate found in {}", cert.display());
+    }
+    if !String::from_utf8_lossy(&key_pem).contains("PRIVATE KEY-----") {
+        anyhow::bail!("No PEM private key found in {}", key.display());
+    }
+    RustlsConfig::from_pem(cert_pem, key_pem)
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Invalid TLS certificate {} or key {}: {e}",
+                cert.display(),
+                key.display()
+            )
+        })
+}
 
 pub async fn render_app(tx: tokio::sync::broadcast::Sender<EventData>, port: u16) {
     let addr = format!("0.0.0.0:{port}");
 
+    // Loaded before anything else starts, so a bad certificate stops the server right away
+    let tls = match (&config().tls_cert, &config().tls_key) {
+        (Some(cert), Some(key)) => Some(load_tls(cert, key).await.unwrap_or_else(|e| {
+            error!("{e}");
+            std::process::exit(1);
+        })),
+        _ => None,
+    };
+
+    let otlp_endpoint = config().otlp_endpoint.as_ref().map(|url| {
+        OtlpEndpoint::parse(url).unwrap_or_else(|e| {
+            error!("Invalid --otlp-endpoint {url}: {e}");
+            std::process::exit(1);
+        })
+    });
+    let syslog_target = config().syslog_forward.as_ref().map(|addr| {
+        SyslogTarget::parse(addr).unwrap_or_else(|e| {
+            error!("Invalid --syslog-forward {addr}: {e}");
+            std::process::exit(1);
+        })
+    });
+
+    let alerting = match (&config().alert_webhook, config().alert_rules.is_empty()) {
+        (Some(url), false) => {
+            let webhook = Webhook::parse(url).unwrap_or_else(|e| {
+                error!("Invalid --alert-webhook {url}: {e}");
+                std::process::exit(1);
+            });
+            let rules = config()
+                .alert_rules
+                .iter()
+                .map(|spec| AlertRule::parse(spec).map_err(|e| anyhow::anyhow!("{spec}: {e}")))
+                .collect::<anyhow::Result<Vec<_>>>()
+                .unwrap_or_else(|e| {
+                    error!("Invalid --alert-rule {e}");
+                    std::process::exit(1);
+                });
+            Some((url, webhook, rules))
+        }
+        (Some(_), true) => {
+            error!("--alert-webhook needs at least one --alert-rule");
+            std::process::exit(1);
+        }
+        (None, false) => {
+            error!("--alert-rule needs an --alert-webhook to send to");
+            std::process::exit(1);
+        }
+        (None, true) => None,
+    };
+
+    // The store, the exporters and the alerter share one set of followers, so each
+    // service is read once. Alerts alone only need the services their rules watch
+    let persisting = store::is_enabled();
+    let exporting = otlp_endpoint.is_some() || syslog_target.is_some();
+    let services: Vec<&'static str> = exportable_services()
+        .into_iter()
+        .filter(|name| {
+            persisting
+                || exporting
+                || alerting
+                    .as_ref()
+                    .is_some_and(|(_, _, rules)| rules.iter().any(|rule| rule.watches(name)))
+        })
+        .collect();
+    if !services.is_empty() {
+        let followed = spawn_followers(&services, persisting);
+        if let Some(endpoint) = otlp_endpoint {
+            otlp::spawn_exporter(endpoint, followed.subscribe());
+        }
+        if let Some(target) = syslog_target {
+            syslog::spawn_forwarder(target, followed.subscribe());
+        }
+        if let Some((url, webhook, rules)) = alerting {
+            let cooldown = Duration::from_secs(config().alert_cooldown_secs);
+            if let Err(e) = alert::spawn_alerter(webhook, rules, cooldown, followed.subscribe()) {
+                error!("Can't send alerts to {url}: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let origins = &config().cors_origins;
+    let allow_origin = if origins.is_empty() {
+        AllowOrigin::from(Any)
+    } else {
+        AllowOrigin::list(origins.iter().cloned())
+    };
     let cors = CorsLayer::new()
-        .allow_origin(Any)
+        .allow_origin(allow_origin)
         .allow_methods(Any)
         .allow_headers(Any);
 
+    // SSE routes are left uncompressed, gzip buffers the stream
+    let compression = CompressionLayer::new();
+
+    let streams = StreamLimit::new(config().max_connections);
+
     let live_app = Router::new()
         .route("/live", get(receive_data))
         .layer(cors.clone())
-        .with_state(tx.clone());
+        .with_state(LiveState {
+            tx: tx.clone(),
+            streams: streams.clone(),
+        });
 
     let drain_app = Router::new()
         .route("/drain", get(drain_upto_n_entries))
-        .layer(cors.clone());
+        .layer(cors.clone())
+        .with_state(streams.clone());
 
     let drain_older_logs_app = Router::new()
         .route("/older", get(drain_older_logs))
-        .layer(cors.clone());
+        .layer(cors.clone())
+        .with_state(streams.clone());
 
     let drain_previous_logs_app = Router::new()
         .route("/previous", get(drain_previous_logs))
+        .layer(cors.clone())
+        .with_state(streams.clone());
+
+    let history_app = Router::new()
+        .route("/history", get(get_history))
+        .layer(cors.clone())
+        .layer(compression.clone());
+
+    let context_app = Router::new()
+        .route("/context", get(get_context))
+        .layer(cors.clone())
+        .layer(compression.clone());
+
+    let replay_app = Router::new()
+        .route("/replay", get(replay_events))
+        .layer(cors.clone())
+        .with_state(streams.clone());
+
+    let search_app = Router::new()
+        .route("/search", get(search_events))
+        .layer(cors.clone())
+        .layer(compression.clone());
+
+    let histogram_app = Router::new()
+        .route("/histogram", get(get_histogram))
+        .layer(cors.clone())
+        .layer(compression.clone());
+
+    let facets_app = Router::new()
+        .route("/facets", get(get_facets))
+        .layer(cors.clone())
+        .layer(compression.clone());
+
+    let rule_stats_app = Router::new()
+        .route("/rule-stats", get(get_rule_stats))
+        .layer(cors.clone())
+        .layer(compression.clone());
+
+    let bundle_app = Router::new()
+        .route("/bundle", get(get_bundle))
+        .layer(cors.clone())
+        .layer(compression.clone());
+
+    let services_app = Router::new()
+        .route("/services", get(get_services))
+        .layer(cors.clone())
+        .layer(compression.clone());
+
+    let schema_app = Router::new()
+        .route("/schema", get(get_schema))
+        .layer(cors.clone())
+        .layer(compression.clone());
+
+    let healthz_app = Router::new()
+        .route("/healthz", get(get_healthz))
         .layer(cors.clone());
 
+    let parse_app = Router::new()
+        .route("/parse", post(parse_message))
+        .layer(cors.clone())
+        .layer(compression.clone());
+
     let config = Router::new()
         .route("/config.json", get(get_config))
+        .layer(compression.clone())
         .with_state(port);
 
-    let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("static");
-    let frontend = Router::new().nest_service("/app/", ServeDir::new(path));
+    let path = crate::config::config().static_dir();
+    if path.is_dir() {
+        info!("Serving the UI from {}", path.display());
+    } else {
+        warn!(
+            "No UI found at {}, /app/ will return 404 (see --static-dir)",
+            path.display()
+        );
+    }
+    let frontend = Router::new()
+        .nest_service("/app/", ServeDir::new(path))
+        .layer(compression.clone());
 
-    let app = Router::new()
+    let mut app = Router::new()
         .merge(config)
         .merge(frontend)
         .merge(live_app)
         .merge(drain_app)
         .merge(drain_older_logs_app)
-        .merge(drain_previous_logs_app);
+        .merge(drain_previous_logs_app)
+        .merge(history_app)
+        .merge(context_app)
+        .merge(replay_app)
+        .merge(search_app)
+        .merge(histogram_app)
+        .merge(facets_app)
+        .merge(rule_stats_app)
+        .merge(bundle_app)
+        .merge(services_app)
+        .merge(schema_app)
+        .merge(parse_app)
+        .merge(healthz_app);
 
-    let listener = tokio::net::TcpListener::bind(&addr)
-        .await
-        .expect(&format!("Failed to start the listener"));
-
-    println!("{CYAN}[INFO] {RESET}Started Listening at - {}", &addr);
-    println!(
-        "{CYAN}[INFO] {RESET}UI is started at - {}",
-        format!("http://{}/app/", &addr)
-    );
-    axum::serve(listener, app.into_make_service())
+    if metrics::is_enabled() {
+        let metrics_app = Router::new()
+            .route("/metrics", get(get_metrics))
+            .layer(compression.clone());
+        app = app.merge(metrics_app);
+    }
+
+    let Some(tls) = tls else {
+        let listener = tokio::net::TcpListener::bind(&addr)
+            .await
+            .expect("Failed to start the listener");
+
+        info!("Started Listening at - {}", &addr);
+        info!("UI is started at - http://{addr}/app/");
+        axum::serve(listener, app.into_make_service())
+            .with_graceful_shutdown(shutdown::signal_received())
+            .await
+            .inspect_err(|e| error!("{e}"))
+            .unwrap();
+        return;
+    };
+
+    let listener = std::net::TcpListener::bind(&addr).expect("Failed to start the listener");
+    let handle = Handle::new();
+    let shutdown_handle = handle.clone();
+    tokio::spawn(async move {
+        shutdown::signal_received().await;
+        shutdown_handle.graceful_shutdown(None);
+    });
+
+    info!("Started Listening at - {} (TLS)", &addr);
+    info!("UI is started at - https://{addr}/app/");
+    axum_server::from_tcp_rustls(listener, tls)
+        .handle(handle)
+        .serve(app.into_make_service())
         .await
-        .inspect_err(|e| eprintln!("{e}"))
+        .inspect_err(|e| error!("{e}"))
         .unwrap();
 }
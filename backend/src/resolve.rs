@@ -0,0 +1,77 @@
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use ahash::AHashMap;
+use hickory_resolver::TokioResolver;
+use log::warn;
+use once_cell::sync::{Lazy, OnceCell};
+use tokio::sync::Semaphore;
+
+use crate::config::config;
+use crate::parser::EventData;
+
+const MAX_CACHED_PTRS: usize = 4096;
+const MAX_CONCURRENT_LOOKUPS: usize = 8;
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+static RESOLVER: OnceCell<Option<TokioResolver>> = OnceCell::new();
+static PTR_CACHE: Lazy<Mutex<AHashMap<IpAddr, Option<String>>>> =
+    Lazy::new(|| Mutex::new(AHashMap::new()));
+static LOOKUP_PERMITS: Semaphore = Semaphore::const_new(MAX_CONCURRENT_LOOKUPS);
+
+pub fn is_enabled() -> bool {
+    config().resolve_ptr
+}
+
+fn resolver() -> Option<&'static TokioResolver> {
+    RESOLVER
+        .get_or_init(|| match TokioResolver::builder_tokio() {
+            Ok(builder) => Some(builder.build()),
+            Err(e) => {
+                warn!("Reverse DNS disabled, failed to read the system resolver config: {e}");
+                None
+            }
+        })
+        .as_ref()
+}
+
+pub async fn resolve_ptr(ip: IpAddr) -> Option<String> {
+    if let Some(cached) = PTR_CACHE.lock().unwrap().get(&ip) {
+        return cached.clone();
+    }
+
+    let resolver = resolver()?;
+    let _permit = LOOKUP_PERMITS.acquire().await.ok()?;
+
+    let hostname = match tokio::time::timeout(LOOKUP_TIMEOUT, resolver.reverse_lookup(ip)).await {
+        Ok(Ok(lookup)) => lookup
+            .iter()
+            .next()
+            .map(|ptr| ptr.to_string().trim_end_matches('.').to_string()),
+        _ => None,
+    };
+
+    let mut cache = PTR_CACHE.lock().unwrap();
+    if cache.len() >= MAX_CACHED_PTRS {
+        cache.clear();
+    }
+    cache.insert(ip, hostname.clone());
+
+    hostname
+}
+
+pub async fn annotate_rhostname(mut ev: EventData) -> EventData {
+    if !is_enabled() || ev.data.contains_key("rhostname") {
+        return ev;
+    }
+
+    let Some(ip) = ev.data.get("ip").and_then(|ip| ip.parse::<IpAddr>().ok()) else {
+        return ev;
+    };
+
+    if let Some(hostname) = resolve_ptr(ip).await {
+        ev.data.insert("rhostname".to_string(), hostname);
+    }
+    ev
+}
@@ -0,0 +1,593 @@
+use std::collections::BTreeSet;
+
+use serde::Deserialize;
+use serde::de::{self, Deserializer, Visitor};
+use serde_json::{Value, json};
+
+use crate::parser::*;
+
+// Keys each parser can put in `data`. Parsers assign them per rule, so this is kept by
+// hand: add a key here when a parser starts emitting it
+const DATA_FIELDS: [(&str, &[&str]); 26] = [
+    (
+        "accesslog.events",
+        &[
+            "ip",
+            "user",
+            "request",
+            "method",
+            "path",
+            "protocol",
+            "status",
+            "bytes",
+            "referer",
+            "user_agent",
+        ],
+    ),
+    (
+        "apparmor.events",
+        &[
+            "apparmor",
+            "operation",
+            "profile",
+            "path",
+            "requested_mask",
+            "denied_mask",
+            "comm",
+            "pid",
+            "fsuid",
+            "info",
+            "class",
+        ],
+    ),
+    (
+        "bluetooth.events",
+        &[
+            "mac", "name", "reason", "error", "status", "profile", "request", "msg",
+        ],
+    ),
+    (
+        "configchange.events",
+        &["user", "cron_cmd", "cron_reload", "uid"],
+    ),
+    (
+        "coredump.events",
+        &[
+            "exe",
+            "comm",
+            "pid",
+            "uid",
+            "gid",
+            "signal",
+            "signal_number",
+            "unit",
+            "msg",
+        ],
+    ),
+    (
+        "display.events",
+        &[
+            "driver",
+            "device",
+            "process",
+            "pid",
+            "ring",
+            "reason",
+            "state",
+            "xid",
+            "msg",
+            "address",
+            "signal",
+            "signal_name",
+            "compositor",
+        ],
+    ),
+    (
+        "docker.events",
+        &[
+            "container_id",
+            "timeout_secs",
+            "signal",
+            "image",
+            "image_ref",
+            "level",
+            "msg",
+        ],
+    ),
+    (
+        "fail2ban.events",
+        &["level", "jail", "ip", "found_at", "msg"],
+    ),
+    (
+        "firewalld.events",
+        &[
+            "zone",
+            "interface",
+            "service",
+            "port",
+            "protocol",
+            "rule",
+            "msg",
+            "module",
+            "details",
+        ],
+    ),
+    (
+        "flatpak.events",
+        &[
+            "installation",
+            "ref",
+            "kind",
+            "pkg_name",
+            "arch",
+            "branch",
+            "remote",
+            "msg",
+            "source",
+        ],
+    ),
+    (
+        "fwlog.events",
+        &[
+            "prefix",
+            "msg",
+            "in_iface",
+            "out_iface",
+            "src_ip",
+            "dst_ip",
+            "proto",
+            "src_port",
+            "dst_port",
+            "length",
+            "ttl",
+        ],
+    ),
+    (
+        "kernel.events",
+        &[
+            "msg",
+            "cpu",
+            "pid",
+            "process",
+            "score",
+            "total_vm_kb",
+            "anon_rss_kb",
+            "file_rss_kb",
+            "shmem_rss_kb",
+            "uid",
+            "pgtables_kb",
+            "oom_score_adj",
+            "address",
+            "ip",
+            "sp",
+            "error",
+            "binary",
+            "device",
+            "error_code",
+            "event",
+            "details",
+            "vendor_id",
+            "product_id",
+            "sector",
+            "operation",
+            "action",
+            "location",
+            "driver",
+            "interface",
+            "old_name",
+            "speed",
+            "proto",
+            "port",
+            "dst_ip",
+            "reason",
+            "table",
+            "stall_ms",
+            "order",
+            "gfp_mode",
+            "gfp_flags",
+            "resource",
+            "page",
+            "size",
+            "stall_pct",
+            "limit_pct",
+            "duration",
+            "killed",
+            "swap_used",
+            "swap_total",
+            "zone",
+            "temperature",
+            "sensor",
+            "throttle_events",
+            "throttled",
+            "filesystem",
+            "state",
+            "type",
+            "module",
+            "firmware",
+            "irq",
+            "cpus",
+            "version",
+        ],
+    ),
+    (
+        "login.events",
+        &[
+            "user",
+            "tty",
+            "session_type",
+            "command",
+            "display_manager",
+            "failures",
+            "locked",
+            "msg",
+        ],
+    ),
+    (
+        "mail.events",
+        &[
+            "client",
+            "ip",
+            "mechanism",
+            "reason",
+            "queue_id",
+            "stage",
+            "smtp_code",
+            "dsn",
+            "sender",
+            "recipient",
+            "orig_recipient",
+            "relay",
+            "status",
+            "size",
+            "nrcpt",
+            "user",
+            "protocol",
+            "attempts",
+            "backend",
+            "msg",
+        ],
+    ),
+    (
+        "networkmanager.events",
+        &[
+            "level",
+            "ts",
+            "conn_old",
+            "device",
+            "conn_new",
+            "reason_old",
+            "reason_new",
+            "result",
+            "details",
+            "from",
+            "to",
+            "reason",
+            "sys_state",
+            "mgmt_type",
+            "state",
+            "version",
+            "action",
+            "iface",
+            "option",
+            "value",
+            "msg",
+            "client",
+            "connection",
+            "purpose",
+            "operation",
+            "errno",
+            "error",
+            "uuid",
+            "carrier",
+            "component",
+            "vpn_type",
+            "plugin_state",
+        ],
+    ),
+    (
+        "pkgmanager.events",
+        &["pkg_name", "version_from", "version_to", "version"],
+    ),
+    (
+        "power.events",
+        &["button", "press", "state", "operation", "msg"],
+    ),
+    (
+        "smartd.events",
+        &[
+            "device",
+            "device_type",
+            "value",
+            "attribute",
+            "limit",
+            "attribute_id",
+            "from",
+            "msg",
+        ],
+    ),
+    (
+        "resolved.events",
+        &[
+            "domain",
+            "record_type",
+            "result",
+            "server",
+            "server_name",
+            "server_type",
+            "interface",
+            "feature_level",
+            "previous_level",
+            "transaction",
+            "msg",
+        ],
+    ),
+    (
+        "sshd.events",
+        &[
+            "user",
+            "ip",
+            "port",
+            "method",
+            "key_type",
+            "key_fingerprint",
+            "msg",
+            "uid",
+            "throttling",
+            "duration",
+            "dropped",
+            "connection",
+            "local_ip",
+            "local_port",
+            "reason",
+            "preauth",
+        ],
+    ),
+    (
+        "storage.events",
+        &[
+            "event",
+            "array",
+            "device",
+            "percent",
+            "mismatches",
+            "level",
+            "active_devices",
+            "operation",
+            "device_index",
+            "pool",
+            "space",
+            "dm_device",
+            "mode",
+            "msg",
+        ],
+    ),
+    (
+        "sudo.events",
+        &[
+            "invoking_user",
+            "tty",
+            "pwd",
+            "target_user",
+            "target_group",
+            "command",
+            "binary",
+            "uid",
+            "invoking_uid",
+            "logname",
+            "euid",
+            "ruser",
+            "rhost",
+            "attempts",
+            "user",
+            "msg",
+        ],
+    ),
+    (
+        "systemd.events",
+        &[
+            "unit",
+            "description",
+            "result",
+            "code",
+            "status",
+            "msg",
+            "action",
+        ],
+    ),
+    (
+        "timesync.events",
+        &[
+            "server",
+            "port",
+            "server_name",
+            "offset_secs",
+            "reason",
+            "msg",
+        ],
+    ),
+    (
+        "userchange.events",
+        &[
+            "name",
+            "uid",
+            "gid",
+            "home",
+            "shell",
+            "pts",
+            "process_id",
+            "user",
+            "action",
+            "group",
+            "shadow",
+            "from",
+            "to",
+        ],
+    ),
+    (
+        "wifi.events",
+        &[
+            "interface",
+            "ssid",
+            "auth_failures",
+            "reason",
+            "bssid",
+            "status_code",
+            "reason_code",
+            "initiator",
+            "from_ap",
+            "msg",
+        ],
+    ),
+];
+
+// Added to any service's data: count by dedup=true, rhostname by --resolve-ptr
+const COMMON_DATA_FIELDS: [&str; 2] = ["count", "rhostname"];
+
+// Reads the variant names serde was generated with, so the schema can't fall behind
+// the enums. Deserializing stops at deserialize_enum, which is handed the full list
+struct EnumVariants<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for EnumVariants<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not an enum"))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = variants;
+        Err(de::Error::custom("variants read"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+        byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map
+        struct identifier ignored_any
+    }
+}
+
+fn variants<T: Deserialize<'static>>() -> &'static [&'static str] {
+    let mut variants: &'static [&'static str] = &[];
+    let _ = T::deserialize(EnumVariants(&mut variants));
+    variants
+}
+
+// The variants of each EventType category, in the order of EventType
+fn event_type_categories() -> [(&'static str, &'static [&'static str]); 22] {
+    [
+        ("Auth", variants::<AuthEvent>()),
+        ("User", variants::<UserEvent>()),
+        ("Package", variants::<PkgEvent>()),
+        ("Network", variants::<NetworkEvent>()),
+        ("Firewall", variants::<FirewallEvent>()),
+        ("Kernel", variants::<KernelEvent>()),
+        ("Config", variants::<ConfigEvent>()),
+        ("System", variants::<SystemEvent>()),
+        ("Systemd", variants::<SystemdEvent>()),
+        ("Fail2ban", variants::<Fail2banEvent>()),
+        ("Timesync", variants::<TimesyncEvent>()),
+        ("Container", variants::<ContainerEvent>()),
+        ("Bluetooth", variants::<BluetoothEvent>()),
+        ("AppArmor", variants::<AppArmorEvent>()),
+        ("DiskHealth", variants::<DiskHealthEvent>()),
+        ("Http", variants::<HttpEvent>()),
+        ("Power", variants::<PowerEvent>()),
+        ("Mail", variants::<MailEvent>()),
+        ("Storage", variants::<StorageEvent>()),
+        ("Display", variants::<DisplayEvent>()),
+        ("Coredump", variants::<CoredumpEvent>()),
+        ("Dns", variants::<DnsEvent>()),
+    ]
+}
+
+fn data_fields(service: &str) -> Vec<&'static str> {
+    let members: &[&str] = if service == "security.events" {
+        &SECURITY_SERVICES
+    } else {
+        &[service]
+    };
+    let fields: BTreeSet<&str> = DATA_FIELDS
+        .iter()
+        .filter(|(name, _)| members.contains(name))
+        .flat_map(|(_, fields)| fields.iter().copied())
+        .collect();
+    fields.into_iter().collect()
+}
+
+// JSON Schema for the events sent on the SSE streams and by /history and /parse, plus
+// the data keys and event_type filters of every enabled service under x-services
+pub fn event_schema() -> Value {
+    let event_types: Vec<Value> = event_type_categories()
+        .iter()
+        .map(|(category, variants)| {
+            json!({
+                "type": "object",
+                "properties": { *category: { "enum": variants } },
+                "required": [category],
+                "additionalProperties": false,
+            })
+        })
+        .collect();
+
+    let services: serde_json::Map<String, Value> = list_services()
+        .into_iter()
+        .map(|service| {
+            let info = json!({
+                "manual": service.manual,
+                "data_fields": data_fields(service.name),
+                "event_types": service.event_types,
+            });
+            (service.name.to_string(), info)
+        })
+        .collect();
+
+    let string_map = json!({ "type": "object", "additionalProperties": { "type": "string" } });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "EventData",
+        "type": "object",
+        "required": ["timestamp", "service", "event_type", "data"],
+        "properties": {
+            "timestamp": {
+                "description": "Syslog-style time, or RFC 3339 / Unix seconds with timestamp_format",
+                "type": ["string", "integer"],
+            },
+            "service": { "enum": variants::<Service>() },
+            "event_type": { "oneOf": event_types },
+            "data": {
+                "description": "Parsed fields, see x-services for the keys of each service",
+                "type": "object",
+                "additionalProperties": { "type": "string" },
+            },
+            "raw_msg": {
+                "description": "The original entry, left out with include_raw=none",
+                "oneOf": [
+                    {
+                        "type": "object",
+                        "properties": {
+                            "type": { "const": "Structured" },
+                            "value": string_map,
+                        },
+                        "required": ["type", "value"],
+                    },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "type": { "const": "Plain" },
+                            "value": { "type": "string" },
+                        },
+                        "required": ["type", "value"],
+                    },
+                ],
+            },
+            "matched_rule": { "type": "string" },
+        },
+        "x-common-data-fields": COMMON_DATA_FIELDS,
+        "x-services": services,
+    })
+}
@@ -0,0 +1,41 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use log::info;
+use once_cell::sync::Lazy;
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::sync::watch;
+
+// How long open SSE streams keep delivering after SIGINT/SIGTERM before they are closed
+const GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+static SHUTDOWN: Lazy<watch::Sender<bool>> = Lazy::new(|| watch::channel(false).0);
+
+// Set with SHUTDOWN, for the background readers that run on plain threads
+pub static STOPPING: AtomicBool = AtomicBool::new(false);
+
+pub async fn signal_received() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to install SIGTERM handler");
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+
+    info!(
+        "Shutting down, closing open streams in {}s",
+        GRACE_PERIOD.as_secs()
+    );
+    SHUTDOWN.send_replace(true);
+    STOPPING.store(true, Ordering::Relaxed);
+}
+
+// Resolves once long-lived streams should end, so the server's graceful shutdown
+// doesn't wait on them forever
+pub async fn streams_closing() {
+    let mut rx = SHUTDOWN.subscribe();
+    if rx.wait_for(|&shutting_down| shutting_down).await.is_err() {
+        std::future::pending::<()>().await;
+    }
+    tokio::time::sleep(GRACE_PERIOD).await;
+}
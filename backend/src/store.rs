@@ -0,0 +1,120 @@
+use std::path::Path;
+
+use anyhow::Result;
+use log::{info, warn};
+use once_cell::sync::OnceCell;
+
+use crate::config::config;
+use crate::parser::{EventData, RawMsgType, SECURITY_SERVICES, entry_datetime};
+
+pub static STORE: OnceCell<sled::Db> = OnceCell::new();
+
+pub fn open_store(path: &Path) -> Result<()> {
+    let db = sled::open(path)?;
+    info!("Persisting events to {}", path.display());
+    let _ = STORE.set(db);
+    Ok(())
+}
+
+pub fn flush_store() {
+    let Some(db) = STORE.get() else {
+        return;
+    };
+
+    match db.flush() {
+        Ok(bytes) => info!("Flushed {bytes} bytes to the event store"),
+        Err(e) => warn!("Failed to flush the event store: {e}"),
+    }
+}
+
+pub fn is_enabled() -> bool {
+    STORE.get().is_some()
+}
+
+fn now_micros() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or_default()
+}
+
+fn event_micros(ev: &EventData) -> u64 {
+    if let RawMsgType::Structured(entry) = &ev.raw_msg
+        && let Some(datetime) = entry_datetime(entry)
+    {
+        return datetime.timestamp_micros() as u64;
+    }
+    now_micros()
+}
+
+pub fn persist_event(service_name: &str, ev: &EventData) {
+    let Some(db) = STORE.get() else {
+        return;
+    };
+
+    if let Err(e) = insert_event(db, service_name, ev) {
+        warn!("Failed to persist event for {service_name}: {e}");
+    }
+}
+
+fn insert_event(db: &sled::Db, service_name: &str, ev: &EventData) -> Result<()> {
+    let tree = db.open_tree(service_name)?;
+    let mut key = Vec::with_capacity(16);
+    key.extend_from_slice(&event_micros(ev).to_be_bytes());
+    key.extend_from_slice(&db.generate_id()?.to_be_bytes());
+    tree.insert(key, serde_json::to_vec(ev)?)?;
+    Ok(())
+}
+
+pub fn read_history(
+    service_name: Option<&str>,
+    from: Option<u64>,
+    to: Option<u64>,
+    limit: usize,
+) -> Result<Vec<EventData>> {
+    let Some(db) = STORE.get() else {
+        anyhow::bail!("Persistence is not enabled, start with --persist <PATH>");
+    };
+
+    let start = from.unwrap_or(0).saturating_mul(1_000_000).to_be_bytes();
+    let end = to
+        .map(|t| t.saturating_add(1).saturating_mul(1_000_000))
+        .unwrap_or(u64::MAX)
+        .to_be_bytes();
+
+    // open_tree creates missing trees, so only names already in the store are opened.
+    // Events persisted before a service was disabled stay hidden while it is
+    let tree_names: Vec<String> = db
+        .tree_names()
+        .iter()
+        .filter(|name| name.as_ref() != b"__sled__default")
+        .map(|name| String::from_utf8_lossy(name).to_string())
+        .filter(|name| {
+            // security.events is stored under its member services
+            service_name.is_none_or(|wanted| {
+                wanted == name
+                    || (wanted == "security.events" && SECURITY_SERVICES.contains(&name.as_str()))
+            })
+        })
+        .filter(|name| config().service_enabled(name))
+        .collect();
+
+    let mut rows = Vec::new();
+    for name in tree_names {
+        let tree = db.open_tree(&name)?;
+        for item in tree.range(start..end).take(limit) {
+            let (key, value) = item?;
+            rows.push((key, value));
+        }
+    }
+
+    rows.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let events = rows
+        .into_iter()
+        .take(limit)
+        .filter_map(|(_, value)| serde_json::from_slice::<EventData>(&value).ok())
+        .collect();
+
+    Ok(events)
+}
@@ -0,0 +1,239 @@
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use chrono::SecondsFormat;
+use log::{info, warn};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::net::parse_host_port;
+use crate::parser::{EventData, EventType, RawMsgType, SEVERITIES, event_datetime};
+
+const DEFAULT_PORT: u16 = 514;
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+// Example enterprise number reserved by IANA for documentation (RFC 5612)
+const SD_ID: &str = "drashta@32473";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Transport {
+    Udp,
+    Tcp,
+}
+
+// Remote syslog collector, e.g. 10.0.0.5:514, udp://logs:514 or tcp://[::1]:6514
+#[derive(Debug, Clone)]
+pub struct SyslogTarget {
+    host: String,
+    port: u16,
+    transport: Transport,
+}
+
+impl SyslogTarget {
+    pub fn parse(addr: &str) -> Result<Self> {
+        let (transport, authority) = match addr.split_once("://") {
+            Some(("udp", rest)) => (Transport::Udp, rest),
+            Some(("tcp", rest)) => (Transport::Tcp, rest),
+            Some((scheme, _)) => bail!("unsupported scheme {scheme}, expected udp or tcp"),
+            None => (Transport::Udp, addr),
+        };
+
+        let (host, port) = parse_host_port(authority)?;
+
+        Ok(Self {
+            host,
+            port: port.unwrap_or(DEFAULT_PORT),
+            transport,
+        })
+    }
+}
+
+enum Connection {
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+}
+
+impl Connection {
+    async fn open(target: &SyslogTarget) -> Result<Self> {
+        let addr = (target.host.as_str(), target.port);
+        Ok(match target.transport {
+            Transport::Udp => {
+                let bind = if target.host.contains(':') {
+                    "[::]:0"
+                } else {
+                    "0.0.0.0:0"
+                };
+                let socket = UdpSocket::bind(bind).await?;
+                socket.connect(addr).await?;
+                Connection::Udp(socket)
+            }
+            Transport::Tcp => Connection::Tcp(TcpStream::connect(addr).await?),
+        })
+    }
+
+    // One datagram per message over UDP; octet-counted frames (RFC 6587) over TCP
+    async fn send(&mut self, msg: &str) -> Result<()> {
+        match self {
+            Connection::Udp(socket) => {
+                socket.send(msg.as_bytes()).await?;
+            }
+            Connection::Tcp(stream) => {
+                let frame = format!("{} {msg}", msg.len());
+                stream.write_all(frame.as_bytes()).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Forwards every event on the broadcast channel to the collector as an RFC 5424 message.
+// A message that can't be sent is dropped and the connection reopened for the next one,
+// so a dead collector can't back up the forwarder
+pub fn spawn_forwarder(target: SyslogTarget, mut rx: broadcast::Receiver<EventData>) {
+    info!(
+        "Forwarding events as syslog to {:?} {}:{}",
+        target.transport, target.host, target.port
+    );
+
+    tokio::spawn(async move {
+        let mut conn: Option<Connection> = None;
+        loop {
+            let ev = match rx.recv().await {
+                Ok(ev) => ev,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Syslog forwarder fell behind, {skipped} events were not forwarded");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            let msg = syslog_message(&ev);
+            let sent = tokio::time::timeout(SEND_TIMEOUT, async {
+                if conn.is_none() {
+                    conn = Some(Connection::open(&target).await?);
+                }
+                match conn.as_mut() {
+                    Some(c) => c.send(&msg).await,
+                    None => Ok(()),
+                }
+            })
+            .await;
+            let err = match sent {
+                Ok(Ok(())) => continue,
+                Ok(Err(e)) => e.to_string(),
+                Err(_) => "timed out".to_string(),
+            };
+            warn!("Failed to forward event over syslog: {err}");
+            conn = None;
+        }
+    });
+}
+
+// Auth and user changes go to authpriv, kernel events to kern, the rest to daemon
+fn facility(event_type: &EventType) -> u8 {
+    match event_type {
+        EventType::Auth(_) | EventType::User(_) => 10,
+        EventType::Kernel(_) => 0,
+        _ => 3,
+    }
+}
+
+// The entry's own PRIORITY when it had one, otherwise a guess from the event type
+fn severity(ev: &EventData) -> u8 {
+    if let Some(level) = ev
+        .data
+        .get("severity")
+        .and_then(|s| SEVERITIES.iter().position(|l| l == s))
+    {
+        return level as u8;
+    }
+
+    let (_, variant) = ev.event_type.kind();
+    match variant.as_str() {
+        "Panic" | "Emergency" => 0,
+        "Alert" => 1,
+        "Critical" | "OomKill" | "Segfault" | "FsReadOnly" => 2,
+        v if ["Error", "Failure", "Fail", "Crash", "Denied"]
+            .iter()
+            .any(|w| v.contains(w)) =>
+        {
+            3
+        }
+        v if v.contains("Warning") => 4,
+        "Notice" => 5,
+        _ => 6,
+    }
+}
+
+// SD-NAME is up to 32 printable ASCII characters other than '=', ' ', ']' and '"'
+fn sd_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_graphic() && !matches!(c, '=' | ']' | '"'))
+        .take(32)
+        .collect()
+}
+
+fn sd_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// Header fields are printable ASCII without spaces, or "-" when there is nothing to send
+fn header_field(value: Option<&str>, max_len: usize) -> String {
+    let field: String = value
+        .unwrap_or_default()
+        .chars()
+        .filter(char::is_ascii_graphic)
+        .take(max_len)
+        .collect();
+    if field.is_empty() {
+        "-".to_string()
+    } else {
+        field
+    }
+}
+
+fn syslog_message(ev: &EventData) -> String {
+    let (entry, msg) = match &ev.raw_msg {
+        RawMsgType::Structured(entry) => (
+            Some(entry),
+            entry.get("MESSAGE").cloned().unwrap_or_default(),
+        ),
+        RawMsgType::Plain(line) => (None, line.clone()),
+    };
+    let field = |name: &str| entry.and_then(|e| e.get(name)).map(String::as_str);
+
+    let pri = facility(&ev.event_type) * 8 + severity(ev);
+    let timestamp = event_datetime(ev)
+        .map(|dt| dt.to_rfc3339_opts(SecondsFormat::Micros, false))
+        .unwrap_or_else(|| "-".to_string());
+    let hostname = header_field(field("_HOSTNAME"), 255);
+    let procid = header_field(field("_PID"), 128);
+    let (category, variant) = ev.event_type.kind();
+    let msgid = header_field(Some(&format!("{category}:{variant}")), 32);
+
+    let mut sd = format!(
+        "[{SD_ID} service=\"{:?}\" event_type=\"{category}:{variant}\"",
+        ev.service
+    );
+    if let Some(rule) = &ev.matched_rule {
+        sd.push_str(&format!(" rule=\"{}\"", sd_value(rule)));
+    }
+    let mut fields: Vec<_> = ev.data.iter().collect();
+    fields.sort();
+    for (key, value) in fields {
+        let name = sd_name(key);
+        if !name.is_empty() {
+            sd.push_str(&format!(" {name}=\"{}\"", sd_value(value)));
+        }
+    }
+    sd.push(']');
+
+    format!("<{pri}>1 {timestamp} {hostname} drashta {procid} {msgid} {sd} {msg}")
+}
@@ -0,0 +1,108 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use drashta::parser::{
+    Entry, LogSource, ParserFuncArgs, ProcessLogType, get_service_configs,
+    process_entries_in_parallel,
+};
+
+// Counts every allocation in the process, rayon's workers included
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const ENTRIES: usize = 10_000;
+
+// An sshd entry with the 16 fields a journal entry typically carries
+fn sshd_entry(i: usize) -> Entry {
+    [
+        (
+            "MESSAGE",
+            format!("Failed password for user{i} from 10.0.0.1 port {i} ssh2"),
+        ),
+        ("PRIORITY", "6".to_string()),
+        ("SYSLOG_FACILITY", "4".to_string()),
+        ("SYSLOG_IDENTIFIER", "sshd".to_string()),
+        ("SYSLOG_PID", "1234".to_string()),
+        ("_PID", "1234".to_string()),
+        ("_UID", "0".to_string()),
+        ("_GID", "0".to_string()),
+        ("_COMM", "sshd".to_string()),
+        ("_EXE", "/usr/bin/sshd".to_string()),
+        ("_CMDLINE", "sshd: user [priv]".to_string()),
+        ("_SYSTEMD_UNIT", "sshd.service".to_string()),
+        ("_BOOT_ID", "0123456789abcdef0123456789abcdef".to_string()),
+        ("_HOSTNAME", "host".to_string()),
+        ("_SOURCE_REALTIME_TIMESTAMP", "1760608801000000".to_string()),
+        ("__REALTIME_TIMESTAMP", "1760608801000000".to_string()),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v))
+    .collect()
+}
+
+fn drain(data: VecDeque<Entry>) -> usize {
+    let (tx, _rx) = tokio::sync::mpsc::channel(ENTRIES);
+    let opts = ParserFuncArgs::try_new(
+        "sshd.events",
+        tx,
+        ENTRIES as i32,
+        ProcessLogType::ProcessInitialLogs,
+        None,
+        Vec::new(),
+        Vec::new(),
+        None,
+        None,
+        None,
+        LogSource::Journal,
+    )
+    .unwrap();
+    let config = &get_service_configs()["sshd.events"];
+    let len = data.len();
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let sent = process_entries_in_parallel(data, &opts, config, ENTRIES as i32).unwrap();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    assert_eq!(sent as usize, len);
+    allocations
+}
+
+#[test]
+fn drain_moves_entries_into_parsers() {
+    // Starts rayon's pool and the lazily built regexes and counters outside the measurement
+    drain((0..100).map(sshd_entry).collect());
+
+    let data: VecDeque<Entry> = (0..ENTRIES).map(sshd_entry).collect();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let copy = data.clone();
+    let clone_cost = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    drop(copy);
+
+    // A drain that cloned every entry before parsing it would allocate at least what
+    // cloning the batch does, on top of the parsing itself
+    let allocations = drain(data);
+    assert!(
+        allocations < clone_cost,
+        "{allocations} allocations for {ENTRIES} entries, cloning them alone takes {clone_cost}"
+    );
+}
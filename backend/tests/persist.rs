@@ -0,0 +1,91 @@
+use std::io::Write;
+use std::time::Duration;
+
+use drashta::config::{CONFIG, Config};
+use drashta::render::render_app;
+use drashta::store::{open_store, read_history};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const LINES: usize = 3;
+
+// Opens a /live stream and counts the data events it delivers until `want` arrive. /live
+// streams share one channel, so a client can also see the other reader's events
+async fn live_client(port: u16, want: usize) -> usize {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    stream
+        .write_all(b"GET /live?event_name=pkgmanager.events HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .await
+        .unwrap();
+
+    let mut received = String::new();
+    let mut buf = [0u8; 4096];
+    let read_all = async {
+        while received.matches("data: {").count() < want {
+            let n = stream.read(&mut buf).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            received.push_str(&String::from_utf8_lossy(&buf[..n]));
+        }
+    };
+    let _ = tokio::time::timeout(Duration::from_secs(10), read_all).await;
+    received.matches("data: {").count()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn live_subscribers_store_each_event_once() {
+    let dir = std::env::temp_dir().join(format!("drashta-persist-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let log = dir.join("pacman.log");
+    std::fs::File::create(&log).unwrap();
+
+    let store = dir.join("store");
+    open_store(&store).unwrap();
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    CONFIG
+        .set(Config {
+            port,
+            persist: Some(store),
+            pacman_log: log.clone(),
+            poll_interval_ms: 50,
+            enabled_services: vec!["pkgmanager.events".to_string()],
+            ..Config::default()
+        })
+        .unwrap();
+
+    let (tx, _) = tokio::sync::broadcast::channel(1024);
+    tokio::spawn(render_app(tx, port));
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let clients = [
+        tokio::spawn(live_client(port, LINES)),
+        tokio::spawn(live_client(port, LINES)),
+    ];
+    // Both /live readers have to be following before the lines are written
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let mut file = std::fs::OpenOptions::new().append(true).open(&log).unwrap();
+    for i in 0..LINES {
+        writeln!(
+            file,
+            "[2026-10-16T10:00:0{i}+0000] [ALPM] installed pkg{i} (1.0-1)"
+        )
+        .unwrap();
+    }
+    drop(file);
+
+    for client in clients {
+        assert!(client.await.unwrap() >= LINES);
+    }
+    // Give the store's own follower time to catch up, and any extra writes time to land
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    let stored = read_history(Some("pkgmanager.events"), None, None, 100).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(stored.len(), LINES);
+}
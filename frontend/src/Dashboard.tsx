@@ -18,6 +18,7 @@ const EVENT_TYPE_LISTS = {
     "Auth::AuthError",
     "Auth::AuthFailure",
     "Auth::NotInSudoers",
+    "Auth::CommandRun",
     "Auth::AccountExpired",
     "Auth::NologinRefused",
     "Auth::Warning",
@@ -85,6 +86,9 @@ const EVENT_TYPE_LISTS = {
     "Firewall::OperationStatus",
     "Firewall::ModuleMessage",
     "Firewall::DBusMessage",
+    "Firewall::PacketDropped",
+    "Firewall::PacketAccepted",
+    "Firewall::PacketLogged",
     "Firewall::Warning",
     "Firewall::Error",
     "Firewall::Info",
@@ -100,11 +104,14 @@ const EVENT_TYPE_LISTS = {
     "Kernel::DiskError",
     "Kernel::FsMount",
     "Kernel::FsError",
+    "Kernel::FsReadOnly",
     "Kernel::CpuError",
     "Kernel::MemoryError",
+    "Kernel::MemoryPressure",
     "Kernel::DeviceDetected",
     "Kernel::DriverEvent",
     "Kernel::NetInterface",
+    "Kernel::NetStack",
     "Kernel::PciDevice",
     "Kernel::AcpiEvent",
     "Kernel::ThermalEvent",
@@ -175,6 +182,7 @@ export type AuthEvent =
   | "AuthError"
   | "AuthFailure"
   | "NotInSudoers"
+  | "CommandRun"
   | "AccountExpired"
   | "NologinRefused"
   | "Warning"
@@ -252,6 +260,9 @@ export type FirewallEvent =
   | "OperationStatus"
   | "ModuleMessage"
   | "DBusMessage"
+  | "PacketDropped"
+  | "PacketAccepted"
+  | "PacketLogged"
   | "Warning"
   | "Error"
   | "Info"
@@ -267,11 +278,14 @@ export type KernelEvent =
   | "DiskError"
   | "FsMount"
   | "FsError"
+  | "FsReadOnly"
   | "CpuError"
   | "MemoryError"
+  | "MemoryPressure"
   | "DeviceDetected"
   | "DriverEvent"
   | "NetInterface"
+  | "NetStack"
   | "PciDevice"
   | "AcpiEvent"
   | "ThermalEvent"